@@ -0,0 +1,383 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Conversion charts from the paints in one series to those in another.
+
+use std::{io::Write, rc::Rc};
+
+use gcd::Gcd;
+
+use colour_math::{mixing::SubtractiveMixer, ColourAttributes, ColourBasics, HCV};
+
+use crate::{
+    delta_e::delta_e,
+    series::{SeriesId, SeriesPaint, SeriesPaintSeries},
+    svg::SvgWriter,
+    BasicPaintIfce,
+};
+
+/// A mixture of two target paints that approximates a source paint.
+#[derive(Debug)]
+pub struct TwoPaintMix {
+    pub components: [(Rc<SeriesPaint>, u64); 2],
+    pub colour: HCV,
+    pub delta_e: f64,
+}
+
+impl TwoPaintMix {
+    pub fn description(&self) -> String {
+        let [(first, first_parts), (second, second_parts)] = &self.components;
+        format!(
+            "{} x {} + {} x {}",
+            first_parts,
+            first.id(),
+            second_parts,
+            second.id()
+        )
+    }
+}
+
+/// The closest available approximation to a colour: a single paint or a two paint mix.
+#[derive(Debug)]
+pub enum ColourMatch {
+    Paint(Rc<SeriesPaint>, f64),
+    Mix(TwoPaintMix),
+}
+
+impl ColourMatch {
+    pub fn delta_e(&self) -> f64 {
+        match self {
+            Self::Paint(_, delta_e) => *delta_e,
+            Self::Mix(mix) => mix.delta_e,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Paint(paint, _) => paint.id().to_string(),
+            Self::Mix(mix) => mix.description(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ConversionEntry {
+    pub source: Rc<SeriesPaint>,
+    /// The closest target paints (with their distances) nearest first.
+    pub matches: Vec<(Rc<SeriesPaint>, f64)>,
+    /// The best two paint mix if no single target paint was close enough.
+    pub mix: Option<TwoPaintMix>,
+}
+
+#[derive(Debug)]
+pub struct ConversionChart {
+    source_series_id: Rc<SeriesId>,
+    target_series_id: Rc<SeriesId>,
+    entries: Vec<ConversionEntry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConversionChartBuilder {
+    max_matches: usize,
+    mix_threshold: f64,
+    mix_candidates: usize,
+    max_parts: u64,
+}
+
+impl Default for ConversionChartBuilder {
+    fn default() -> Self {
+        Self {
+            max_matches: 3,
+            mix_threshold: 5.0,
+            mix_candidates: 12,
+            max_parts: 4,
+        }
+    }
+}
+
+impl ConversionChartBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of single paint matches to list for each source paint.
+    pub fn max_matches(&mut self, max_matches: usize) -> &mut Self {
+        self.max_matches = max_matches.max(1);
+        self
+    }
+
+    /// Look for a two paint mix when the best single match is further away than this.
+    pub fn mix_threshold(&mut self, mix_threshold: f64) -> &mut Self {
+        self.mix_threshold = mix_threshold;
+        self
+    }
+
+    /// The number of nearest target paints considered as mix components.
+    pub fn mix_candidates(&mut self, mix_candidates: usize) -> &mut Self {
+        self.mix_candidates = mix_candidates;
+        self
+    }
+
+    /// The largest number of parts of either component tried in mixes.
+    pub fn max_parts(&mut self, max_parts: u64) -> &mut Self {
+        self.max_parts = max_parts.max(1);
+        self
+    }
+
+    fn best_mix(
+        &self,
+        source: &(impl ColourBasics + ColourAttributes),
+        nearest: &[(Rc<SeriesPaint>, f64)],
+    ) -> Option<TwoPaintMix> {
+        let candidates = &nearest[..nearest.len().min(self.mix_candidates)];
+        let mut best: Option<TwoPaintMix> = None;
+        for (i, (first, _)) in candidates.iter().enumerate() {
+            for (second, _) in candidates[i + 1..].iter() {
+                for first_parts in 1..=self.max_parts {
+                    for second_parts in 1..=self.max_parts {
+                        if first_parts.gcd(second_parts) != 1 {
+                            continue;
+                        }
+                        let mut mixer = SubtractiveMixer::new();
+                        mixer.add(&first.hcv(), first_parts);
+                        mixer.add(&second.hcv(), second_parts);
+                        if let Some(colour) = mixer.mixed_colour() {
+                            let distance = delta_e(source, &colour);
+                            if !matches!(&best, Some(mix) if mix.delta_e <= distance) {
+                                best = Some(TwoPaintMix {
+                                    components: [
+                                        (Rc::clone(first), first_parts),
+                                        (Rc::clone(second), second_parts),
+                                    ],
+                                    colour,
+                                    delta_e: distance,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// The paints nearest to `colour` (nearest first) along with the best two paint mix
+    /// if the nearest paint is further away than the mix threshold and the mix is closer.
+    pub fn match_colour<'a>(
+        &self,
+        colour: &(impl ColourBasics + ColourAttributes),
+        paints: impl IntoIterator<Item = &'a Rc<SeriesPaint>>,
+    ) -> (Vec<(Rc<SeriesPaint>, f64)>, Option<TwoPaintMix>) {
+        let mut nearest: Vec<(Rc<SeriesPaint>, f64)> = paints
+            .into_iter()
+            .map(|paint| (Rc::clone(paint), delta_e(colour, paint.as_ref())))
+            .collect();
+        nearest.sort_by(|a, b| a.1.partial_cmp(&b.1).expect("distances are not NaN"));
+        let mix = match nearest.first() {
+            Some((_, distance)) if *distance > self.mix_threshold => self
+                .best_mix(colour, &nearest)
+                .filter(|mix| mix.delta_e < *distance),
+            _ => None,
+        };
+        nearest.truncate(self.max_matches);
+        (nearest, mix)
+    }
+
+    pub fn build(&self, source: &SeriesPaintSeries, target: &SeriesPaintSeries) -> ConversionChart {
+        let mut entries = vec![];
+        for source_paint in source.paints() {
+            let (matches, mix) = self.match_colour(source_paint.as_ref(), target.paints());
+            entries.push(ConversionEntry {
+                source: Rc::clone(source_paint),
+                matches,
+                mix,
+            });
+        }
+        ConversionChart {
+            source_series_id: Rc::clone(source.series_id()),
+            target_series_id: Rc::clone(target.series_id()),
+            entries,
+        }
+    }
+}
+
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl ConversionChart {
+    pub fn source_series_id(&self) -> &SeriesId {
+        &self.source_series_id
+    }
+
+    pub fn target_series_id(&self) -> &SeriesId {
+        &self.target_series_id
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &ConversionEntry> {
+        self.entries.iter()
+    }
+
+    fn max_matches(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|entry| entry.matches.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> Result<(), crate::Error> {
+        let mut header = vec!["Source Id".to_string(), "Source Name".to_string()];
+        for i in 1..=self.max_matches() {
+            header.push(format!("Match {i} Id"));
+            header.push(format!("Match {i} Name"));
+            header.push(format!("Match {i} ΔE"));
+        }
+        header.push("Mix".to_string());
+        header.push("Mix ΔE".to_string());
+        writeln!(writer, "{}", header.join(","))?;
+        for entry in self.entries.iter() {
+            let mut fields = vec![
+                csv_field(entry.source.id()),
+                csv_field(entry.source.name().unwrap_or("")),
+            ];
+            for i in 0..self.max_matches() {
+                if let Some((paint, distance)) = entry.matches.get(i) {
+                    fields.push(csv_field(paint.id()));
+                    fields.push(csv_field(paint.name().unwrap_or("")));
+                    fields.push(format!("{distance:.2}"));
+                } else {
+                    fields.extend(["".to_string(), "".to_string(), "".to_string()]);
+                }
+            }
+            if let Some(mix) = &entry.mix {
+                fields.push(csv_field(&mix.description()));
+                fields.push(format!("{:.2}", mix.delta_e));
+            } else {
+                fields.extend(["".to_string(), "".to_string()]);
+            }
+            writeln!(writer, "{}", fields.join(","))?;
+        }
+        Ok(())
+    }
+
+    pub fn write_svg<W: Write>(&self, writer: &mut W) -> Result<(), crate::Error> {
+        const ROW_HEIGHT: f64 = 40.0;
+        const SWATCH: f64 = 30.0;
+        const LABEL_WIDTH: f64 = 150.0;
+        const CELL_WIDTH: f64 = SWATCH + LABEL_WIDTH;
+        let n_cells = self.max_matches() + 2;
+        let width = CELL_WIDTH * n_cells as f64 + 10.0;
+        let height = ROW_HEIGHT * (self.entries.len() + 1) as f64 + 10.0;
+        let mut svg = SvgWriter::new(writer, width, height)?;
+        svg.bold_text(
+            5.0,
+            20.0,
+            &format!("{} → {}", self.source_series_id, self.target_series_id),
+        )?;
+        for (row, entry) in self.entries.iter().enumerate() {
+            let y = ROW_HEIGHT * (row + 1) as f64 + 5.0;
+            let text_y = y + SWATCH / 2.0 + 4.0;
+            svg.swatch(5.0, y, SWATCH, SWATCH, entry.source.as_ref(), None)?;
+            svg.bold_text(SWATCH + 10.0, text_y, &entry.source.label_text())?;
+            let mut x = CELL_WIDTH + 5.0;
+            for (paint, distance) in entry.matches.iter() {
+                svg.swatch(x, y, SWATCH, SWATCH, paint.as_ref(), None)?;
+                svg.text(
+                    x + SWATCH + 5.0,
+                    text_y,
+                    &format!("{} (ΔE {:.1})", paint.id(), distance),
+                )?;
+                x += CELL_WIDTH;
+            }
+            if let Some(mix) = &entry.mix {
+                let x = CELL_WIDTH * (n_cells - 1) as f64 + 5.0;
+                svg.swatch(x, y, SWATCH, SWATCH, &mix.colour, Some(&mix.description()))?;
+                svg.text(
+                    x + SWATCH + 5.0,
+                    text_y,
+                    &format!("mix (ΔE {:.1})", mix.delta_e),
+                )?;
+            }
+        }
+        svg.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::series::{test_series_spec, BasicPaintSpec};
+    use colour_math::{HueConstants, RGBConstants};
+
+    fn series(name: &str, paints: &[(&str, HCV)]) -> SeriesPaintSeries {
+        let mut spec = test_series_spec(name);
+        for (id, colour) in paints.iter() {
+            spec.add(&BasicPaintSpec::new(colour, id));
+        }
+        SeriesPaintSeries::from(&spec)
+    }
+
+    #[test]
+    fn conversion_chart() {
+        let source = series(
+            "source",
+            &[
+                ("red", HCV::RED),
+                ("orange", {
+                    let mut mixer = SubtractiveMixer::new();
+                    mixer.add(&HCV::RED, 1);
+                    mixer.add(&HCV::YELLOW, 1);
+                    mixer.mixed_colour().unwrap()
+                }),
+            ],
+        );
+        let target = series(
+            "target",
+            &[
+                ("R", HCV::RED),
+                ("Y", HCV::YELLOW),
+                ("B", HCV::BLUE),
+                ("W", HCV::WHITE),
+            ],
+        );
+        let chart = ConversionChartBuilder::new()
+            .max_matches(2)
+            .build(&source, &target);
+        let red = chart.entries().find(|e| e.source.id() == "red").unwrap();
+        assert_eq!(red.matches.len(), 2);
+        assert_eq!(red.matches[0].0.id(), "R");
+        assert_eq!(red.matches[0].1, 0.0);
+        assert!(red.mix.is_none());
+        let orange = chart.entries().find(|e| e.source.id() == "orange").unwrap();
+        let mix = orange.mix.as_ref().unwrap();
+        assert!(mix.delta_e < 0.000_001);
+        assert_eq!(mix.description(), "1 x R + 1 x Y");
+
+        let mut csv = vec![];
+        chart.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv
+            .lines()
+            .next()
+            .unwrap()
+            .starts_with("Source Id,Source Name,Match 1 Id"));
+
+        let mut svg = vec![];
+        chart.write_svg(&mut svg).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn quote_csv_fields() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! A simple measure of the perceived difference between two colours.
+//!
+//! Colours are placed in a cylinder whose axis is value and whose polar coordinates are
+//! hue angle and chroma.  The straight line distance between two colours is then scaled
+//! so that black and white are 100 units apart which puts the numbers in the same
+//! ballpark as the more familiar CIE ΔE values.
+
+use std::ops::Deref;
+
+use colour_math::{ColourAttributes, ColourBasics, ScalarAttribute};
+
+/// The distance between black and white.
+pub const DELTA_E_MAX: f64 = 100.0;
+
+fn coordinates(colour: &(impl ColourBasics + ColourAttributes)) -> [f64; 3] {
+    let value = f64::from(colour.value());
+    if let Some(angle) = colour.hue_angle() {
+        let radians = f64::from(angle).to_radians();
+        let chroma = f64::from(colour.scalar_attribute(ScalarAttribute::Chroma));
+        [chroma * radians.cos(), chroma * radians.sin(), value]
+    } else {
+        [0.0, 0.0, value]
+    }
+}
+
+/// Return the difference between `a` and `b` in the range `0.0..=DELTA_E_MAX` (or a little
+/// over for very saturated complementary colours).
+pub fn delta_e(
+    a: &(impl ColourBasics + ColourAttributes),
+    b: &(impl ColourBasics + ColourAttributes),
+) -> f64 {
+    let a = coordinates(a);
+    let b = coordinates(b);
+    let sum_sq: f64 = a.iter().zip(b.iter()).map(|(a, b)| (a - b).powi(2)).sum();
+    sum_sq.sqrt() * DELTA_E_MAX
+}
+
+/// Return the item whose colour is nearest to `target` along with its distance.  Items are
+/// anything that dereferences to a colour, e.g. `&HCV` or `Rc<SeriesPaint>`.
+pub fn nearest<T, C>(
+    target: &(impl ColourBasics + ColourAttributes),
+    candidates: impl IntoIterator<Item = T>,
+) -> Option<(T, f64)>
+where
+    T: Deref<Target = C>,
+    C: ColourBasics + ColourAttributes,
+{
+    let mut best: Option<(T, f64)> = None;
+    for candidate in candidates {
+        let distance = delta_e(target, &*candidate);
+        match best {
+            Some((_, best_distance)) if best_distance <= distance => (),
+            _ => best = Some((candidate, distance)),
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use colour_math::{HueConstants, RGBConstants, HCV};
+
+    #[test]
+    fn delta_e_basics() {
+        assert_eq!(delta_e(&HCV::RED, &HCV::RED), 0.0);
+        assert!((delta_e(&HCV::BLACK, &HCV::WHITE) - DELTA_E_MAX).abs() < 0.000_001);
+        assert!(delta_e(&HCV::RED, &HCV::YELLOW) > 0.0);
+        let candidates = vec![HCV::CYAN, HCV::BLUE, HCV::YELLOW];
+        let (colour, _) = nearest(&HCV::RED, &candidates).unwrap();
+        assert_eq!(*colour, HCV::YELLOW);
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Claims that a paint matches a colour in a published standard (e.g. FS 595C or RAL).
+
+use std::fmt;
+
+use regex::Regex;
+
+use crate::series::SeriesPaintSeriesSpec;
+
+pub mod verify;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Equivalent {
+    pub standard: String,
+    pub code: String,
+}
+
+impl Equivalent {
+    pub fn new(standard: &str, code: &str) -> Self {
+        Self {
+            standard: standard.to_string(),
+            code: code.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Equivalent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.standard, self.code)
+    }
+}
+
+/// Extracts equivalents from free text (such as paint notes) using a list of regular
+/// expressions.  Each expression must have a group named `code`.
+#[derive(Debug, Clone)]
+pub struct EquivalentsImporter {
+    patterns: Vec<(String, Regex)>,
+}
+
+impl Default for EquivalentsImporter {
+    /// An importer that recognises the forms used in the model paint files, e.g.
+    /// "FS37925-RAL9016-RLM21", "FS30266-ANA615" and "BS216".
+    fn default() -> Self {
+        let mut importer = Self::new();
+        for (standard, pattern) in [
+            ("FS", r"\bFS\s?(?P<code>\d{5})\b"),
+            ("RAL", r"\bRAL\s?(?P<code>\d{3,4})\b"),
+            ("RLM", r"\bRLM\s?(?P<code>\d{1,3})\b"),
+            ("BS381C", r"\bBS\s?(?:381C\s?)?(?P<code>\d{3})\b"),
+            ("ANA", r"\bANA\s?(?P<code>\d{3})\b"),
+        ] {
+            importer
+                .add_pattern(standard, pattern)
+                .expect("programmer error");
+        }
+        importer
+    }
+}
+
+impl EquivalentsImporter {
+    /// An importer with no patterns.
+    pub fn new() -> Self {
+        Self { patterns: vec![] }
+    }
+
+    pub fn add_pattern(
+        &mut self,
+        standard: &str,
+        pattern: &str,
+    ) -> Result<&mut Self, crate::Error> {
+        let regex = Regex::new(pattern)?;
+        if !regex.capture_names().any(|name| name == Some("code")) {
+            return Err(crate::Error::NotAValidPattern(pattern.to_string()));
+        }
+        self.patterns.push((standard.to_string(), regex));
+        Ok(self)
+    }
+
+    /// Return the (sorted and deduplicated) equivalents claimed in `text`.
+    pub fn extract(&self, text: &str) -> Vec<Equivalent> {
+        let mut equivalents = vec![];
+        for (standard, regex) in self.patterns.iter() {
+            for captures in regex.captures_iter(text) {
+                equivalents.push(Equivalent::new(standard, &captures["code"]));
+            }
+        }
+        equivalents.sort();
+        equivalents.dedup();
+        equivalents
+    }
+
+    /// Add the equivalents found in each paint's notes to its list of equivalents and
+    /// return the number of paints that gained any.
+    pub fn import(&self, series_spec: &mut SeriesPaintSeriesSpec) -> usize {
+        let mut count = 0;
+        for paint_spec in series_spec.paint_list.iter_mut() {
+            let mut found = false;
+            for equivalent in self.extract(&paint_spec.notes) {
+                if let Err(index) = paint_spec.equivalents.binary_search(&equivalent) {
+                    paint_spec.equivalents.insert(index, equivalent);
+                    found = true;
+                }
+            }
+            if found {
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::series::{test_series_spec, BasicPaintSpec};
+    use colour_math::{HueConstants, HCV};
+
+    #[test]
+    fn extract_equivalents() {
+        let importer = EquivalentsImporter::default();
+        assert_eq!(
+            importer.extract("FS37925-RAL9016-RLM21"),
+            vec![
+                Equivalent::new("FS", "37925"),
+                Equivalent::new("RAL", "9016"),
+                Equivalent::new("RLM", "21"),
+            ]
+        );
+        assert_eq!(
+            importer.extract("FS30266-ANA615"),
+            vec![
+                Equivalent::new("ANA", "615"),
+                Equivalent::new("FS", "30266")
+            ]
+        );
+        assert_eq!(
+            importer.extract("BS216"),
+            vec![Equivalent::new("BS381C", "216")]
+        );
+        assert_eq!(
+            importer.extract("BS381C 101"),
+            vec![Equivalent::new("BS381C", "101")]
+        );
+        assert!(importer.extract("PBk 7, Lightfastness: 1").is_empty());
+    }
+
+    #[test]
+    fn import_equivalents() {
+        let mut series_spec = test_series_spec("series name");
+        let mut paint_spec = BasicPaintSpec::new(&HCV::RED, "70.957");
+        paint_spec.notes = "FS31433-RAL3012".to_string();
+        series_spec.add(&paint_spec);
+        series_spec.add(&BasicPaintSpec::new(&HCV::YELLOW, "70.953"));
+        let mut importer = EquivalentsImporter::new();
+        importer.add_pattern("FS", r"FS(?P<code>\d{5})").unwrap();
+        assert!(importer.add_pattern("RAL", r"RAL\d{4}").is_err());
+        assert_eq!(importer.import(&mut series_spec), 1);
+        assert_eq!(importer.import(&mut series_spec), 0);
+        let paint_spec = series_spec.find("70.957").unwrap();
+        assert_eq!(paint_spec.equivalents, vec![Equivalent::new("FS", "31433")]);
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Check paints' equivalence claims against the colours of the standards themselves.
+
+use std::{fmt, rc::Rc};
+
+use crate::{
+    delta_e::delta_e, equivalents::Equivalent, library::PaintLibrary, series::SeriesPaint,
+    BasicPaintIfce,
+};
+
+/// The default threshold above which an equivalence claim is considered suspect.
+pub const DEFAULT_DELTA_E_THRESHOLD: f64 = 5.0;
+
+/// The ids that the standard's own entry for `equivalent` may have (in order of
+/// preference).  For FS 595 the first digit only encodes the finish so, if the exact
+/// code isn't available, the same colour with a different finish will do.
+pub fn standard_paint_ids(equivalent: &Equivalent) -> Vec<String> {
+    let mut ids = vec![
+        format!("{}{}", equivalent.standard, equivalent.code),
+        format!("{} {}", equivalent.standard, equivalent.code),
+    ];
+    if equivalent.standard == "FS" && equivalent.code.len() == 5 {
+        for finish in ['1', '2', '3'] {
+            if !equivalent.code.starts_with(finish) {
+                ids.push(format!("FS{}{}", finish, &equivalent.code[1..]));
+            }
+        }
+    }
+    ids
+}
+
+#[derive(Debug)]
+pub struct EquivalenceCheck {
+    pub paint: Rc<SeriesPaint>,
+    pub equivalent: Equivalent,
+    /// The standard's entry and its distance from the paint (if the standard is loaded).
+    pub standard: Option<(Rc<SeriesPaint>, f64)>,
+}
+
+impl EquivalenceCheck {
+    pub fn is_verifiable(&self) -> bool {
+        self.standard.is_some()
+    }
+
+    pub fn exceeds(&self, threshold: f64) -> bool {
+        match self.standard {
+            Some((_, delta_e)) => delta_e > threshold,
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EquivalenceReport {
+    threshold: f64,
+    checks: Vec<EquivalenceCheck>,
+}
+
+impl EquivalenceReport {
+    /// Check every equivalence claimed by `paints` against the entries in `standards`.
+    pub fn new<'a>(
+        paints: impl IntoIterator<Item = &'a Rc<SeriesPaint>>,
+        standards: &PaintLibrary,
+        threshold: f64,
+    ) -> Self {
+        let mut checks = vec![];
+        for paint in paints {
+            for equivalent in paint.equivalents() {
+                let standard = standard_paint_ids(equivalent)
+                    .iter()
+                    .find_map(|id| standards.index().find(id).ok())
+                    .map(|standard| {
+                        let distance = delta_e(paint.as_ref(), standard.as_ref());
+                        (standard, distance)
+                    });
+                checks.push(EquivalenceCheck {
+                    paint: Rc::clone(paint),
+                    equivalent: equivalent.clone(),
+                    standard,
+                });
+            }
+        }
+        Self { threshold, checks }
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    pub fn checks(&self) -> impl Iterator<Item = &EquivalenceCheck> {
+        self.checks.iter()
+    }
+
+    /// The claims that are further from the standard than the threshold allows.
+    pub fn suspect(&self) -> impl Iterator<Item = &EquivalenceCheck> {
+        self.checks
+            .iter()
+            .filter(move |check| check.exceeds(self.threshold))
+    }
+
+    /// The claims for which no entry in the standards could be found.
+    pub fn unverifiable(&self) -> impl Iterator<Item = &EquivalenceCheck> {
+        self.checks.iter().filter(|check| !check.is_verifiable())
+    }
+}
+
+impl fmt::Display for EquivalenceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in self.checks.iter() {
+            write!(
+                f,
+                "{}:({}) claims {}: ",
+                check.paint.id(),
+                check.paint.series_id(),
+                check.equivalent
+            )?;
+            match &check.standard {
+                Some((standard, delta_e)) => {
+                    write!(f, "ΔE {:.1} from {}", delta_e, standard.id())?;
+                    if *delta_e > self.threshold {
+                        write!(f, " SUSPECT")?;
+                    }
+                    writeln!(f)?;
+                }
+                None => writeln!(f, "no standard entry")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::*;
+    use crate::{
+        equivalents::EquivalentsImporter,
+        library::read_paint_series_spec_file,
+        series::{test_series_spec, BasicPaintSpec, SeriesPaintSeries},
+    };
+    use colour_math::{HueConstants, RGBConstants, HCV};
+
+    #[test]
+    fn fs_ids() {
+        assert_eq!(
+            standard_paint_ids(&Equivalent::new("FS", "30266")),
+            vec!["FS30266", "FS 30266", "FS10266", "FS20266"]
+        );
+        assert_eq!(
+            standard_paint_ids(&Equivalent::new("BS381C", "216")),
+            vec!["BS381C216", "BS381C 216"]
+        );
+    }
+
+    #[test]
+    fn verify_known_claims() {
+        let mut standards_spec = test_series_spec("FS 595");
+        standards_spec.add(&BasicPaintSpec::new(&HCV::WHITE, "FS37875"));
+        let mut standards = PaintLibrary::new();
+        standards
+            .add_series(SeriesPaintSeries::from(&standards_spec), None)
+            .unwrap();
+        let mut series_spec = test_series_spec("series name");
+        for (colour, id, standard, code) in [
+            (HCV::WHITE, "white", "FS", "37875"),
+            (HCV::BLACK, "black", "FS", "17875"),
+            (HCV::RED, "red", "RAL", "3000"),
+        ] {
+            let mut paint_spec = BasicPaintSpec::new(&colour, id);
+            paint_spec.equivalents = vec![Equivalent::new(standard, code)];
+            series_spec.add(&paint_spec);
+        }
+        let series = SeriesPaintSeries::from(&series_spec);
+        let report = EquivalenceReport::new(series.paints(), &standards, DEFAULT_DELTA_E_THRESHOLD);
+        let claims: Vec<(&str, String)> = report
+            .checks()
+            .map(|check| (check.paint.id(), check.equivalent.to_string()))
+            .collect();
+        assert_eq!(
+            claims,
+            vec![
+                ("black", "FS 17875".to_string()),
+                ("red", "RAL 3000".to_string()),
+                ("white", "FS 37875".to_string()),
+            ]
+        );
+        // black's claim is checked against the same colour with a different finish
+        let distances: Vec<Option<(&str, f64)>> = report
+            .checks()
+            .map(|check| {
+                check
+                    .standard
+                    .as_ref()
+                    .map(|(standard, delta_e)| (standard.id(), (delta_e * 10.0).round() / 10.0))
+            })
+            .collect();
+        assert_eq!(
+            distances,
+            vec![Some(("FS37875", 100.0)), None, Some(("FS37875", 0.0))]
+        );
+        let suspects: Vec<&str> = report.suspect().map(|check| check.paint.id()).collect();
+        assert_eq!(suspects, vec!["black"]);
+        let unverifiable: Vec<&str> = report
+            .unverifiable()
+            .map(|check| check.paint.id())
+            .collect();
+        assert_eq!(unverifiable, vec!["red"]);
+        assert_eq!(
+            format!("{report}"),
+            "black:(series name:(owner)) claims FS 17875: ΔE 100.0 from FS37875 SUSPECT\n\
+             red:(series name:(owner)) claims RAL 3000: no standard entry\n\
+             white:(series name:(owner)) claims FS 37875: ΔE 0.0 from FS37875\n"
+        );
+    }
+
+    #[test]
+    fn verify_vallejo_claims() {
+        let data_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../data");
+        let standards = PaintLibrary::from_dir(&data_dir.join("standards")).unwrap();
+        let mut spec = read_paint_series_spec_file(
+            &data_dir.join("model_paint/Vallejo-ModelAirColor-rev18-Baja.json"),
+        )
+        .unwrap();
+        assert!(EquivalentsImporter::default().import(&mut spec) > 0);
+        let series = SeriesPaintSeries::from(&spec);
+        let report = EquivalenceReport::new(series.paints(), &standards, DEFAULT_DELTA_E_THRESHOLD);
+        let n_verifiable = report.checks().filter(|c| c.is_verifiable()).count();
+        assert!(n_verifiable > 0);
+        for check in report.checks().filter(|c| c.is_verifiable()) {
+            let (_, delta_e) = check.standard.as_ref().unwrap();
+            assert_eq!(
+                report.suspect().any(|c| std::ptr::eq(c, check)),
+                *delta_e > DEFAULT_DELTA_E_THRESHOLD
+            );
+        }
+        assert!(report
+            .unverifiable()
+            .any(|c| c.equivalent.standard == "RLM"));
+        let report = EquivalenceReport::new(series.paints(), &standards, f64::MAX);
+        assert_eq!(report.suspect().count(), 0);
+        assert!(!format!("{report}").is_empty());
+    }
+}
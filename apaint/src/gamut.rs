@@ -0,0 +1,282 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! The range of colours (gamut) that can be mixed from a limited palette of paints.
+//!
+//! The gamut is estimated by sampling mixes of up to a few paints in small whole number
+//! ratios using the same subtractive mixing model as mixtures.  It is then summarised as
+//! the greatest chroma reached in each sector of the hue wheel.
+
+use std::rc::Rc;
+
+use gcd::Gcd;
+
+use colour_math::{mixing::SubtractiveMixer, ColourAttributes, ColourBasics, ScalarAttribute, HCV};
+
+use crate::{
+    delta_e::{delta_e, nearest},
+    series::SeriesPaint,
+};
+
+#[derive(Debug, Clone)]
+pub struct GamutBuilder {
+    max_components: usize,
+    max_parts: u64,
+    sectors: usize,
+}
+
+impl Default for GamutBuilder {
+    fn default() -> Self {
+        Self {
+            max_components: 3,
+            max_parts: 3,
+            sectors: 24,
+        }
+    }
+}
+
+impl GamutBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The largest number of paints in the sampled mixes.
+    pub fn max_components(&mut self, max_components: usize) -> &mut Self {
+        self.max_components = max_components.max(1);
+        self
+    }
+
+    /// The largest number of parts of any component in the sampled mixes.
+    pub fn max_parts(&mut self, max_parts: u64) -> &mut Self {
+        self.max_parts = max_parts.max(1);
+        self
+    }
+
+    /// The number of (equal) sectors the hue wheel is divided into.
+    pub fn sectors(&mut self, sectors: usize) -> &mut Self {
+        self.sectors = sectors.max(1);
+        self
+    }
+
+    fn add_mixes(
+        &self,
+        colours: &[HCV],
+        first: usize,
+        chosen: &mut Vec<usize>,
+        samples: &mut Vec<HCV>,
+    ) {
+        if !chosen.is_empty() {
+            let mut parts = vec![1_u64; chosen.len()];
+            loop {
+                if parts.iter().fold(0, |gcd, parts| gcd.gcd(*parts)) == 1 {
+                    let mut mixer = SubtractiveMixer::new();
+                    for (index, parts) in chosen.iter().zip(parts.iter()) {
+                        mixer.add(&colours[*index], *parts);
+                    }
+                    if let Some(colour) = mixer.mixed_colour() {
+                        samples.push(colour);
+                    }
+                }
+                match parts.iter().position(|parts| *parts < self.max_parts) {
+                    Some(index) => {
+                        parts[index] += 1;
+                        for parts in parts[..index].iter_mut() {
+                            *parts = 1;
+                        }
+                    }
+                    None => break,
+                }
+            }
+        }
+        if chosen.len() < self.max_components {
+            for index in first..colours.len() {
+                chosen.push(index);
+                self.add_mixes(colours, index + 1, chosen, samples);
+                chosen.pop();
+            }
+        }
+    }
+
+    pub fn build(&self, paints: &[Rc<SeriesPaint>]) -> Gamut {
+        let colours: Vec<HCV> = paints.iter().map(|paint| paint.hcv()).collect();
+        let mut samples = vec![];
+        self.add_mixes(&colours, 0, &mut vec![], &mut samples);
+        let sector_degrees = 360.0 / self.sectors as f64;
+        let mut sector_limits: Vec<Option<HCV>> = vec![None; self.sectors];
+        for sample in samples.iter() {
+            if let Some(angle) = sample.hue_angle() {
+                let degrees = f64::from(angle).rem_euclid(360.0);
+                let index = (degrees / sector_degrees) as usize % self.sectors;
+                if !matches!(sector_limits[index], Some(limit) if chroma(&limit) >= chroma(sample))
+                {
+                    sector_limits[index] = Some(*sample);
+                }
+            }
+        }
+        Gamut {
+            samples,
+            sector_degrees,
+            sector_limits,
+        }
+    }
+}
+
+fn chroma(colour: &impl ColourAttributes) -> f64 {
+    f64::from(colour.scalar_attribute(ScalarAttribute::Chroma))
+}
+
+#[derive(Debug)]
+pub struct Gamut {
+    samples: Vec<HCV>,
+    sector_degrees: f64,
+    sector_limits: Vec<Option<HCV>>,
+}
+
+impl Gamut {
+    /// The sampled colours (including the paints themselves).
+    pub fn samples(&self) -> &[HCV] {
+        &self.samples
+    }
+
+    /// The width of the hue sectors in degrees.
+    pub fn sector_degrees(&self) -> f64 {
+        self.sector_degrees
+    }
+
+    /// The most chromatic sampled colour in each hue sector (starting at 0°).
+    pub fn sector_limits(&self) -> &[Option<HCV>] {
+        &self.sector_limits
+    }
+
+    /// The greatest chroma reached in each hue sector.
+    pub fn sector_max_chromas(&self) -> Vec<f64> {
+        self.sector_limits
+            .iter()
+            .map(|limit| limit.map(|colour| chroma(&colour)).unwrap_or(0.0))
+            .collect()
+    }
+
+    /// The hue ranges (start and end in degrees) of the sectors in which `chroma` cannot be
+    /// reached.  Adjacent sectors are merged.
+    pub fn unreachable_hues(&self, chroma: f64) -> Vec<(f64, f64)> {
+        let mut ranges: Vec<(f64, f64)> = vec![];
+        let mut previous: Option<usize> = None;
+        for (index, max_chroma) in self.sector_max_chromas().into_iter().enumerate() {
+            if max_chroma < chroma {
+                let start = index as f64 * self.sector_degrees;
+                let end = start + self.sector_degrees;
+                match ranges.last_mut() {
+                    Some(range) if previous == Some(index.wrapping_sub(1)) => range.1 = end,
+                    _ => ranges.push((start, end)),
+                }
+                previous = Some(index);
+            }
+        }
+        if ranges.len() > 1 && ranges[0].0 == 0.0 && ranges[ranges.len() - 1].1 >= 360.0 {
+            let (_, end) = ranges.remove(0);
+            ranges.last_mut().expect("len > 1").1 = 360.0 + end;
+        }
+        ranges
+    }
+
+    /// The fraction of the hue wheel in which `chroma` can be reached.
+    pub fn coverage(&self, chroma: f64) -> f64 {
+        let reached = self
+            .sector_max_chromas()
+            .into_iter()
+            .filter(|max_chroma| *max_chroma >= chroma)
+            .count();
+        reached as f64 / self.sector_limits.len() as f64
+    }
+
+    /// The lowest and highest values in the gamut.
+    pub fn value_range(&self) -> Option<(f64, f64)> {
+        self.samples.iter().fold(None, |range, sample| {
+            let value = f64::from(sample.value());
+            match range {
+                Some((low, high)) => Some((value.min(low), value.max(high))),
+                None => Some((value, value)),
+            }
+        })
+    }
+
+    /// The sampled colour nearest to `target` and its distance.
+    pub fn nearest(&self, target: &(impl ColourBasics + ColourAttributes)) -> Option<(HCV, f64)> {
+        nearest(target, &self.samples).map(|(colour, distance)| (*colour, distance))
+    }
+
+    /// Whether a mix within `tolerance` (ΔE) of `target` was found.
+    pub fn can_reach(
+        &self,
+        target: &(impl ColourBasics + ColourAttributes),
+        tolerance: f64,
+    ) -> bool {
+        self.samples
+            .iter()
+            .any(|sample| delta_e(target, sample) <= tolerance)
+    }
+
+    /// A one line summary of the coverage at `chroma`.
+    pub fn report(&self, chroma: f64) -> String {
+        let (low, high) = self.value_range().unwrap_or((0.0, 0.0));
+        let unreachable: Vec<String> = self
+            .unreachable_hues(chroma)
+            .iter()
+            .map(|(start, end)| format!("{:.0}°-{:.0}°", start, end))
+            .collect();
+        format!(
+            "{:.0}% of hues reach chroma {:.2}; values {:.2} to {:.2}; unreachable: {}",
+            self.coverage(chroma) * 100.0,
+            chroma,
+            low,
+            high,
+            if unreachable.is_empty() {
+                "none".to_string()
+            } else {
+                unreachable.join(", ")
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::series::test_series;
+    use colour_math::{HueConstants, RGBConstants};
+
+    fn palette(colours: &[(HCV, &str)]) -> Vec<Rc<SeriesPaint>> {
+        test_series(colours).paints().cloned().collect()
+    }
+
+    #[test]
+    fn gamut_coverage() {
+        let paints = palette(&[
+            (HCV::RED, "red"),
+            (HCV::YELLOW, "yellow"),
+            (HCV::WHITE, "white"),
+        ]);
+        let gamut = GamutBuilder::new().sectors(12).build(&paints);
+        assert!(gamut.can_reach(&HCV::RED, 0.001));
+        assert!(!gamut.can_reach(&HCV::BLUE, 10.0));
+        assert!(gamut.coverage(0.5) < 0.5);
+        assert!(gamut.coverage(0.0) > 0.0);
+        let unreachable = gamut.unreachable_hues(0.5);
+        assert!(!unreachable.is_empty());
+        assert!(unreachable
+            .iter()
+            .any(|(start, end)| *start <= 240.0 && 240.0 < *end));
+        assert_eq!(gamut.value_range().map(|(_, high)| high), Some(1.0));
+
+        let paints = palette(&[
+            (HCV::RED, "red"),
+            (HCV::YELLOW, "yellow"),
+            (HCV::CYAN, "cyan"),
+            (HCV::BLUE, "blue"),
+            (HCV::MAGENTA, "magenta"),
+            (HCV::GREEN, "green"),
+        ]);
+        let gamut = GamutBuilder::new().sectors(12).build(&paints);
+        assert!(gamut.unreachable_hues(0.5).is_empty());
+        assert_eq!(gamut.coverage(0.5), 1.0);
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Glazing: predicting the colour of layers of paint applied over a substrate.
+//!
+//! Unlike mixing, a glaze acts as a filter over what lies beneath it.  Each layer is
+//! modelled as partly hiding the colour below (according to its opacity) and filtering
+//! the rest by its own colour (more strongly for thicker layers).
+//! Thickness is relative to a normal undiluted coat.
+
+use colour_math::{ColourBasics, HCV, RGB};
+
+use crate::{
+    properties::{Opacity, PropertyType},
+    BasicPaintIfce,
+};
+
+/// The fraction of the colour below hidden by a normal coat of paint with `opacity`.
+pub fn hiding_power(opacity: Opacity) -> f64 {
+    match opacity {
+        Opacity::Opaque => 1.0,
+        Opacity::SemiOpaque => 0.7,
+        Opacity::SemiTransparent => 0.4,
+        Opacity::Transparent => 0.15,
+        Opacity::Clear => 0.0,
+    }
+}
+
+/// The relative thickness of a coat of paint thinned with `medium_parts` of medium to
+/// `paint_parts` of paint.
+pub fn dilution_thickness(paint_parts: u64, medium_parts: u64) -> f64 {
+    if paint_parts + medium_parts == 0 {
+        0.0
+    } else {
+        paint_parts as f64 / (paint_parts + medium_parts) as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlazeLayer {
+    pub colour: HCV,
+    pub opacity: Opacity,
+    /// Relative to a normal undiluted coat.
+    pub thickness: f64,
+}
+
+impl GlazeLayer {
+    pub fn new(colour: &impl ColourBasics, opacity: Opacity, thickness: f64) -> Self {
+        Self {
+            colour: colour.hcv(),
+            opacity,
+            thickness: thickness.max(0.0),
+        }
+    }
+
+    /// A layer of `paint` using its recorded opacity (or transparency if that is all that
+    /// is recorded).
+    pub fn from_paint(paint: &impl BasicPaintIfce, thickness: f64) -> Self {
+        let opacity = if paint.recorded_property(PropertyType::Opacity).is_some() {
+            paint.opacity()
+        } else {
+            paint.transparency().into()
+        };
+        Self::new(paint, opacity, thickness)
+    }
+
+    /// The fraction of the colour below that this layer hides.
+    pub fn hiding(&self) -> f64 {
+        1.0 - (1.0 - hiding_power(self.opacity)).powf(self.thickness)
+    }
+
+    /// The fraction of the light in each channel that passes through this layer.
+    fn transmission(&self, glaze: f64) -> f64 {
+        if self.opacity == Opacity::Clear {
+            // Clear layers don't filter (their recorded colour is of no consequence).
+            1.0
+        } else if self.thickness <= 1.0 {
+            1.0 - self.thickness * (1.0 - glaze)
+        } else {
+            glaze.powf(self.thickness)
+        }
+    }
+
+    fn apply(&self, below: [f64; 3]) -> [f64; 3] {
+        let glaze: [f64; 3] = self.colour.rgb::<f64>().into();
+        let hiding = self.hiding();
+        let channel = |i: usize| {
+            let filtered = below[i] * self.transmission(glaze[i]);
+            (hiding * glaze[i] + (1.0 - hiding) * filtered).clamp(0.0, 1.0)
+        };
+        [channel(0), channel(1), channel(2)]
+    }
+}
+
+/// A substrate and the glaze layers applied over it (bottom layer first).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glazing {
+    substrate: HCV,
+    layers: Vec<GlazeLayer>,
+}
+
+impl Glazing {
+    pub fn new(substrate: &impl ColourBasics) -> Self {
+        Self {
+            substrate: substrate.hcv(),
+            layers: vec![],
+        }
+    }
+
+    pub fn add_layer(&mut self, layer: GlazeLayer) -> &mut Self {
+        self.layers.push(layer);
+        self
+    }
+
+    pub fn substrate(&self) -> HCV {
+        self.substrate
+    }
+
+    pub fn layers(&self) -> &[GlazeLayer] {
+        &self.layers
+    }
+
+    /// The colour after each layer has been applied (bottom layer first).
+    pub fn colours(&self) -> Vec<HCV> {
+        let mut rgb: [f64; 3] = self.substrate.rgb::<f64>().into();
+        self.layers
+            .iter()
+            .map(|layer| {
+                rgb = layer.apply(rgb);
+                RGB::<f64>::from(rgb).hcv()
+            })
+            .collect()
+    }
+
+    /// The predicted colour of the glazed surface.
+    pub fn colour(&self) -> HCV {
+        self.colours().pop().unwrap_or(self.substrate)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::delta_e::delta_e;
+    use colour_math::{HueConstants, RGBConstants};
+
+    #[test]
+    fn glazing() {
+        let mut glazing = Glazing::new(&HCV::BLUE);
+        assert!(delta_e(&glazing.colour(), &HCV::BLUE) < 0.001);
+        glazing.add_layer(GlazeLayer::new(&HCV::RED, Opacity::Clear, 1.0));
+        assert!(delta_e(&glazing.colour(), &HCV::BLUE) < 0.001);
+        glazing.add_layer(GlazeLayer::new(&HCV::YELLOW, Opacity::Opaque, 1.0));
+        assert!(delta_e(&glazing.colour(), &HCV::YELLOW) < 0.001);
+        assert_eq!(glazing.colours().len(), 2);
+
+        let mut glazing = Glazing::new(&HCV::WHITE);
+        glazing.add_layer(GlazeLayer::new(&HCV::RED, Opacity::Transparent, 1.0));
+        let red_glaze = glazing.colour();
+        assert!(delta_e(&red_glaze, &HCV::RED) < 0.001);
+        let mut thin = Glazing::new(&HCV::WHITE);
+        thin.add_layer(GlazeLayer::new(
+            &HCV::RED,
+            Opacity::Transparent,
+            dilution_thickness(1, 3),
+        ));
+        assert!(f64::from(thin.colour().value()) > f64::from(red_glaze.value()));
+        assert!(f64::from(thin.colour().hue_angle().unwrap()).abs() < 0.001);
+
+        let mut glazing = Glazing::new(&HCV::BLACK);
+        glazing.add_layer(GlazeLayer::new(&HCV::WHITE, Opacity::SemiOpaque, 1.0));
+        let one_coat = glazing.colour();
+        glazing.add_layer(GlazeLayer::new(&HCV::WHITE, Opacity::SemiOpaque, 1.0));
+        assert!(f64::from(glazing.colour().value()) > f64::from(one_coat.value()));
+    }
+}
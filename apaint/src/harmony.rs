@@ -0,0 +1,205 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Colour harmonies (sets of colours at fixed hue offsets from a base colour) and their
+//! nearest approximations using the paints at hand.
+//!
+//! Hues are rotated about the grey axis of the RGB cube so the harmony colours have the
+//! same value as the base colour.  Their chroma is reduced if necessary to keep them in
+//! gamut.
+
+use std::rc::Rc;
+
+use colour_math::{ColourAttributes, ColourBasics, HCV, RGB};
+
+use crate::{
+    conversion::{ColourMatch, ConversionChartBuilder},
+    series::SeriesPaint,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Harmony {
+    Complementary,
+    SplitComplementary,
+    Triadic,
+    Tetradic,
+    Analogous,
+}
+
+impl Harmony {
+    pub const ALL: [Harmony; 5] = [
+        Harmony::Complementary,
+        Harmony::SplitComplementary,
+        Harmony::Triadic,
+        Harmony::Tetradic,
+        Harmony::Analogous,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Complementary => "Complementary",
+            Self::SplitComplementary => "Split Complementary",
+            Self::Triadic => "Triadic",
+            Self::Tetradic => "Tetradic",
+            Self::Analogous => "Analogous",
+        }
+    }
+
+    /// The hue offsets (in degrees) of the harmony's colours from the base colour (which
+    /// is not included).
+    pub fn offsets(self) -> &'static [f64] {
+        match self {
+            Self::Complementary => &[180.0],
+            Self::SplitComplementary => &[150.0, 210.0],
+            Self::Triadic => &[120.0, 240.0],
+            Self::Tetradic => &[90.0, 180.0, 270.0],
+            Self::Analogous => &[-30.0, 30.0],
+        }
+    }
+}
+
+impl std::fmt::Display for Harmony {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// `colour` with its hue angle increased by `degrees` or `None` if `colour` is a grey.
+pub fn rotated_hue(colour: &impl ColourBasics, degrees: f64) -> Option<HCV> {
+    colour.hue_angle()?;
+    let rgb: [f64; 3] = colour.rgb::<f64>().into();
+    let grey = rgb.iter().sum::<f64>() / 3.0;
+    let d = [rgb[0] - grey, rgb[1] - grey, rgb[2] - grey];
+    // Rodrigues' rotation about the unit grey axis (d is perpendicular to it).
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let root3 = 3.0_f64.sqrt();
+    let cross = [
+        (d[2] - d[1]) / root3,
+        (d[0] - d[2]) / root3,
+        (d[1] - d[0]) / root3,
+    ];
+    let rotated: Vec<f64> = (0..3).map(|i| d[i] * cos + cross[i] * sin).collect();
+    let scale = rotated.iter().fold(1.0_f64, |scale, component| {
+        if grey + component * scale > 1.0 {
+            (1.0 - grey) / component
+        } else if grey + component * scale < 0.0 {
+            -grey / component
+        } else {
+            scale
+        }
+    });
+    let rgb = [
+        grey + rotated[0] * scale,
+        grey + rotated[1] * scale,
+        grey + rotated[2] * scale,
+    ];
+    Some(RGB::<f64>::from(rgb).hcv())
+}
+
+/// The colours of `harmony` for `colour` (empty if `colour` is a grey).
+pub fn harmony_colours(colour: &impl ColourBasics, harmony: Harmony) -> Vec<HCV> {
+    harmony
+        .offsets()
+        .iter()
+        .filter_map(|offset| rotated_hue(colour, *offset))
+        .collect()
+}
+
+/// A harmony colour (with its offset from the base colour) and its closest match.
+#[derive(Debug)]
+pub struct HarmonyEntry {
+    pub offset: f64,
+    pub colour: HCV,
+    pub best_match: Option<ColourMatch>,
+}
+
+impl HarmonyEntry {
+    pub fn tooltip_text(&self) -> String {
+        match &self.best_match {
+            Some(best_match) => format!(
+                "{:+}°: {} (ΔE {:.1})",
+                self.offset,
+                best_match.description(),
+                best_match.delta_e()
+            ),
+            None => format!("{:+}°: no match", self.offset),
+        }
+    }
+}
+
+/// Match the colours of `harmony` for `colour` to the nearest of `paints` (or a two paint
+/// mix of them when `matcher` finds a better one).
+pub fn match_harmony(
+    colour: &(impl ColourBasics + ColourAttributes),
+    harmony: Harmony,
+    paints: &[Rc<SeriesPaint>],
+    matcher: &ConversionChartBuilder,
+) -> Vec<HarmonyEntry> {
+    harmony
+        .offsets()
+        .iter()
+        .filter_map(|offset| {
+            let harmony_colour = rotated_hue(colour, *offset)?;
+            let (matches, mix) = matcher.match_colour(&harmony_colour, paints);
+            let best_match = match mix {
+                Some(mix) => Some(ColourMatch::Mix(mix)),
+                None => matches
+                    .into_iter()
+                    .next()
+                    .map(|(paint, delta_e)| ColourMatch::Paint(paint, delta_e)),
+            };
+            Some(HarmonyEntry {
+                offset: *offset,
+                colour: harmony_colour,
+                best_match,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{delta_e::delta_e, series::test_series, BasicPaintIfce};
+    use colour_math::{HueConstants, RGBConstants};
+
+    #[test]
+    fn rotate_hues() {
+        assert!(rotated_hue(&HCV::WHITE, 90.0).is_none());
+        let green = rotated_hue(&HCV::RED, 120.0).unwrap();
+        assert!(delta_e(&green, &HCV::GREEN) < 0.001);
+        let cyan = rotated_hue(&HCV::RED, 180.0).unwrap();
+        assert!(delta_e(&cyan, &HCV::CYAN) < 0.001);
+        let magenta = rotated_hue(&HCV::RED, -60.0).unwrap();
+        assert!(delta_e(&magenta, &HCV::MAGENTA) < 0.001);
+        let tetradic = harmony_colours(&HCV::RED, Harmony::Tetradic);
+        assert_eq!(tetradic.len(), 3);
+        for colour in tetradic {
+            assert!((f64::from(colour.value()) - f64::from(HCV::RED.value())).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn match_harmonies() {
+        let series = test_series(&[
+            (HCV::BLUE, "blue"),
+            (HCV::GREEN, "green"),
+            (HCV::RED, "red"),
+        ]);
+        let paints: Vec<Rc<SeriesPaint>> = series.paints().cloned().collect();
+        let entries = match_harmony(
+            &HCV::RED,
+            Harmony::Triadic,
+            &paints,
+            &ConversionChartBuilder::new(),
+        );
+        assert_eq!(entries.len(), 2);
+        match &entries[0].best_match {
+            Some(ColourMatch::Paint(paint, _)) => assert_eq!(paint.id(), "green"),
+            _ => panic!("expected green"),
+        }
+        match &entries[1].best_match {
+            Some(ColourMatch::Paint(paint, _)) => assert_eq!(paint.id(), "blue"),
+            _ => panic!("expected blue"),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Undo/redo history.
+//!
+//! `History` only keeps the edits in order; applying and reverting them is up to the owner
+//! of the edited state.  `SessionEdit` covers the edits of a `MixingSession` and
+//! `SessionHistory` does the applying and reverting for headless use.
+
+use std::rc::Rc;
+
+use crate::{
+    mixtures::{MixingSession, Mixture},
+    properties::PropertyMixRules,
+    series::SeriesPaint,
+    BasicPaintIfce,
+};
+
+#[derive(Debug)]
+pub struct History<E> {
+    done: Vec<E>,
+    undone: Vec<E>,
+}
+
+impl<E> Default for History<E> {
+    fn default() -> Self {
+        Self {
+            done: vec![],
+            undone: vec![],
+        }
+    }
+}
+
+impl<E> History<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an edit that has just been made.  This discards any undone edits.
+    pub fn record(&mut self, edit: E) {
+        self.undone.clear();
+        self.done.push(edit);
+    }
+
+    /// Record an edit that has just been made unless `absorb` merges it into the previous
+    /// edit (e.g. successive keystrokes in the same entry).
+    pub fn record_or_absorb(&mut self, edit: E, absorb: impl FnOnce(&mut E, &E) -> bool) {
+        self.undone.clear();
+        match self.done.last_mut() {
+            Some(last) if absorb(last, &edit) => (),
+            _ => self.done.push(edit),
+        }
+    }
+
+    /// Move the most recent edit to the redo stack and return it so that it can be reverted.
+    pub fn undo(&mut self) -> Option<&E> {
+        let edit = self.done.pop()?;
+        self.undone.push(edit);
+        self.undone.last()
+    }
+
+    /// Move the most recently undone edit back to the undo stack and return it so that it
+    /// can be reapplied.
+    pub fn redo(&mut self) -> Option<&E> {
+        let edit = self.undone.pop()?;
+        self.done.push(edit);
+        self.done.last()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// The edit that `undo()` would revert.
+    pub fn next_undo(&self) -> Option<&E> {
+        self.done.last()
+    }
+
+    /// The edit that `redo()` would reapply.
+    pub fn next_redo(&self) -> Option<&E> {
+        self.undone.last()
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+#[derive(Debug)]
+pub enum SessionEdit {
+    /// A mixture was added (replacing any mixture with the same id).
+    AddMixture {
+        mixture: Rc<Mixture>,
+        replaced: Option<Rc<Mixture>>,
+    },
+    RemoveMixture(Rc<Mixture>),
+    ReplaceMixture {
+        old: Rc<Mixture>,
+        new: Rc<Mixture>,
+    },
+    SetNotes {
+        old: String,
+        new: String,
+    },
+    SetMixRules {
+        old: PropertyMixRules,
+        new: PropertyMixRules,
+    },
+}
+
+impl SessionEdit {
+    pub fn description(&self) -> String {
+        match self {
+            Self::AddMixture { mixture, .. } => format!("add {}", mixture.id()),
+            Self::RemoveMixture(mixture) => format!("remove {}", mixture.id()),
+            Self::ReplaceMixture { new, .. } => format!("edit {}", new.id()),
+            Self::SetNotes { .. } => "edit notes".to_string(),
+            Self::SetMixRules { .. } => "change mixing rules".to_string(),
+        }
+    }
+
+    pub fn apply(&self, session: &mut MixingSession) {
+        match self {
+            Self::AddMixture { mixture, .. } => {
+                session.add_mixture(mixture);
+            }
+            Self::RemoveMixture(mixture) => {
+                session.remove_mixture(mixture.id());
+            }
+            Self::ReplaceMixture { new, .. } => {
+                session.add_mixture(new);
+            }
+            Self::SetNotes { new, .. } => session.set_notes(new),
+            Self::SetMixRules { new, .. } => session.set_mix_rules(new),
+        }
+    }
+
+    pub fn revert(&self, session: &mut MixingSession) {
+        match self {
+            Self::AddMixture { mixture, replaced } => {
+                session.remove_mixture(mixture.id());
+                if let Some(replaced) = replaced {
+                    session.add_mixture(replaced);
+                }
+            }
+            Self::RemoveMixture(mixture) => {
+                session.add_mixture(mixture);
+            }
+            Self::ReplaceMixture { old, .. } => {
+                session.add_mixture(old);
+            }
+            Self::SetNotes { old, .. } => session.set_notes(old),
+            Self::SetMixRules { old, .. } => session.set_mix_rules(old),
+        }
+    }
+
+    /// Merge a following notes edit into this one so that typing is undone as a whole.
+    pub fn absorb(&mut self, next: &Self) -> bool {
+        match (self, next) {
+            (Self::SetNotes { new, .. }, Self::SetNotes { old, new: newer }) if new == old => {
+                *new = newer.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub type SessionHistory = History<SessionEdit>;
+
+impl History<SessionEdit> {
+    /// Apply `edit` to `session` and record it.
+    pub fn perform(&mut self, session: &mut MixingSession, edit: SessionEdit) {
+        edit.apply(session);
+        self.record_or_absorb(edit, SessionEdit::absorb);
+    }
+
+    /// Revert the most recent edit of `session` returning whether there was one.
+    pub fn undo_session(&mut self, session: &mut MixingSession) -> bool {
+        match self.undo() {
+            Some(edit) => {
+                edit.revert(session);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reapply the most recently undone edit of `session` returning whether there was one.
+    pub fn redo_session(&mut self, session: &mut MixingSession) -> bool {
+        match self.redo() {
+            Some(edit) => {
+                edit.apply(session);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{mixtures::MixtureBuilder, series::test_series};
+    use colour_math::{HueConstants, HCV};
+
+    #[test]
+    fn undo_redo_session() {
+        let series = test_series(&[(HCV::RED, "red"), (HCV::YELLOW, "yellow")]);
+        let red = series.find("red").unwrap();
+        let yellow = series.find("yellow").unwrap();
+        let orange = MixtureBuilder::new("MIX#001")
+            .series_paint_component((Rc::clone(red), 1))
+            .series_paint_component((Rc::clone(yellow), 1))
+            .build();
+
+        let mut session = MixingSession::new();
+        let original_digest = session.digest().unwrap();
+        let mut history = SessionHistory::new();
+        assert!(!history.undo_session(&mut session));
+        history.perform(
+            &mut session,
+            SessionEdit::AddMixture {
+                mixture: Rc::clone(&orange),
+                replaced: None,
+            },
+        );
+        for (old, new) in [("", "o"), ("o", "or"), ("or", "orange")] {
+            history.perform(
+                &mut session,
+                SessionEdit::SetNotes {
+                    old: old.to_string(),
+                    new: new.to_string(),
+                },
+            );
+        }
+        assert_eq!(session.notes(), "orange");
+        assert_eq!(session.mixtures().count(), 1);
+
+        assert!(history.undo_session(&mut session));
+        assert_eq!(session.notes(), "");
+        assert_eq!(session.mixtures().count(), 1);
+        assert!(history.undo_session(&mut session));
+        assert_eq!(session.mixtures().count(), 0);
+        assert_eq!(session.digest().unwrap(), original_digest);
+        assert!(!history.can_undo());
+
+        assert!(history.redo_session(&mut session));
+        assert!(session.mixture("MIX#001").is_some());
+        history.perform(&mut session, SessionEdit::RemoveMixture(orange));
+        assert!(!history.can_redo());
+        assert_eq!(session.mixtures().count(), 0);
+        assert!(history.undo_session(&mut session));
+        assert!(session.mixture("MIX#001").is_some());
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Tint, shade and tone ladders: a base paint mixed with increasing proportions of a
+//! white, black or grey paint (e.g. 1:0, 1:1, ... 1:8) for value studies.
+
+use std::{io::Write, rc::Rc};
+
+use colour_math::ColourBasics;
+
+use crate::{
+    conversion::csv_field,
+    mixtures::{MixingSession, Mixture, MixtureBuilder},
+    properties::PropertyMixRules,
+    series::SeriesPaint,
+    svg::SvgWriter,
+    BasicPaintIfce,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LadderKind {
+    /// Mixed with white.
+    Tint,
+    /// Mixed with black.
+    Shade,
+    /// Mixed with grey.
+    Tone,
+}
+
+impl LadderKind {
+    pub const ALL: [LadderKind; 3] = [LadderKind::Tint, LadderKind::Shade, LadderKind::Tone];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tint => "Tint",
+            Self::Shade => "Shade",
+            Self::Tone => "Tone",
+        }
+    }
+}
+
+impl std::fmt::Display for LadderKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug)]
+pub struct LadderBuilder {
+    kind: LadderKind,
+    base: Rc<SeriesPaint>,
+    modifier: Rc<SeriesPaint>,
+    steps: Vec<(u64, u64)>,
+    mix_rules: PropertyMixRules,
+}
+
+impl LadderBuilder {
+    /// A ladder of `base` mixed with `modifier` (the white, black or grey paint) at the
+    /// ratios 1:0 to 1:8.
+    pub fn new(kind: LadderKind, base: &Rc<SeriesPaint>, modifier: &Rc<SeriesPaint>) -> Self {
+        let mut builder = Self {
+            kind,
+            base: Rc::clone(base),
+            modifier: Rc::clone(modifier),
+            steps: vec![],
+            mix_rules: PropertyMixRules::default(),
+        };
+        builder.max_ratio(8);
+        builder
+    }
+
+    /// Use the ratios 1:0, 1:1, ... 1:`max_ratio` (base:modifier).
+    pub fn max_ratio(&mut self, max_ratio: u64) -> &mut Self {
+        self.steps = (0..=max_ratio).map(|modifier| (1, modifier)).collect();
+        self
+    }
+
+    /// Use the given ratios (base:modifier).  Steps with no paint at all are ignored.
+    pub fn steps(&mut self, steps: &[(u64, u64)]) -> &mut Self {
+        self.steps = steps
+            .iter()
+            .filter(|(base, modifier)| base + modifier > 0)
+            .copied()
+            .collect();
+        self
+    }
+
+    pub fn mix_rules(&mut self, mix_rules: &PropertyMixRules) -> &mut Self {
+        self.mix_rules = mix_rules.clone();
+        self
+    }
+
+    /// Build the ladder's mixtures using `next_id` to supply their ids.
+    pub fn build(&self, mut next_id: impl FnMut() -> String) -> Ladder {
+        let steps = self
+            .steps
+            .iter()
+            .map(|(base_parts, modifier_parts)| {
+                let mut builder = MixtureBuilder::new(&next_id());
+                builder
+                    .name(&format!(
+                        "{} {} {}:{}",
+                        self.base.id(),
+                        self.kind,
+                        base_parts,
+                        modifier_parts
+                    ))
+                    .notes(&format!(
+                        "{} of {} with {}",
+                        self.kind,
+                        self.base.id(),
+                        self.modifier.id()
+                    ))
+                    .mix_rules(&self.mix_rules);
+                if *base_parts > 0 {
+                    builder.series_paint_component((Rc::clone(&self.base), *base_parts));
+                }
+                if *modifier_parts > 0 {
+                    builder.series_paint_component((Rc::clone(&self.modifier), *modifier_parts));
+                }
+                ((*base_parts, *modifier_parts), builder.build())
+            })
+            .collect();
+        Ladder {
+            kind: self.kind,
+            base: Rc::clone(&self.base),
+            modifier: Rc::clone(&self.modifier),
+            steps,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Ladder {
+    kind: LadderKind,
+    base: Rc<SeriesPaint>,
+    modifier: Rc<SeriesPaint>,
+    steps: Vec<((u64, u64), Rc<Mixture>)>,
+}
+
+impl Ladder {
+    pub fn kind(&self) -> LadderKind {
+        self.kind
+    }
+
+    pub fn base(&self) -> &Rc<SeriesPaint> {
+        &self.base
+    }
+
+    pub fn modifier(&self) -> &Rc<SeriesPaint> {
+        &self.modifier
+    }
+
+    /// The ratios (base:modifier) and their mixtures.
+    pub fn steps(&self) -> impl Iterator<Item = &((u64, u64), Rc<Mixture>)> {
+        self.steps.iter()
+    }
+
+    pub fn mixtures(&self) -> impl Iterator<Item = &Rc<Mixture>> {
+        self.steps.iter().map(|(_, mixture)| mixture)
+    }
+
+    /// Add the ladder's mixtures to `session` returning any mixtures that they replaced.
+    pub fn add_to_session(&self, session: &mut MixingSession) -> Vec<Rc<Mixture>> {
+        self.mixtures()
+            .filter_map(|mixture| session.add_mixture(mixture))
+            .collect()
+    }
+
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> Result<(), crate::Error> {
+        writeln!(writer, "Id,Ratio,Name,RGB,Value")?;
+        for ((base_parts, modifier_parts), mixture) in self.steps.iter() {
+            writeln!(
+                writer,
+                "{},{}:{},{},{},{:.3}",
+                csv_field(mixture.id()),
+                base_parts,
+                modifier_parts,
+                csv_field(mixture.name().unwrap_or("")),
+                crate::svg::fill(mixture.as_ref()),
+                f64::from(mixture.value())
+            )?;
+        }
+        Ok(())
+    }
+
+    /// A chart with one swatch per step labelled with its ratio.
+    pub fn write_svg<W: Write>(&self, writer: &mut W) -> Result<(), crate::Error> {
+        const SWATCH: f64 = 60.0;
+        const GAP: f64 = 10.0;
+        let width = (SWATCH + GAP) * self.steps.len().max(1) as f64 + GAP;
+        let height = SWATCH + 70.0;
+        let mut svg = SvgWriter::new(writer, width, height)?;
+        svg.bold_text(
+            GAP,
+            20.0,
+            &format!(
+                "{} ladder: {} with {}",
+                self.kind,
+                self.base.id(),
+                self.modifier.id()
+            ),
+        )?;
+        for (index, ((base_parts, modifier_parts), mixture)) in self.steps.iter().enumerate() {
+            let x = GAP + (SWATCH + GAP) * index as f64;
+            svg.swatch(
+                x,
+                35.0,
+                SWATCH,
+                SWATCH,
+                mixture.as_ref(),
+                Some(mixture.id()),
+            )?;
+            svg.text(
+                x,
+                35.0 + SWATCH + 20.0,
+                &format!("{}:{}", base_parts, modifier_parts),
+            )?;
+        }
+        svg.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{delta_e::delta_e, series::test_series};
+    use colour_math::{HueConstants, RGBConstants, HCV};
+
+    #[test]
+    fn tint_ladder() {
+        let series = test_series(&[(HCV::RED, "red"), (HCV::WHITE, "white")]);
+        let red = series.find("red").unwrap();
+        let white = series.find("white").unwrap();
+        let mut count = 0;
+        let ladder = LadderBuilder::new(LadderKind::Tint, red, white)
+            .max_ratio(4)
+            .build(|| {
+                count += 1;
+                format!("MIX#{:03}", count)
+            });
+        let values: Vec<f64> = ladder.mixtures().map(|m| f64::from(m.value())).collect();
+        assert_eq!(values.len(), 5);
+        assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(delta_e(ladder.mixtures().next().unwrap().as_ref(), &HCV::RED) < 0.000_001);
+
+        let mut session = MixingSession::new();
+        assert!(ladder.add_to_session(&mut session).is_empty());
+        assert_eq!(session.mixtures().count(), 5);
+        assert_eq!(
+            session.mixture("MIX#005").unwrap().name(),
+            Some("red Tint 1:4")
+        );
+
+        let mut csv = vec![];
+        ladder.write_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 6);
+        let mut svg = vec![];
+        ladder.write_svg(&mut svg).unwrap();
+        assert!(String::from_utf8(svg).unwrap().starts_with("<svg"));
+    }
+}
@@ -7,7 +7,7 @@ use regex::Regex;
 use lazy_static::lazy_static;
 
 use crate::{
-    properties::{Finish, Fluorescence, Metallicness, Permanence, Transparency},
+    properties::{Finish, Fluorescence, Metallicness, Permanence, Property, Transparency},
     series::{BasicPaintSpec, SeriesPaintSeriesSpec},
 };
 
@@ -50,22 +50,29 @@ fn extract_paint_spec(line: &str) -> Result<BasicPaintSpec, crate::Error> {
                 .ok_or(NotAValidLegacySpec)?;
             match &c[1] {
                 "finish" => {
-                    bps.finish = Finish::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?
+                    bps.set_property(Property::Finish(
+                        Finish::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?,
+                    ));
                 }
                 "fluorescence" => {
-                    bps.fluorescence =
-                        Fluorescence::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?
+                    bps.set_property(Property::Fluorescence(
+                        Fluorescence::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?,
+                    ));
                 }
                 "permanence" => {
-                    bps.permanence = Permanence::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?
+                    bps.set_property(Property::Permanence(
+                        Permanence::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?,
+                    ));
                 }
                 "metallicness" | "metallic" => {
-                    bps.metallicness =
-                        Metallicness::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?
+                    bps.set_property(Property::Metallicness(
+                        Metallicness::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?,
+                    ));
                 }
                 "transparency" => {
-                    bps.transparency =
-                        Transparency::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?
+                    bps.set_property(Property::Transparency(
+                        Transparency::from_str(&c[2]).map_err(|_| NotAValidLegacySpec)?,
+                    ));
                 }
                 _ => return Err(NotAValidLegacySpec),
             }
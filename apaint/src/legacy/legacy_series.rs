@@ -10,7 +10,11 @@ use colour_math_derive::Colour;
 use colour_math::{ColourBasics, LightLevel, RGB};
 
 use crate::{
-    properties::{Finish, Fluorescence, Metallicness, Permanence, Transparency},
+    pigments::Pigment,
+    properties::{
+        Finish, Fluorescence, Metallicness, Permanence, PropertyMixRules, PropertyType,
+        Transparency,
+    },
     series::{BasicPaintSpec, SeriesId, SeriesPaintSeriesSpec},
     BasicPaintIfce,
 };
@@ -21,11 +25,19 @@ pub struct BasicPaintSpec00<F: LightLevel> {
     pub id: String,
     pub name: String,
     pub notes: String,
+    #[paint(property)]
     pub finish: Finish,
+    #[paint(property)]
     pub transparency: Transparency,
+    #[paint(property)]
     pub permanence: Permanence,
+    #[paint(property)]
     pub fluorescence: Fluorescence,
+    #[paint(property)]
     pub metallicness: Metallicness,
+    #[paint(pigments)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pigments: Vec<Pigment>,
 }
 
 impl<F: LightLevel> From<&BasicPaintSpec00<F>> for BasicPaintSpec {
@@ -43,11 +55,11 @@ impl<F: LightLevel> From<&BasicPaintSpec00<F>> for BasicPaintSpec {
             } else {
                 "".to_string()
             },
-            finish: paint00.finish(),
-            transparency: paint00.transparency(),
-            permanence: paint00.permanence(),
-            fluorescence: paint00.fluorescence(),
-            metallicness: paint00.metallicness(),
+            properties: paint00.recorded_properties(),
+            custom_properties: vec![],
+            pigments: paint00.pigments.clone(),
+            single_pigment: None,
+            equivalents: vec![],
         }
     }
 }
@@ -70,6 +82,9 @@ impl<F: LightLevel> From<SeriesPaintSeriesSpec00<F>> for SeriesPaintSeriesSpec {
         }
         Self {
             series_id,
+            property_types: PropertyType::BASIC.to_vec(),
+            custom_property_types: vec![],
+            mix_rules: PropertyMixRules::default(),
             paint_list,
         }
     }
@@ -114,6 +129,7 @@ mod test {
                 permanence: Permanence::default(),
                 fluorescence: Fluorescence::default(),
                 metallicness: Metallicness::default(),
+                pigments: vec![],
             }
         }
     }
@@ -12,11 +12,26 @@ use std::{error, fmt, io, result};
 
 use colour_math::{ColourAttributes, ColourBasics};
 
+pub mod conversion;
+pub mod delta_e;
+pub mod equivalents;
+pub mod gamut;
+pub mod glaze;
+pub mod harmony;
+pub mod history;
+pub mod ladder;
 pub mod legacy;
+pub mod library;
 pub mod mixtures;
+pub mod pigments;
 pub mod properties;
+pub mod query;
+pub mod recipe;
+pub mod recommend;
+pub mod search;
 pub mod series;
-pub mod watercolour;
+pub mod sort;
+pub mod svg;
 
 use crate::properties::*;
 
@@ -39,97 +54,121 @@ pub trait BasicPaintIfce: ColourBasics + ColourAttributes {
         None
     }
 
+    /// The values of the properties recorded for this paint.
+    fn recorded_properties(&self) -> Vec<Property> {
+        vec![]
+    }
+
+    /// The value of the property of type `property_type` if it is recorded for this paint.
+    fn recorded_property(&self, property_type: PropertyType) -> Option<Property> {
+        self.recorded_properties()
+            .into_iter()
+            .find(|property| property.property_type() == property_type)
+    }
+
     fn finish(&self) -> Finish {
-        Finish::default()
+        match self.recorded_property(PropertyType::Finish) {
+            Some(Property::Finish(value)) => value,
+            _ => Finish::default(),
+        }
     }
 
     fn transparency(&self) -> Transparency {
-        Transparency::default()
+        match self.recorded_property(PropertyType::Transparency) {
+            Some(Property::Transparency(value)) => value,
+            _ => Transparency::default(),
+        }
     }
 
     fn opacity(&self) -> Opacity {
-        Opacity::default()
+        match self.recorded_property(PropertyType::Opacity) {
+            Some(Property::Opacity(value)) => value,
+            _ => Opacity::default(),
+        }
     }
 
     fn fluorescence(&self) -> Fluorescence {
-        Fluorescence::default()
+        match self.recorded_property(PropertyType::Fluorescence) {
+            Some(Property::Fluorescence(value)) => value,
+            _ => Fluorescence::default(),
+        }
     }
 
     fn permanence(&self) -> Permanence {
-        Permanence::default()
+        match self.recorded_property(PropertyType::Permanence) {
+            Some(Property::Permanence(value)) => value,
+            _ => Permanence::default(),
+        }
     }
 
     fn metallicness(&self) -> Metallicness {
-        Metallicness::default()
+        match self.recorded_property(PropertyType::Metallicness) {
+            Some(Property::Metallicness(value)) => value,
+            _ => Metallicness::default(),
+        }
     }
 
     fn light_fastness(&self) -> LightFastness {
-        LightFastness::default()
+        match self.recorded_property(PropertyType::LightFastness) {
+            Some(Property::LightFastness(value)) => value,
+            _ => LightFastness::default(),
+        }
     }
 
     fn staining(&self) -> Staining {
-        Staining::default()
+        match self.recorded_property(PropertyType::Staining) {
+            Some(Property::Staining(value)) => value,
+            _ => Staining::default(),
+        }
     }
 
     fn granulation(&self) -> Granulation {
-        Granulation::default()
-    }
-
-    fn property(&self, property_type: PropertyType) -> Property {
-        match property_type {
-            PropertyType::Finish => Property::Finish(self.finish()),
-            PropertyType::Transparency => Property::Transparency(self.transparency()),
-            PropertyType::Permanence => Property::Permanence(self.permanence()),
-            PropertyType::Fluorescence => Property::Fluorescence(self.fluorescence()),
-            PropertyType::Metallicness => Property::Metallicness(self.metallicness()),
-            PropertyType::Staining => Property::Staining(self.staining()),
-            PropertyType::LightFastness => Property::LightFastness(self.light_fastness()),
-            PropertyType::Granulation => Property::Granulation(self.granulation()),
-            PropertyType::Opacity => Property::Granulation(self.granulation()),
+        match self.recorded_property(PropertyType::Granulation) {
+            Some(Property::Granulation(value)) => value,
+            _ => Granulation::default(),
         }
     }
-}
-
-pub trait WatercolourIfce: ColourBasics + ColourAttributes {
-    fn id(&self) -> &str;
-
-    fn name(&self) -> Option<&str> {
-        None
-    }
 
-    fn notes(&self) -> Option<&str> {
-        None
+    /// The values of the custom properties declared by this paint's series.
+    fn custom_values(&self) -> Vec<custom::CustomPropertyValue> {
+        vec![]
     }
 
-    fn transparency(&self) -> Transparency {
-        Transparency::default()
+    /// The value of the custom property `property_type` if it is recorded for this paint.
+    fn custom_value(
+        &self,
+        property_type: &std::rc::Rc<custom::CustomPropertyType>,
+    ) -> Option<custom::CustomPropertyValue> {
+        self.custom_values()
+            .into_iter()
+            .find(|value| value.name() == property_type.name())
     }
 
-    fn fluorescence(&self) -> Fluorescence {
-        Fluorescence::default()
+    /// The Colour Index codes of the pigments in this paint.
+    fn pigments(&self) -> &[pigments::Pigment] {
+        &[]
     }
 
-    fn light_fastness(&self) -> LightFastness {
-        LightFastness::default()
+    fn is_single_pigment(&self) -> bool {
+        self.pigments().len() == 1
     }
 
-    fn staining(&self) -> Staining {
-        Staining::default()
+    /// The standard colours that this paint is claimed to match.
+    fn equivalents(&self) -> &[equivalents::Equivalent] {
+        &[]
     }
 
-    fn granulation(&self) -> Granulation {
-        Granulation::default()
-    }
-
-    fn property(&self, property_type: WatercolourPropertyType) -> Property {
+    fn property(&self, property_type: PropertyType) -> Property {
         match property_type {
-            WatercolourPropertyType::Transparency => Property::Transparency(self.transparency()),
-            WatercolourPropertyType::Fluorescence => Property::Fluorescence(self.fluorescence()),
-            WatercolourPropertyType::Staining => Property::Staining(self.staining()),
-            WatercolourPropertyType::LightFastness => {
-                Property::LightFastness(self.light_fastness())
-            }
-            WatercolourPropertyType::Granulation => Property::Granulation(self.granulation()),
+            PropertyType::Finish => Property::Finish(self.finish()),
+            PropertyType::Transparency => Property::Transparency(self.transparency()),
+            PropertyType::Permanence => Property::Permanence(self.permanence()),
+            PropertyType::Fluorescence => Property::Fluorescence(self.fluorescence()),
+            PropertyType::Metallicness => Property::Metallicness(self.metallicness()),
+            PropertyType::Staining => Property::Staining(self.staining()),
+            PropertyType::LightFastness => Property::LightFastness(self.light_fastness()),
+            PropertyType::Granulation => Property::Granulation(self.granulation()),
+            PropertyType::Opacity => Property::Opacity(self.opacity()),
         }
     }
 }
@@ -138,10 +177,21 @@ pub trait WatercolourIfce: ColourBasics + ColourAttributes {
 pub enum Error {
     IOError(io::Error),
     SerdeJsonError(serde_json::Error),
+    RegexError(regex::Error),
     NotFound(String),
     UnknownSeries(series::SeriesId),
     UnknownSeriesPaint(series::SeriesId, String),
+    DuplicateSeries(series::SeriesId),
+    AmbiguousPaint(String, Vec<(series::SeriesId, String)>),
+    CyclicMixtureDependency(Vec<String>),
+    DuplicateMixture(String),
+    MixtureInUse(String, Vec<String>),
+    UnresolvedPaints(mixtures::UnresolvedPaints),
     NotAValidLegacySpec,
+    NotAValidPattern(String),
+    InvalidCustomProperty(String, String),
+    NotAValidPigment(String),
+    NotAValidQuery(String, String),
     NotImplemented,
 }
 
@@ -150,12 +200,41 @@ impl fmt::Display for Error {
         match self {
             Error::IOError(err) => write!(f, "IOError: {err}"),
             Error::SerdeJsonError(err) => write!(f, "Serde Json Error: {err}"),
+            Error::RegexError(err) => write!(f, "Regex Error: {err}"),
             Error::NotFound(string) => write!(f, "{string}: Not found."),
             Error::UnknownSeries(series_id) => write!(f, "{series_id}: unknown paint series"),
             Error::UnknownSeriesPaint(series_id, id) => {
                 write!(f, "{id}:({series_id}): unknown paint")
             }
+            Error::DuplicateSeries(series_id) => write!(f, "{series_id}: duplicate paint series"),
+            Error::AmbiguousPaint(id, matches) => {
+                let matches: Vec<String> = matches
+                    .iter()
+                    .map(|(series_id, id)| format!("{id}:({series_id})"))
+                    .collect();
+                write!(f, "{id}: ambiguous paint matches: {}", matches.join(", "))
+            }
+            Error::CyclicMixtureDependency(ids) => {
+                write!(f, "{}: cyclic mixture dependency", ids.join(" -> "))
+            }
+            Error::DuplicateMixture(id) => write!(f, "{id}: duplicate mixture id"),
+            Error::MixtureInUse(id, users) => {
+                write!(f, "{id}: used by mixtures: {}", users.join(", "))
+            }
+            Error::UnresolvedPaints(unresolved) => write!(f, "{unresolved}"),
             Error::NotAValidLegacySpec => write!(f, "Not a valid specification."),
+            Error::NotAValidPattern(pattern) => {
+                write!(f, "{pattern}: not a valid pattern (needs a \"code\" group).")
+            }
+            Error::InvalidCustomProperty(name, reason) => {
+                write!(f, "{name}: invalid custom property: {reason}.")
+            }
+            Error::NotAValidPigment(string) => {
+                write!(f, "{string}: not a valid Colour Index pigment code.")
+            }
+            Error::NotAValidQuery(query, reason) => {
+                write!(f, "{query}: not a valid query: {reason}.")
+            }
             Error::NotImplemented => write!(f, "Feature not yet implemented."),
         }
     }
@@ -166,6 +245,7 @@ impl error::Error for Error {
         match self {
             Error::IOError(err) => Some(err),
             Error::SerdeJsonError(err) => Some(err),
+            Error::RegexError(err) => Some(err),
             _ => None,
         }
     }
@@ -183,4 +263,10 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<regex::Error> for Error {
+    fn from(err: regex::Error) -> Self {
+        Error::RegexError(err)
+    }
+}
+
 pub type Result<T> = result::Result<T, Error>;
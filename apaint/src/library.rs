@@ -0,0 +1,271 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! A collection of paint series loaded from files without any GUI.
+
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    legacy::{extract_legacy_paint_series_spec, legacy_series::SeriesPaintSeriesSpec00},
+    series::{
+        PaintIndex, SeriesId, SeriesPaint, SeriesPaintFinder, SeriesPaintSeries,
+        SeriesPaintSeriesSpec,
+    },
+};
+
+/// Read a paint series specification in any of the formats that have been used over time:
+/// the current format, the earlier RGB based format or the legacy text format.
+pub fn read_paint_series_spec<R: Read>(
+    reader: &mut R,
+) -> Result<SeriesPaintSeriesSpec, crate::Error> {
+    let mut string = String::new();
+    reader.read_to_string(&mut string)?;
+    if let Ok(spec) = SeriesPaintSeriesSpec::read(&mut string.as_bytes()) {
+        return Ok(spec);
+    }
+    match SeriesPaintSeriesSpec00::<f64>::read(&mut string.as_bytes()) {
+        Ok(spec) => Ok(spec),
+        Err(err) => match &err {
+            crate::Error::SerdeJsonError(_) => {
+                extract_legacy_paint_series_spec(&string).map_err(|_| err)
+            }
+            _ => Err(err),
+        },
+    }
+}
+
+pub fn read_paint_series_spec_file(path: &Path) -> Result<SeriesPaintSeriesSpec, crate::Error> {
+    let mut file = File::open(path)?;
+    read_paint_series_spec(&mut file)
+}
+
+/// Paint series indexed by their `SeriesId`.
+#[derive(Debug, Default)]
+pub struct PaintLibrary {
+    series: Vec<(SeriesPaintSeries, Option<PathBuf>)>,
+    index: PaintIndex,
+}
+
+impl PaintLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a library containing every paint series file found in the directory tree
+    /// rooted at `dir`.  Any file that isn't a valid series (or is a duplicate) is an error.
+    pub fn from_dir(dir: &Path) -> Result<Self, crate::Error> {
+        let mut library = Self::new();
+        if let Some((_, err)) = library.add_dir(dir)?.into_iter().next() {
+            Err(err)
+        } else {
+            Ok(library)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.series.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+
+    fn binary_search_series_id(&self, series_id: &SeriesId) -> Result<usize, usize> {
+        self.series
+            .binary_search_by(|(series, _)| series.series_id().as_ref().cmp(series_id))
+    }
+
+    pub fn add_series(
+        &mut self,
+        series: SeriesPaintSeries,
+        path: Option<&Path>,
+    ) -> Result<(), crate::Error> {
+        match self.binary_search_series_id(series.series_id()) {
+            Ok(_) => Err(crate::Error::DuplicateSeries(series.series_id().into())),
+            Err(index) => {
+                self.index.add_series(&series);
+                self.series
+                    .insert(index, (series, path.map(|path| path.to_path_buf())));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn add_series_from_file(&mut self, path: &Path) -> Result<SeriesId, crate::Error> {
+        let spec = read_paint_series_spec_file(path)?;
+        let series = SeriesPaintSeries::from(&spec);
+        let series_id = SeriesId::from(series.series_id());
+        self.add_series(series, Some(path))?;
+        Ok(series_id)
+    }
+
+    /// Add every paint series file in the directory tree rooted at `dir`.  Files that
+    /// can't be added are skipped and returned along with the reason.
+    pub fn add_dir(&mut self, dir: &Path) -> Result<Vec<(PathBuf, crate::Error)>, crate::Error> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(dir)? {
+            paths.push(entry?.path());
+        }
+        paths.sort();
+        let mut skipped = vec![];
+        for path in paths {
+            if path.is_dir() {
+                skipped.append(&mut self.add_dir(&path)?);
+            } else if let Err(err) = self.add_series_from_file(&path) {
+                skipped.push((path, err));
+            }
+        }
+        Ok(skipped)
+    }
+
+    pub fn remove_series(&mut self, series_id: &SeriesId) -> Option<SeriesPaintSeries> {
+        match self.binary_search_series_id(series_id) {
+            Ok(index) => {
+                self.index.remove_series(series_id);
+                Some(self.series.remove(index).0)
+            }
+            Err(_) => None,
+        }
+    }
+
+    pub fn series(&self, series_id: &SeriesId) -> Option<&SeriesPaintSeries> {
+        match self.binary_search_series_id(series_id) {
+            Ok(index) => Some(&self.series[index].0),
+            Err(_) => None,
+        }
+    }
+
+    /// The file that the series was loaded from (if any).
+    pub fn series_path(&self, series_id: &SeriesId) -> Option<&Path> {
+        match self.binary_search_series_id(series_id) {
+            Ok(index) => self.series[index].1.as_deref(),
+            Err(_) => None,
+        }
+    }
+
+    pub fn series_ids(&self) -> impl Iterator<Item = &Rc<SeriesId>> {
+        self.series.iter().map(|(series, _)| series.series_id())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SeriesPaintSeries> {
+        self.series.iter().map(|(series, _)| series)
+    }
+
+    /// The index of all paints by id and name.
+    pub fn index(&self) -> &PaintIndex {
+        &self.index
+    }
+
+    /// All of the paints in all of the series.
+    pub fn paints(&self) -> impl Iterator<Item = &Rc<SeriesPaint>> {
+        self.series.iter().flat_map(|(series, _)| series.paints())
+    }
+}
+
+impl SeriesPaintFinder for PaintLibrary {
+    fn get_series_paint(
+        &self,
+        paint_id: &str,
+        series_id: Option<&SeriesId>,
+    ) -> Result<Rc<SeriesPaint>, crate::Error> {
+        if let Some(series_id) = series_id {
+            match self.series(series_id) {
+                Some(series) => match series.find(paint_id) {
+                    Some(paint) => Ok(Rc::clone(paint)),
+                    None => Err(crate::Error::UnknownSeriesPaint(
+                        series_id.clone(),
+                        paint_id.to_string(),
+                    )),
+                },
+                None => Err(crate::Error::UnknownSeries(series_id.clone())),
+            }
+        } else {
+            self.index.find(paint_id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{
+        mixtures::MixingSession,
+        pigments::{paints_containing, single_pigment_paints, Pigment},
+        BasicPaintIfce,
+    };
+
+    fn data_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../data")
+    }
+
+    const SESSION: &str = r###"{
+  "notes": "uses paints from the data directory",
+  "mixtures": [
+    {
+      "id": "#001",
+      "name": "",
+      "notes": "",
+      "components": [
+        [{"Series": [{"proprietor": "Humbrol", "series_name": "Humbrol Enamel Paints"}, "1"]}, 1],
+        [{"Series": [{"proprietor": "Nature/Physics", "series_name": "Ideal Paint Series"}, "Black"]}, 2]
+      ]
+    }
+  ]
+}"###;
+
+    #[test]
+    fn load_data_dir() {
+        let library = PaintLibrary::from_dir(&data_dir()).unwrap();
+        assert_eq!(library.len(), 8);
+        let series_id = SeriesId::new(
+            "British Standard 381C (www.britishstandardcolour.com)",
+            "UK Government",
+        );
+        let paint = library
+            .get_series_paint("BS381C 101", Some(&series_id))
+            .unwrap();
+        assert_eq!(paint.name(), Some("Sky Blue"));
+        assert!(library
+            .series_path(&series_id)
+            .unwrap()
+            .ends_with("standards/bs381c.json"));
+        assert!(library.get_series_paint("BS381C 101", None).is_ok());
+        match library.get_series_paint("Sky Blue", None) {
+            Err(crate::Error::AmbiguousPaint(_, matches)) => assert_eq!(matches.len(), 4),
+            _ => panic!("expected ambiguous paint"),
+        }
+        assert!(library.paints().count() > 700);
+        match library.get_series_paint("no such paint", Some(&series_id)) {
+            Err(crate::Error::UnknownSeriesPaint(_, _)) => (),
+            _ => panic!("expected unknown paint"),
+        }
+
+        let umber = "PBr7".parse::<Pigment>().unwrap();
+        let burnt_umber = paints_containing(&umber, library.paints())
+            .find(|paint| paint.name() == Some("Burnt Umber"))
+            .unwrap();
+        assert!(burnt_umber.is_single_pigment());
+        assert!(single_pigment_paints(library.paints()).count() > 30);
+
+        let library = Rc::new(library);
+        let session = MixingSession::read(&mut SESSION.as_bytes(), &library).unwrap();
+        assert_eq!(session.mixtures().count(), 1);
+    }
+
+    #[test]
+    fn reject_duplicate_series() {
+        let mut library = PaintLibrary::new();
+        let path = data_dir().join("standards/bs381c.json");
+        library.add_series_from_file(&path).unwrap();
+        match library.add_series_from_file(&path) {
+            Err(crate::Error::DuplicateSeries(_)) => (),
+            _ => panic!("duplicate series not detected"),
+        }
+        assert_eq!(library.len(), 1);
+    }
+}
@@ -2,6 +2,8 @@
 
 use std::{
     cmp::Ordering,
+    collections::{HashMap, VecDeque},
+    fmt,
     io::{Read, Write},
     rc::Rc,
 };
@@ -18,15 +20,169 @@ use colour_math::{
 
 use colour_math_derive::Colour;
 
-use crate::properties::{PropertyMixer};
 use crate::{
+    delta_e::nearest,
+    equivalents::Equivalent,
+    pigments::Pigment,
     properties::{
-        Finish, Fluorescence, FuzzyProperty, Metallicness, Permanence, Transparency,
+        custom::{CustomPropertyType, CustomPropertyValue},
+        Property, PropertyMixRule, PropertyMixRules, PropertyType,
     },
     series::{SeriesId, SeriesPaint, SeriesPaintFinder},
     BasicPaintIfce, LabelText, TooltipText,
 };
 
+/// The properties (other than colour) of a mixture.  Only properties recorded
+/// for all of a mixture's components are known for the mixture and custom properties are
+/// only mixed if they are ordinal.
+#[derive(Debug, Clone, Default)]
+pub struct MixedProperties {
+    values: Vec<(PropertyType, f64)>,
+    custom_values: Vec<(Rc<CustomPropertyType>, f64)>,
+}
+
+impl MixedProperties {
+    /// The mixed value of the property of type `property_type` if it is known.
+    pub fn mixed_property(&self, property_type: PropertyType) -> Option<Property> {
+        self.values
+            .iter()
+            .find(|(pt, _)| *pt == property_type)
+            .map(|(pt, value)| pt.value_from_ordinal(*value))
+    }
+
+    /// The mixed values of all the known properties.
+    pub fn values(&self) -> Vec<Property> {
+        self.values
+            .iter()
+            .map(|(pt, value)| pt.value_from_ordinal(*value))
+            .collect()
+    }
+
+    /// The mixed values of the known (ordinal) custom properties.
+    pub fn custom_values(&self) -> Vec<CustomPropertyValue> {
+        self.custom_values
+            .iter()
+            .map(|(pt, value)| CustomPropertyValue::from_ordinal(pt, *value))
+            .collect()
+    }
+}
+
+/// Combines the properties of the components of a mixture.
+///
+/// Properties are mixed using the session's rules, if it has any, then the rules of the
+/// components' series (if they agree) and, otherwise, the property types' default rules.
+/// Custom properties are mixed using their weighted mean.
+#[derive(Debug, Default)]
+pub struct PropertiesMixer {
+    components: usize,
+    total_parts: u64,
+    // (type, (value, parts) for each component recording it)
+    values: Vec<(PropertyType, Vec<(f64, u64)>)>,
+    custom_sums: Vec<(Rc<CustomPropertyType>, f64, usize)>,
+    mix_rules: PropertyMixRules,
+    series_rules: Vec<(PropertyType, PropertyMixRule)>,
+}
+
+impl PropertiesMixer {
+    fn add_value(&mut self, property_type: PropertyType, value: f64, parts: u64) {
+        match self.values.iter_mut().find(|(pt, _)| *pt == property_type) {
+            Some((_, values)) => values.push((value, parts)),
+            None => self.values.push((property_type, vec![(value, parts)])),
+        }
+    }
+
+    fn add_series_rules(&mut self, mix_rules: &PropertyMixRules) {
+        for series_rule in mix_rules.overrides() {
+            if !self.series_rules.contains(series_rule) {
+                self.series_rules.push(*series_rule);
+            }
+        }
+    }
+
+    fn rule(&self, property_type: PropertyType) -> PropertyMixRule {
+        if let Some(rule) = self.mix_rules.overriding_rule(property_type) {
+            return rule;
+        }
+        let mut series_rules = self
+            .series_rules
+            .iter()
+            .filter(|(pt, _)| *pt == property_type);
+        match (series_rules.next(), series_rules.next()) {
+            (Some((_, rule)), None) => *rule,
+            _ => property_type.default_mix_rule(),
+        }
+    }
+
+    fn add_custom_value(&mut self, property_type: &Rc<CustomPropertyType>, value: f64, parts: u64) {
+        match self
+            .custom_sums
+            .iter_mut()
+            .find(|(pt, _, _)| **pt == **property_type)
+        {
+            Some((_, sum, count)) => {
+                *sum += value * parts as f64;
+                *count += 1;
+            }
+            None => self
+                .custom_sums
+                .push((Rc::clone(property_type), value * parts as f64, 1)),
+        }
+    }
+
+    /// Override the rules used to mix the properties (e.g. with a session's rules).
+    pub fn set_mix_rules(&mut self, mix_rules: &PropertyMixRules) {
+        self.mix_rules = mix_rules.clone();
+    }
+
+    pub fn add_paint(&mut self, paint: &SeriesPaint, parts: u64) {
+        self.components += 1;
+        self.total_parts += parts;
+        self.add_series_rules(paint.mix_rules());
+        for property in paint.recorded_properties() {
+            self.add_value(property.property_type(), property.ordinal(), parts);
+        }
+        for value in paint.custom_values() {
+            if value.property_type().is_ordinal() {
+                self.add_custom_value(value.property_type(), value.ordinal(), parts);
+            }
+        }
+    }
+
+    pub fn add_mixture(&mut self, properties: &MixedProperties, parts: u64) {
+        self.components += 1;
+        self.total_parts += parts;
+        for (property_type, value) in properties.values.iter() {
+            self.add_value(*property_type, *value, parts);
+        }
+        for (property_type, value) in properties.custom_values.iter() {
+            self.add_custom_value(property_type, *value, parts);
+        }
+    }
+
+    pub fn mixed_properties(&self) -> Option<MixedProperties> {
+        if self.total_parts == 0 {
+            return None;
+        }
+        let mut values: Vec<(PropertyType, f64)> = self
+            .values
+            .iter()
+            .filter(|(_, values)| values.len() == self.components)
+            .filter_map(|(pt, values)| Some((*pt, self.rule(*pt).mix(values)?)))
+            .collect();
+        values.sort_by_key(|(pt, _)| *pt);
+        let custom_values = self
+            .custom_sums
+            .iter()
+            .filter(|(_, _, count)| *count == self.components)
+            .map(|(pt, sum, _)| (Rc::clone(pt), sum / self.total_parts as f64))
+            .collect();
+        Some(MixedProperties {
+            values,
+            custom_values,
+        })
+    }
+}
+
 // TODO: make an untargeted version of TargetedMixture
 #[derive(Debug, Colour)]
 pub struct Mixture {
@@ -36,11 +192,10 @@ pub struct Mixture {
     id: String,
     name: String,
     notes: String,
-    finish: FuzzyProperty<Finish>,
-    transparency: FuzzyProperty<Transparency>,
-    permanence: FuzzyProperty<Permanence>,
-    fluorescence: FuzzyProperty<Fluorescence>,
-    metallicness: FuzzyProperty<Metallicness>,
+    properties: MixedProperties,
+    // the union of the components' pigments
+    pigments: Vec<Pigment>,
+    single_pigment: bool,
     components: Vec<(Paint, u64)>,
 }
 
@@ -83,6 +238,31 @@ impl Mixture {
     pub fn components(&self) -> impl Iterator<Item = &(Paint, u64)> {
         self.components.iter()
     }
+
+    /// Whether the mixture with `id` is used (directly or indirectly) as a component of
+    /// this mixture.
+    pub fn uses(&self, id: &str) -> bool {
+        self.path_to(id).is_some()
+    }
+
+    // The ids of the chain of mixed components leading to the mixture with `id`.
+    fn path_to(&self, id: &str) -> Option<Vec<String>> {
+        for (paint, _) in self.components.iter() {
+            if let Paint::Mixed(mixture) = paint {
+                if mixture.id == id {
+                    return Some(vec![mixture.id.clone()]);
+                } else if let Some(mut path) = mixture.path_to(id) {
+                    path.insert(0, mixture.id.clone());
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn properties(&self) -> &MixedProperties {
+        &self.properties
+    }
 }
 
 impl BasicPaintIfce for Mixture {
@@ -106,22 +286,24 @@ impl BasicPaintIfce for Mixture {
         }
     }
 
-    fn finish(&self) -> Finish { self.finish.property() }
+    fn recorded_properties(&self) -> Vec<Property> {
+        self.properties.values()
+    }
 
-    fn transparency(&self) -> Transparency {
-        self.transparency.property()
+    fn recorded_property(&self, property_type: PropertyType) -> Option<Property> {
+        self.properties.mixed_property(property_type)
     }
 
-    fn fluorescence(&self) -> Fluorescence {
-        self.fluorescence.property()
+    fn custom_values(&self) -> Vec<CustomPropertyValue> {
+        self.properties.custom_values()
     }
 
-    fn permanence(&self) -> Permanence {
-        self.permanence.property()
+    fn pigments(&self) -> &[Pigment] {
+        &self.pigments
     }
 
-    fn metallicness(&self) -> Metallicness {
-        self.metallicness.property()
+    fn is_single_pigment(&self) -> bool {
+        self.single_pigment
     }
 }
 
@@ -180,9 +362,19 @@ impl Ord for Mixture {
     }
 }
 
+/// What to do about the mixtures that use a mixture that is being edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dependents {
+    /// Refuse to make the edit.
+    Refuse,
+    /// Remix the dependent mixtures with the edited mixture.
+    Cascade,
+}
+
 #[derive(Debug, Default)]
 pub struct MixingSession {
     notes: String,
+    mix_rules: PropertyMixRules,
     mixtures: Vec<Rc<Mixture>>,
 }
 
@@ -199,6 +391,56 @@ impl MixingSession {
         self.notes = notes.to_string()
     }
 
+    /// The session's overrides of the series' (and default) property mixing rules.
+    pub fn mix_rules(&self) -> &PropertyMixRules {
+        &self.mix_rules
+    }
+
+    /// Change the session's property mixing rules and remix the session's mixtures.
+    pub fn set_mix_rules(&mut self, mix_rules: &PropertyMixRules) {
+        self.mix_rules = mix_rules.clone();
+        let mut remixed: HashMap<String, Rc<Mixture>> = HashMap::new();
+        let mixtures: Vec<Rc<Mixture>> = self
+            .mixtures
+            .iter()
+            .map(|mixture| self.remix(mixture, &mut remixed))
+            .collect();
+        self.mixtures = mixtures;
+    }
+
+    fn remix(
+        &self,
+        mixture: &Rc<Mixture>,
+        remixed: &mut HashMap<String, Rc<Mixture>>,
+    ) -> Rc<Mixture> {
+        if let Some(mixture) = remixed.get(mixture.id()) {
+            return Rc::clone(mixture);
+        }
+        let mut mixture_builder = MixtureBuilder::new(&mixture.id);
+        mixture_builder
+            .name(&mixture.name)
+            .notes(&mixture.notes)
+            .mix_rules(&self.mix_rules);
+        #[cfg(feature = "targeted_mixtures")]
+        if let Some(targeted_colour) = mixture.targeted_colour {
+            mixture_builder.targeted_colour(&targeted_colour);
+        }
+        for (paint, parts) in mixture.components.iter() {
+            match paint {
+                Paint::Series(paint) => {
+                    mixture_builder.series_paint_component((Rc::clone(paint), *parts));
+                }
+                Paint::Mixed(paint) => {
+                    let paint = self.remix(paint, remixed);
+                    mixture_builder.mixed_paint_component((paint, *parts));
+                }
+            }
+        }
+        let new_mixture = mixture_builder.build();
+        remixed.insert(mixture.id.to_string(), Rc::clone(&new_mixture));
+        new_mixture
+    }
+
     pub fn mixtures(&self) -> impl Iterator<Item = &Rc<Mixture>> {
         self.mixtures.iter()
     }
@@ -239,6 +481,193 @@ impl MixingSession {
         }
     }
 
+    pub fn remove_mixture(&mut self, id: &str) -> Option<Rc<Mixture>> {
+        debug_assert!(self.is_sorted_unique());
+        match self.mixtures.binary_search_by_key(&id, |p| p.id()) {
+            Ok(index) => Some(self.mixtures.remove(index)),
+            Err(_) => None,
+        }
+    }
+
+    /// The mixtures that use the mixture with `id` (directly or indirectly).
+    pub fn dependents(&self, id: &str) -> Vec<Rc<Mixture>> {
+        self.mixtures
+            .iter()
+            .filter(|mixture| mixture.uses(id))
+            .cloned()
+            .collect()
+    }
+
+    fn in_use_error(id: &str, users: &[Rc<Mixture>]) -> crate::Error {
+        let users = users.iter().map(|user| user.id().to_string()).collect();
+        crate::Error::MixtureInUse(id.to_string(), users)
+    }
+
+    /// Replace the session's mixture with the same id as `mixture` (e.g. to change its
+    /// name, notes or components).  If other mixtures use it they are either remixed or the
+    /// edit is refused according to `dependents`.  The (old, new) versions of the changed
+    /// mixtures are returned with the edited mixture first.
+    pub fn edit_mixture(
+        &mut self,
+        mixture: &Rc<Mixture>,
+        dependents: Dependents,
+    ) -> Result<Vec<(Rc<Mixture>, Rc<Mixture>)>, crate::Error> {
+        let id = mixture.id();
+        let old = match self.mixture(id) {
+            Some(old) => Rc::clone(old),
+            None => return Err(crate::Error::NotFound(id.to_string())),
+        };
+        if let Some(path) = mixture.path_to(id) {
+            let mut ids = vec![id.to_string()];
+            ids.extend(path);
+            return Err(crate::Error::CyclicMixtureDependency(ids));
+        }
+        let users = self.dependents(id);
+        if dependents == Dependents::Refuse && !users.is_empty() {
+            return Err(Self::in_use_error(id, &users));
+        }
+        let mut replacements: HashMap<String, Rc<Mixture>> = HashMap::new();
+        replacements.insert(id.to_string(), Rc::clone(mixture));
+        let mut changes = vec![(old, Rc::clone(mixture))];
+        for user in users {
+            let new = self.remix_with(&user, &mut replacements);
+            changes.push((user, new));
+        }
+        for (_, new) in changes.iter() {
+            self.add_mixture(new);
+        }
+        Ok(changes)
+    }
+
+    // Remix `mixture` if any of its components (directly or indirectly) have replacements.
+    fn remix_with(
+        &self,
+        mixture: &Rc<Mixture>,
+        replacements: &mut HashMap<String, Rc<Mixture>>,
+    ) -> Rc<Mixture> {
+        if let Some(replacement) = replacements.get(mixture.id()) {
+            return Rc::clone(replacement);
+        }
+        let mut changed = false;
+        let mut series_components = vec![];
+        let mut mixture_components = vec![];
+        for (paint, parts) in mixture.components.iter() {
+            match paint {
+                Paint::Series(paint) => series_components.push((Rc::clone(paint), *parts)),
+                Paint::Mixed(paint) => {
+                    let component = self.remix_with(paint, replacements);
+                    changed |= !Rc::ptr_eq(&component, paint);
+                    mixture_components.push((component, *parts));
+                }
+            }
+        }
+        if !changed {
+            return Rc::clone(mixture);
+        }
+        let mut mixture_builder = MixtureBuilder::new(&mixture.id);
+        mixture_builder
+            .name(&mixture.name)
+            .notes(&mixture.notes)
+            .mix_rules(&self.mix_rules)
+            .series_paint_components(series_components)
+            .mixed_paint_components(mixture_components);
+        #[cfg(feature = "targeted_mixtures")]
+        if let Some(targeted_colour) = mixture.targeted_colour {
+            mixture_builder.targeted_colour(&targeted_colour);
+        }
+        let new_mixture = mixture_builder.build();
+        replacements.insert(mixture.id.to_string(), Rc::clone(&new_mixture));
+        new_mixture
+    }
+
+    /// Remove the mixture with `id` unless other mixtures use it.
+    pub fn delete_mixture(&mut self, id: &str) -> Result<Rc<Mixture>, crate::Error> {
+        let users = self.dependents(id);
+        if !users.is_empty() {
+            return Err(Self::in_use_error(id, &users));
+        }
+        self.remove_mixture(id)
+            .ok_or_else(|| crate::Error::NotFound(id.to_string()))
+    }
+
+    /// Copy the mixtures with `ids` (and the mixtures that they use) from `other` into this
+    /// session.  They are given new ids supplied by `next_id` (skipping any already in use)
+    /// and remixed using this session's mixing rules.  The imported mixtures are returned
+    /// with their ids in `other` (mixtures before those that use them).  If `next_id` fails
+    /// to supply an unused id nothing is imported and `Error::DuplicateMixture` is returned.
+    pub fn import_mixtures(
+        &mut self,
+        other: &MixingSession,
+        ids: &[&str],
+        mut next_id: impl FnMut() -> String,
+    ) -> Result<Vec<(String, Rc<Mixture>)>, crate::Error> {
+        let mut mixtures = vec![];
+        for id in ids.iter() {
+            match other.mixture(id) {
+                Some(mixture) => mixtures.push(Rc::clone(mixture)),
+                None => return Err(crate::Error::NotFound(id.to_string())),
+            }
+        }
+        let mut imported: Vec<(String, Rc<Mixture>)> = vec![];
+        for mixture in mixtures.iter() {
+            if let Err(err) = self.import_mixture(mixture, &mut imported, &mut next_id) {
+                for (_, new_mixture) in imported.iter() {
+                    self.remove_mixture(new_mixture.id());
+                }
+                return Err(err);
+            }
+        }
+        Ok(imported)
+    }
+
+    fn import_mixture(
+        &mut self,
+        mixture: &Rc<Mixture>,
+        imported: &mut Vec<(String, Rc<Mixture>)>,
+        next_id: &mut impl FnMut() -> String,
+    ) -> Result<Rc<Mixture>, crate::Error> {
+        if let Some((_, new_mixture)) = imported.iter().find(|(id, _)| *id == mixture.id) {
+            return Ok(Rc::clone(new_mixture));
+        }
+        let mut series_components = vec![];
+        let mut mixture_components = vec![];
+        for (paint, parts) in mixture.components.iter() {
+            match paint {
+                Paint::Series(paint) => series_components.push((Rc::clone(paint), *parts)),
+                Paint::Mixed(paint) => {
+                    let component = self.import_mixture(paint, imported, next_id)?;
+                    mixture_components.push((component, *parts));
+                }
+            }
+        }
+        // NB: distinct ids can't all be in use so give up if they aren't distinct
+        let mut id = next_id();
+        for _ in 0..self.mixtures.len() {
+            if self.mixture(&id).is_none() {
+                break;
+            }
+            id = next_id();
+        }
+        if self.mixture(&id).is_some() {
+            return Err(crate::Error::DuplicateMixture(id));
+        }
+        let mut mixture_builder = MixtureBuilder::new(&id);
+        mixture_builder
+            .name(&mixture.name)
+            .notes(&mixture.notes)
+            .mix_rules(&self.mix_rules)
+            .series_paint_components(series_components)
+            .mixed_paint_components(mixture_components);
+        #[cfg(feature = "targeted_mixtures")]
+        if let Some(targeted_colour) = mixture.targeted_colour {
+            mixture_builder.targeted_colour(&targeted_colour);
+        }
+        let new_mixture = mixture_builder.build();
+        self.add_mixture(&new_mixture);
+        imported.push((mixture.id.clone(), Rc::clone(&new_mixture)));
+        Ok(new_mixture)
+    }
+
     pub fn mixture(&self, id: &str) -> Option<&Rc<Mixture>> {
         debug_assert!(self.is_sorted_unique());
         match self.mixtures.binary_search_by_key(&id, |p| p.id()) {
@@ -261,9 +690,21 @@ impl MixingSession {
     pub fn read<R: Read>(
         reader: &mut R,
         series_paint_finder: &Rc<impl SeriesPaintFinder>,
+    ) -> Result<Self, crate::Error> {
+        Self::read_with_fallback(reader, series_paint_finder, None)
+    }
+
+    /// Read a session using `fallback` (if any) to replace series paints that
+    /// `series_paint_finder` can't find.  If paints remain unresolved, all of them are
+    /// reported together in an `Error::UnresolvedPaints`.
+    pub fn read_with_fallback<R: Read>(
+        reader: &mut R,
+        series_paint_finder: &Rc<impl SeriesPaintFinder>,
+        fallback: Option<&dyn SeriesPaintFallback>,
     ) -> Result<Self, crate::Error> {
         let saved_session = SaveableMixingSession::read(reader)?;
-        let mixing_session = saved_session.mixing_session(series_paint_finder)?;
+        let mixing_session =
+            saved_session.mixing_session_with_fallback(series_paint_finder, fallback)?;
         Ok(mixing_session)
     }
 }
@@ -285,6 +726,7 @@ pub struct MixtureBuilder {
     notes: String,
     series_components: Vec<(Rc<SeriesPaint>, u64)>,
     mixture_components: Vec<(Rc<Mixture>, u64)>,
+    mix_rules: PropertyMixRules,
     #[cfg(feature = "targeted_mixtures")]
     targeted_colour: Option<HCV>,
 }
@@ -297,6 +739,7 @@ impl MixtureBuilder {
             notes: String::new(),
             series_components: vec![],
             mixture_components: vec![],
+            mix_rules: PropertyMixRules::default(),
             #[cfg(feature = "targeted_mixtures")]
             targeted_colour: None,
         }
@@ -341,6 +784,12 @@ impl MixtureBuilder {
         self
     }
 
+    /// Override the series' (and default) rules for mixing the components' properties.
+    pub fn mix_rules(&mut self, mix_rules: &PropertyMixRules) -> &mut Self {
+        self.mix_rules = mix_rules.clone();
+        self
+    }
+
     pub fn build(&self) -> Rc<Mixture> {
         debug_assert!((self.series_components.len() + self.mixture_components.len()) > 0);
         let mut gcd: u64 = 0;
@@ -354,32 +803,30 @@ impl MixtureBuilder {
         }
         debug_assert!(gcd > 0);
         let mut components = vec![];
-        let mut finish_mix = PropertyMixer::<Finish>::new();
-        let mut transparency_mix = PropertyMixer::<Transparency>::new();
-        let mut permanence_mix = PropertyMixer::<Permanence>::new();
-        let mut fluorescence_mix = PropertyMixer::<Fluorescence>::new();
-        let mut metallicness_mix = PropertyMixer::<Metallicness>::new();
+        let mut pigments: Vec<Pigment> = vec![];
+        let mut properties_mix = PropertiesMixer::default();
+        properties_mix.set_mix_rules(&self.mix_rules);
         let mut colour_mix = SubtractiveMixer::new();
         for (paint, parts) in self.series_components.iter() {
             let adjusted_parts = *parts / gcd;
             colour_mix.add(&paint.hcv(), adjusted_parts);
-            finish_mix.add(paint.finish(), adjusted_parts);
-            transparency_mix.add(paint.transparency(), adjusted_parts);
-            permanence_mix.add(paint.permanence(), adjusted_parts);
-            fluorescence_mix.add(paint.fluorescence(), adjusted_parts);
-            metallicness_mix.add(paint.metallicness(), adjusted_parts);
+            properties_mix.add_paint(paint, adjusted_parts);
+            pigments.extend(paint.pigments().iter().cloned());
             components.push((Paint::Series(Rc::clone(paint)), adjusted_parts));
         }
         for (paint, parts) in self.mixture_components.iter() {
             let adjusted_parts = *parts / gcd;
             colour_mix.add(&paint.hcv(), adjusted_parts);
-            finish_mix.add_value(paint.finish, adjusted_parts);
-            transparency_mix.add_value(paint.transparency, adjusted_parts);
-            permanence_mix.add_value(paint.permanence, adjusted_parts);
-            fluorescence_mix.add_value(paint.fluorescence, adjusted_parts);
-            metallicness_mix.add_value(paint.metallicness, adjusted_parts);
+            properties_mix.add_mixture(&paint.properties, adjusted_parts);
+            pigments.extend(paint.pigments().iter().cloned());
             components.push((Paint::Mixed(Rc::clone(paint)), adjusted_parts));
         }
+        pigments.sort();
+        pigments.dedup();
+        let single_pigment = pigments.len() == 1
+            && components
+                .iter()
+                .all(|(paint, _)| paint.is_single_pigment());
         let mp = Mixture {
             colour: colour_mix.mixed_colour().unwrap(),
             #[cfg(feature = "targeted_mixtures")]
@@ -387,19 +834,9 @@ impl MixtureBuilder {
             id: self.id.clone(),
             name: self.name.clone(),
             notes: self.notes.clone(),
-            finish: finish_mix.property_value().expect("programmer error"),
-            transparency: transparency_mix
-                .property_value()
-                .expect("programmer error"),
-            permanence: permanence_mix
-                .property_value()
-                .expect("programmer error"),
-            fluorescence: fluorescence_mix
-                .property_value()
-                .expect("programmer error"),
-            metallicness: metallicness_mix
-                .property_value()
-                .expect("programmer error"),
+            properties: properties_mix.mixed_properties().expect("programmer error"),
+            pigments,
+            single_pigment,
             components,
         };
         Rc::new(mp)
@@ -552,38 +989,45 @@ impl BasicPaintIfce for Paint {
         }
     }
 
-    fn finish(&self) -> Finish {
+    fn recorded_properties(&self) -> Vec<Property> {
         match self {
-            Paint::Series(paint) => paint.finish(),
-            Paint::Mixed(paint) => paint.finish(),
+            Paint::Series(paint) => paint.recorded_properties(),
+            Paint::Mixed(paint) => paint.recorded_properties(),
         }
     }
 
-    fn transparency(&self) -> Transparency {
+    fn recorded_property(&self, property_type: PropertyType) -> Option<Property> {
         match self {
-            Paint::Series(paint) => paint.transparency(),
-            Paint::Mixed(paint) => paint.transparency(),
+            Paint::Series(paint) => paint.recorded_property(property_type),
+            Paint::Mixed(paint) => paint.recorded_property(property_type),
         }
     }
 
-    fn fluorescence(&self) -> Fluorescence {
+    fn custom_values(&self) -> Vec<CustomPropertyValue> {
         match self {
-            Paint::Series(paint) => paint.fluorescence(),
-            Paint::Mixed(paint) => paint.fluorescence(),
+            Paint::Series(paint) => paint.custom_values(),
+            Paint::Mixed(paint) => paint.custom_values(),
         }
     }
 
-    fn permanence(&self) -> Permanence {
+    fn pigments(&self) -> &[Pigment] {
         match self {
-            Paint::Series(paint) => paint.permanence(),
-            Paint::Mixed(paint) => paint.permanence(),
+            Paint::Series(paint) => paint.pigments(),
+            Paint::Mixed(paint) => paint.pigments(),
         }
     }
 
-    fn metallicness(&self) -> Metallicness {
+    fn is_single_pigment(&self) -> bool {
         match self {
-            Paint::Series(paint) => paint.metallicness(),
-            Paint::Mixed(paint) => paint.metallicness(),
+            Paint::Series(paint) => paint.is_single_pigment(),
+            Paint::Mixed(paint) => paint.is_single_pigment(),
+        }
+    }
+
+    fn equivalents(&self) -> &[Equivalent] {
+        match self {
+            Paint::Series(paint) => paint.equivalents(),
+            Paint::Mixed(paint) => paint.equivalents(),
         }
     }
 }
@@ -643,32 +1087,345 @@ impl From<&Rc<Mixture>> for SaveableMixture {
     }
 }
 
+/// The series paints referenced by a saved session that could not be found.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UnresolvedPaints {
+    unknown_series: Vec<SeriesId>,
+    unknown_paints: Vec<(SeriesId, String)>,
+}
+
+impl UnresolvedPaints {
+    pub fn is_empty(&self) -> bool {
+        self.unknown_paints.is_empty()
+    }
+
+    /// The series that aren't loaded at all.
+    pub fn unknown_series(&self) -> impl Iterator<Item = &SeriesId> {
+        self.unknown_series.iter()
+    }
+
+    /// Every paint that could not be found (including those in unknown series).
+    pub fn unknown_paints(&self) -> impl Iterator<Item = &(SeriesId, String)> {
+        self.unknown_paints.iter()
+    }
+
+    fn contains_paint(&self, series_id: &SeriesId, paint_id: &str) -> bool {
+        self.unknown_paints
+            .binary_search_by(|(sid, id)| (sid, id.as_str()).cmp(&(series_id, paint_id)))
+            .is_ok()
+    }
+
+    fn add_unknown_series(&mut self, series_id: &SeriesId) {
+        if let Err(index) = self.unknown_series.binary_search(series_id) {
+            self.unknown_series.insert(index, series_id.clone());
+        }
+    }
+
+    fn add_unknown_paint(&mut self, series_id: &SeriesId, paint_id: &str) {
+        if let Err(index) = self
+            .unknown_paints
+            .binary_search_by(|(sid, id)| (sid, id.as_str()).cmp(&(series_id, paint_id)))
+        {
+            self.unknown_paints
+                .insert(index, (series_id.clone(), paint_id.to_string()));
+        }
+    }
+}
+
+impl fmt::Display for UnresolvedPaints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} unresolved paint(s):", self.unknown_paints.len())?;
+        for series_id in self.unknown_series.iter() {
+            write!(f, "\n{series_id}: unknown paint series")?;
+        }
+        for (series_id, id) in self.unknown_paints.iter() {
+            write!(f, "\n{id}:({series_id}): unknown paint")?;
+        }
+        Ok(())
+    }
+}
+
+/// A source of replacements for series paints that can't be found when a saved
+/// session is loaded.  `colour` is the missing paint's colour when the session was
+/// saved if the session recorded it.
+pub trait SeriesPaintFallback {
+    fn fallback_paint(
+        &self,
+        series_id: &SeriesId,
+        paint_id: &str,
+        colour: Option<&HCV>,
+    ) -> Option<Rc<SeriesPaint>>;
+}
+
+/// Explicit replacements for individual missing series paints.
+#[derive(Debug, Default)]
+pub struct PaintSubstitutions {
+    substitutions: HashMap<(SeriesId, String), Rc<SeriesPaint>>,
+}
+
+impl PaintSubstitutions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.substitutions.is_empty()
+    }
+
+    pub fn insert(
+        &mut self,
+        series_id: &SeriesId,
+        paint_id: &str,
+        paint: &Rc<SeriesPaint>,
+    ) -> Option<Rc<SeriesPaint>> {
+        self.substitutions
+            .insert((series_id.clone(), paint_id.to_string()), Rc::clone(paint))
+    }
+}
+
+impl SeriesPaintFallback for PaintSubstitutions {
+    fn fallback_paint(
+        &self,
+        series_id: &SeriesId,
+        paint_id: &str,
+        _colour: Option<&HCV>,
+    ) -> Option<Rc<SeriesPaint>> {
+        self.substitutions
+            .get(&(series_id.clone(), paint_id.to_string()))
+            .map(Rc::clone)
+    }
+}
+
+/// Replace missing series paints with the candidate paint whose colour is nearest to
+/// the colour recorded for the missing paint.
+#[derive(Debug, Default)]
+pub struct NearestColourFallback {
+    paints: Vec<Rc<SeriesPaint>>,
+}
+
+impl NearestColourFallback {
+    pub fn new(paints: impl IntoIterator<Item = Rc<SeriesPaint>>) -> Self {
+        Self {
+            paints: paints.into_iter().collect(),
+        }
+    }
+}
+
+impl SeriesPaintFallback for NearestColourFallback {
+    fn fallback_paint(
+        &self,
+        _series_id: &SeriesId,
+        _paint_id: &str,
+        colour: Option<&HCV>,
+    ) -> Option<Rc<SeriesPaint>> {
+        nearest(colour?, self.paints.iter().map(Rc::clone)).map(|(paint, _)| paint)
+    }
+}
+
+/// Try the first fallback and then the second.
+impl<A: SeriesPaintFallback, B: SeriesPaintFallback> SeriesPaintFallback for (A, B) {
+    fn fallback_paint(
+        &self,
+        series_id: &SeriesId,
+        paint_id: &str,
+        colour: Option<&HCV>,
+    ) -> Option<Rc<SeriesPaint>> {
+        self.0
+            .fallback_paint(series_id, paint_id, colour)
+            .or_else(|| self.1.fallback_paint(series_id, paint_id, colour))
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SaveableMixingSession {
     notes: String,
+    #[serde(default, skip_serializing_if = "PropertyMixRules::is_empty")]
+    mix_rules: PropertyMixRules,
     mixtures: Vec<SaveableMixture>,
+    #[serde(default)]
+    series_paint_colours: Vec<(SeriesId, String, HCV)>,
 }
 
 impl From<&MixingSession> for SaveableMixingSession {
     fn from(session: &MixingSession) -> Self {
         let mixtures = session.mixtures.iter().map(SaveableMixture::from).collect();
+        let mut series_paint_colours: Vec<(SeriesId, String, HCV)> = vec![];
+        for mixture in session.mixtures.iter() {
+            for (paint, _) in mixture.components.iter() {
+                if let Paint::Series(paint) = paint {
+                    let key = (paint.series_id().as_ref(), paint.id());
+                    if let Err(index) = series_paint_colours
+                        .binary_search_by(|(sid, id, _)| (sid, id.as_str()).cmp(&key))
+                    {
+                        series_paint_colours.insert(
+                            index,
+                            (
+                                paint.series_id().into(),
+                                paint.id().to_string(),
+                                paint.hcv(),
+                            ),
+                        );
+                    }
+                }
+            }
+        }
         Self {
             notes: session.notes.to_string(),
+            mix_rules: session.mix_rules.clone(),
             mixtures,
+            series_paint_colours,
         }
     }
 }
 
 impl SaveableMixingSession {
+    /// Work out an order in which the saved mixtures can be built so that every mixture
+    /// appears after all of the mixtures that it uses as components.
+    fn resolution_order(&self) -> Result<Vec<usize>, crate::Error> {
+        let mut index_of: HashMap<&str, usize> = HashMap::new();
+        for (index, saved_mixture) in self.mixtures.iter().enumerate() {
+            if index_of.insert(saved_mixture.id.as_str(), index).is_some() {
+                return Err(crate::Error::DuplicateMixture(saved_mixture.id.to_string()));
+            }
+        }
+        let mut dependencies: Vec<Vec<usize>> = vec![vec![]; self.mixtures.len()];
+        let mut dependents: Vec<Vec<usize>> = vec![vec![]; self.mixtures.len()];
+        for (index, saved_mixture) in self.mixtures.iter().enumerate() {
+            for (saved_paint, _) in saved_mixture.components.iter() {
+                if let SaveablePaint::Mixed(id) = saved_paint {
+                    match index_of.get(id.as_str()) {
+                        Some(&dependency) => {
+                            dependencies[index].push(dependency);
+                            dependents[dependency].push(index);
+                        }
+                        None => return Err(crate::Error::NotFound(id.to_string())),
+                    }
+                }
+            }
+        }
+        let mut n_unresolved: Vec<usize> = dependencies.iter().map(|d| d.len()).collect();
+        let mut ready: VecDeque<usize> = (0..self.mixtures.len())
+            .filter(|index| n_unresolved[*index] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.mixtures.len());
+        while let Some(index) = ready.pop_front() {
+            order.push(index);
+            for dependent in dependents[index].iter() {
+                n_unresolved[*dependent] -= 1;
+                if n_unresolved[*dependent] == 0 {
+                    ready.push_back(*dependent);
+                }
+            }
+        }
+        if order.len() < self.mixtures.len() {
+            // Every unresolved mixture has at least one unresolved dependency so following
+            // them from any unresolved mixture must eventually lead us around a cycle.
+            let start = (0..self.mixtures.len())
+                .find(|index| n_unresolved[*index] > 0)
+                .expect("at least one mixture is unresolved");
+            let mut path = vec![start];
+            let mut current = start;
+            loop {
+                current = *dependencies[current]
+                    .iter()
+                    .find(|dependency| n_unresolved[**dependency] > 0)
+                    .expect("unresolved mixtures have unresolved dependencies");
+                if let Some(posn) = path.iter().position(|index| *index == current) {
+                    let cycle = path[posn..]
+                        .iter()
+                        .chain(std::iter::once(&current))
+                        .map(|index| self.mixtures[*index].id.to_string())
+                        .collect();
+                    return Err(crate::Error::CyclicMixtureDependency(cycle));
+                }
+                path.push(current);
+            }
+        }
+        Ok(order)
+    }
+
+    fn saved_colour(&self, series_id: &SeriesId, paint_id: &str) -> Option<&HCV> {
+        self.series_paint_colours
+            .iter()
+            .find(|(sid, id, _)| sid == series_id && id == paint_id)
+            .map(|(_, _, colour)| colour)
+    }
+
+    /// Find all of the series paints used by the saved mixtures, reporting every one
+    /// that can be neither found nor replaced in a single error.
+    fn resolve_series_paints(
+        &self,
+        series_paint_finder: &Rc<impl SeriesPaintFinder>,
+        fallback: Option<&dyn SeriesPaintFallback>,
+    ) -> Result<HashMap<(SeriesId, String), Rc<SeriesPaint>>, crate::Error> {
+        let mut series_paints = HashMap::new();
+        let mut unresolved = UnresolvedPaints::default();
+        for saved_mixture in self.mixtures.iter() {
+            for (saved_paint, _) in saved_mixture.components.iter() {
+                if let SaveablePaint::Series(series_id, id) = saved_paint {
+                    let key = (series_id.clone(), id.to_string());
+                    if series_paints.contains_key(&key) || unresolved.contains_paint(series_id, id)
+                    {
+                        continue;
+                    }
+                    match series_paint_finder.get_series_paint(id, Some(series_id)) {
+                        Ok(paint) => {
+                            series_paints.insert(key, paint);
+                        }
+                        Err(err) => {
+                            let colour = self.saved_colour(series_id, id);
+                            if let Some(paint) = fallback
+                                .and_then(|fallback| fallback.fallback_paint(series_id, id, colour))
+                            {
+                                series_paints.insert(key, paint);
+                                continue;
+                            }
+                            match err {
+                                crate::Error::UnknownSeries(_) => {
+                                    unresolved.add_unknown_series(series_id)
+                                }
+                                crate::Error::UnknownSeriesPaint(_, _)
+                                | crate::Error::NotFound(_) => (),
+                                err => return Err(err),
+                            }
+                            unresolved.add_unknown_paint(series_id, id);
+                        }
+                    }
+                }
+            }
+        }
+        if unresolved.is_empty() {
+            Ok(series_paints)
+        } else {
+            Err(crate::Error::UnresolvedPaints(unresolved))
+        }
+    }
+
     pub fn mixing_session(
         &self,
         series_paint_finder: &Rc<impl SeriesPaintFinder>,
     ) -> Result<MixingSession, crate::Error> {
-        let mut mixtures: Vec<Rc<Mixture>> = vec![];
-        for saved_mixture in self.mixtures.iter() {
+        self.mixing_session_with_fallback(series_paint_finder, None)
+    }
+
+    pub fn mixing_session_with_fallback(
+        &self,
+        series_paint_finder: &Rc<impl SeriesPaintFinder>,
+        fallback: Option<&dyn SeriesPaintFallback>,
+    ) -> Result<MixingSession, crate::Error> {
+        let order = self.resolution_order()?;
+        let series_paints = self.resolve_series_paints(series_paint_finder, fallback)?;
+        let mut built: Vec<Option<Rc<Mixture>>> = vec![None; self.mixtures.len()];
+        let mut index_of: HashMap<&str, usize> = HashMap::new();
+        for (index, saved_mixture) in self.mixtures.iter().enumerate() {
+            index_of.insert(saved_mixture.id.as_str(), index);
+        }
+        for index in order {
+            let saved_mixture = &self.mixtures[index];
             let mut mixture_builder = MixtureBuilder::new(&saved_mixture.id);
             mixture_builder.name(&saved_mixture.name);
             mixture_builder.notes(&saved_mixture.notes);
+            mixture_builder.mix_rules(&self.mix_rules);
             #[cfg(feature = "targeted_mixtures")]
             if let Some(targeted_colour) = saved_mixture.targeted_colour {
                 mixture_builder.targeted_colour(&targeted_colour);
@@ -676,26 +1433,29 @@ impl SaveableMixingSession {
             for saved_component in saved_mixture.components.iter() {
                 match &saved_component.0 {
                     SaveablePaint::Series(series_id, id) => {
-                        let paint = series_paint_finder.get_series_paint(id, Some(series_id))?;
-                        mixture_builder.series_paint_component((paint, saved_component.1));
+                        let paint = series_paints
+                            .get(&(series_id.clone(), id.to_string()))
+                            .expect("all series paints were resolved");
+                        mixture_builder
+                            .series_paint_component((Rc::clone(paint), saved_component.1));
                     }
                     SaveablePaint::Mixed(id) => {
-                        match mixtures.binary_search_by_key(&id.as_str(), |p| p.id()) {
-                            Ok(index) => {
-                                let paint = mixtures.get(index).expect("binary searched index");
-                                mixture_builder
-                                    .mixed_paint_component((Rc::clone(paint), saved_component.1));
-                            }
-                            Err(_) => return Err(crate::Error::NotFound(id.to_string())),
-                        }
+                        let paint = index_of
+                            .get(id.as_str())
+                            .and_then(|index| built[*index].as_ref())
+                            .expect("resolution order guarantees components are built first");
+                        mixture_builder
+                            .mixed_paint_component((Rc::clone(paint), saved_component.1));
                     }
                 }
             }
-            let mixture = mixture_builder.build();
-            mixtures.push(mixture);
+            built[index] = Some(mixture_builder.build());
         }
+        let mut mixtures: Vec<Rc<Mixture>> = built.into_iter().flatten().collect();
+        mixtures.sort_by(|a, b| a.id.cmp(&b.id));
         Ok(MixingSession {
             notes: self.notes.to_string(),
+            mix_rules: self.mix_rules.clone(),
             mixtures,
         })
     }
@@ -732,19 +1492,150 @@ impl SaveableMixingSession {
 mod test {
     use std::rc::Rc;
 
-    use crate::mixtures::{MixingSession, MixtureBuilder};
-    use crate::series::{BasicPaintSpec, SeriesPaintSeries, SeriesPaintSeriesSpec};
-    use colour_math::{HueConstants, HCV, RGB};
+    use crate::mixtures::{
+        Dependents, MixingSession, Mixture, MixtureBuilder, NearestColourFallback,
+        PaintSubstitutions,
+    };
+    use crate::pigments::extract_pigments;
+    use crate::properties::custom::CustomPropertyType;
+    use crate::properties::{
+        Finish, Fluorescence, Granulation, LightFastness, Permanence, Property, PropertyMixRule,
+        PropertyMixRules, PropertyType, Staining, Transparency,
+    };
+    use crate::series::{test_series_spec, BasicPaintSpec, SeriesPaintSeries};
+    use crate::BasicPaintIfce;
+    use colour_math::{ColourBasics, HueConstants, HCV};
+
+    fn test_series() -> Rc<SeriesPaintSeries> {
+        Rc::new(crate::series::test_series(&[
+            (HCV::RED, "red"),
+            (HCV::YELLOW, "yellow"),
+        ]))
+    }
+
+    fn watercolour_series() -> Rc<SeriesPaintSeries> {
+        let mut series_spec = test_series_spec("watercolours");
+        series_spec.set_property_types(&PropertyType::WATERCOLOUR);
+        let mut red = BasicPaintSpec::new(&HCV::RED, "red");
+        red.set_property(Property::Transparency(Transparency::Opaque));
+        red.set_property(Property::LightFastness(LightFastness::Excellent));
+        red.set_property(Property::Staining(Staining::HighStaining));
+        red.set_property(Property::Granulation(Granulation::Granulating));
+        series_spec.add(&red);
+        let mut yellow = BasicPaintSpec::new(&HCV::YELLOW, "yellow");
+        yellow.set_property(Property::Transparency(Transparency::Clear));
+        yellow.set_property(Property::LightFastness(LightFastness::Fair));
+        yellow.set_property(Property::Staining(Staining::NonStaining));
+        yellow.set_property(Property::Granulation(Granulation::NonGranulating));
+        series_spec.add(&yellow);
+        Rc::new(SeriesPaintSeries::from(&series_spec))
+    }
+
+    const OUT_OF_ORDER_SESSION: &str = r###"{
+  "notes": "mixtures saved in an arbitrary order",
+  "mixtures": [
+    {
+      "id": "#002",
+      "name": "dark orange",
+      "notes": "",
+      "components": [
+        [{"Mixed": "#001"}, 2],
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "red"]}, 1]
+      ]
+    },
+    {
+      "id": "#001",
+      "name": "orange",
+      "notes": "",
+      "components": [
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "red"]}, 1],
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "yellow"]}, 1]
+      ]
+    },
+    {
+      "id": "#000",
+      "name": "darker orange",
+      "notes": "",
+      "components": [
+        [{"Mixed": "#002"}, 1],
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "red"]}, 1]
+      ]
+    }
+  ]
+}"###;
+
+    const UNKNOWN_PAINTS_SESSION: &str = r###"{
+  "notes": "mixtures using paints that aren't available",
+  "mixtures": [
+    {
+      "id": "#001",
+      "name": "",
+      "notes": "",
+      "components": [
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "blue"]}, 1],
+        [{"Series": [{"proprietor": "other owner", "series_name": "other series"}, "umber"]}, 1]
+      ]
+    },
+    {
+      "id": "#002",
+      "name": "",
+      "notes": "",
+      "components": [
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "green"]}, 1],
+        [{"Series": [{"proprietor": "other owner", "series_name": "other series"}, "umber"]}, 1],
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "red"]}, 1]
+      ]
+    }
+  ]
+}"###;
+
+    const CYCLIC_SESSION: &str = r###"{
+  "notes": "mixtures that depend on each other",
+  "mixtures": [
+    {
+      "id": "#001",
+      "name": "",
+      "notes": "",
+      "components": [
+        [{"Mixed": "#002"}, 1],
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "red"]}, 1]
+      ]
+    },
+    {
+      "id": "#002",
+      "name": "",
+      "notes": "",
+      "components": [[{"Mixed": "#001"}, 1]]
+    }
+  ]
+}"###;
+
+    const DUPLICATE_IDS_SESSION: &str = r###"{
+  "notes": "two mixtures with the same id",
+  "mixtures": [
+    {
+      "id": "#001",
+      "name": "orange",
+      "notes": "",
+      "components": [
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "red"]}, 1],
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "yellow"]}, 1]
+      ]
+    },
+    {
+      "id": "#001",
+      "name": "red",
+      "notes": "",
+      "components": [
+        [{"Series": [{"proprietor": "owner", "series_name": "series name"}, "red"]}, 1]
+      ]
+    }
+  ]
+}"###;
 
     #[test]
     fn save_and_recover() {
-        let mut series_spec = SeriesPaintSeriesSpec::default();
-        series_spec.set_proprietor("owner");
-        series_spec.set_series_name("series name");
-        assert!(series_spec.paints().next().is_none());
-        series_spec.add(&BasicPaintSpec::new(&RGB::<f64>::RED, "red"));
-        series_spec.add(&BasicPaintSpec::new(&HCV::YELLOW, "yellow"));
-        let series = Rc::new(SeriesPaintSeries::from(&series_spec));
+        let series = test_series();
         let mut session = MixingSession::new();
         session.set_notes("a test mixing session");
         let red = series.find("red").unwrap();
@@ -765,4 +1656,376 @@ mod test {
             assert_eq!(mix1, mix2);
         }
     }
+
+    #[test]
+    fn load_out_of_order() {
+        let series = test_series();
+        let session = MixingSession::read(&mut OUT_OF_ORDER_SESSION.as_bytes(), &series).unwrap();
+        assert!(session.is_sorted_unique());
+        let ids: Vec<&str> = session.mixtures().map(|m| m.id()).collect();
+        assert_eq!(ids, vec!["#000", "#001", "#002"]);
+        let orange = session.mixture("#001").unwrap();
+        let dark_orange = session.mixture("#002").unwrap();
+        assert!(dark_orange
+            .components()
+            .any(|(paint, parts)| paint.id() == orange.id() && *parts == 2));
+    }
+
+    #[test]
+    fn reject_cyclic_dependencies() {
+        let series = test_series();
+        match MixingSession::read(&mut CYCLIC_SESSION.as_bytes(), &series) {
+            Err(crate::Error::CyclicMixtureDependency(ids)) => {
+                assert_eq!(ids.len(), 3);
+                assert_eq!(ids.first(), ids.last());
+                assert!(ids.contains(&"#001".to_string()));
+                assert!(ids.contains(&"#002".to_string()));
+            }
+            _ => panic!("cyclic dependency not detected"),
+        }
+    }
+
+    #[test]
+    fn reject_duplicate_mixture_ids() {
+        let series = test_series();
+        match MixingSession::read(&mut DUPLICATE_IDS_SESSION.as_bytes(), &series) {
+            Err(crate::Error::DuplicateMixture(id)) => assert_eq!(id, "#001"),
+            _ => panic!("duplicate mixture id not detected"),
+        }
+    }
+
+    #[test]
+    fn report_all_unresolved_paints() {
+        let series = test_series();
+        let unresolved = match MixingSession::read(&mut UNKNOWN_PAINTS_SESSION.as_bytes(), &series)
+        {
+            Err(crate::Error::UnresolvedPaints(unresolved)) => unresolved,
+            _ => panic!("unresolved paints not reported"),
+        };
+        assert_eq!(unresolved.unknown_series().count(), 1);
+        let ids: Vec<&str> = unresolved
+            .unknown_paints()
+            .map(|(_, id)| id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["umber", "blue", "green"]);
+
+        let red = series.find("red").unwrap();
+        let mut substitutions = PaintSubstitutions::new();
+        for (series_id, id) in unresolved.unknown_paints() {
+            substitutions.insert(series_id, id, red);
+        }
+        let session = MixingSession::read_with_fallback(
+            &mut UNKNOWN_PAINTS_SESSION.as_bytes(),
+            &series,
+            Some(&substitutions),
+        )
+        .unwrap();
+        for mixture in session.mixtures() {
+            assert!(mixture.components().all(|(paint, _)| paint.id() == "red"));
+        }
+    }
+
+    #[test]
+    fn nearest_colour_fallback() {
+        let series = test_series();
+        let mut other_spec = test_series_spec("other series");
+        other_spec.add(&BasicPaintSpec::new(&HCV::YELLOW, "yellowish"));
+        let other_series = SeriesPaintSeries::from(&other_spec);
+        let mix = vec![
+            (Rc::clone(other_series.find("yellowish").unwrap()), 1),
+            (Rc::clone(series.find("red").unwrap()), 1),
+        ];
+        let mut session = MixingSession::new();
+        session.add_mixture(
+            &MixtureBuilder::new("#001")
+                .series_paint_components(mix)
+                .build(),
+        );
+        let mut buffer: Vec<u8> = vec![];
+        session.write(&mut buffer).unwrap();
+        assert!(MixingSession::read(&mut &buffer[..], &series).is_err());
+        let fallback = NearestColourFallback::new(series.paints().cloned());
+        let read_session =
+            MixingSession::read_with_fallback(&mut &buffer[..], &series, Some(&fallback)).unwrap();
+        let mixture = read_session.mixture("#001").unwrap();
+        let ids: Vec<&str> = mixture.components().map(|(paint, _)| paint.id()).collect();
+        assert_eq!(ids, vec!["yellow", "red"]);
+    }
+
+    #[test]
+    fn mix_watercolours() {
+        let series = watercolour_series();
+        let red = series.find("red").unwrap();
+        let yellow = series.find("yellow").unwrap();
+        let orange = MixtureBuilder::new("#001")
+            .series_paint_component((Rc::clone(red), 1))
+            .series_paint_component((Rc::clone(yellow), 1))
+            .build();
+        assert_eq!(orange.transparency(), Transparency::SemiTransparent);
+        assert_eq!(orange.light_fastness(), LightFastness::Fair);
+        assert_eq!(orange.granulation(), Granulation::SomeGranulation);
+        assert_eq!(orange.recorded_property(PropertyType::Finish), None);
+        let reddish = MixtureBuilder::new("#002")
+            .mixed_paint_component((Rc::clone(&orange), 1))
+            .series_paint_component((Rc::clone(red), 2))
+            .build();
+        assert_eq!(reddish.staining(), Staining::HighStaining);
+        assert_eq!(reddish.granulation(), Granulation::Granulating);
+
+        let mut session = MixingSession::new();
+        session.add_mixture(&orange);
+        session.add_mixture(&reddish);
+        let mut buffer: Vec<u8> = vec![];
+        session.write(&mut buffer).unwrap();
+        let read_session = MixingSession::read(&mut &buffer[..], &series).unwrap();
+        assert_eq!(read_session.mixtures().count(), 2);
+        for (mix1, mix2) in session.mixtures().zip(read_session.mixtures()) {
+            assert_eq!(mix1.id(), mix2.id());
+            assert_eq!(mix1.recorded_properties(), mix2.recorded_properties());
+        }
+    }
+
+    #[test]
+    fn mix_with_rules() {
+        let mut series_spec = test_series_spec("inks");
+        let mut pink = BasicPaintSpec::new(&HCV::RED, "pink");
+        pink.set_property(Property::Fluorescence(Fluorescence::Fluorescent));
+        pink.set_property(Property::Permanence(Permanence::Fugitive));
+        series_spec.add(&pink);
+        series_spec.add(&BasicPaintSpec::new(&HCV::YELLOW, "yellow"));
+        let series = Rc::new(SeriesPaintSeries::from(&series_spec));
+        let mut mixture_builder = MixtureBuilder::new("#001");
+        mixture_builder
+            .series_paint_component((Rc::clone(series.find("pink").unwrap()), 1))
+            .series_paint_component((Rc::clone(series.find("yellow").unwrap()), 3));
+        let mixture = mixture_builder.build();
+        assert_eq!(mixture.fluorescence(), Fluorescence::NonFluorescent);
+        assert_eq!(mixture.permanence(), Permanence::Fugitive);
+
+        let mut mix_rules = PropertyMixRules::new();
+        mix_rules.set_rule(PropertyType::Fluorescence, PropertyMixRule::BestCase);
+        series_spec.set_mix_rules(&mix_rules);
+        let series = Rc::new(SeriesPaintSeries::from(&series_spec));
+        let mixture = MixtureBuilder::new("#001")
+            .series_paint_component((Rc::clone(series.find("pink").unwrap()), 1))
+            .series_paint_component((Rc::clone(series.find("yellow").unwrap()), 3))
+            .build();
+        assert_eq!(mixture.fluorescence(), Fluorescence::Fluorescent);
+
+        let mut session = MixingSession::new();
+        session.add_mixture(&mixture);
+        let mut mix_rules = PropertyMixRules::new();
+        mix_rules.set_rule(PropertyType::Fluorescence, PropertyMixRule::WorstCase);
+        mix_rules.set_rule(PropertyType::Permanence, PropertyMixRule::BestCase);
+        session.set_mix_rules(&mix_rules);
+        let mixture = session.mixture("#001").unwrap();
+        assert_eq!(mixture.fluorescence(), Fluorescence::NonFluorescent);
+        assert_eq!(mixture.permanence(), Permanence::Permanent);
+
+        let mut buffer: Vec<u8> = vec![];
+        session.write(&mut buffer).unwrap();
+        let read_session = MixingSession::read(&mut &buffer[..], &series).unwrap();
+        assert_eq!(read_session.mix_rules(), &mix_rules);
+        let mixture = read_session.mixture("#001").unwrap();
+        assert_eq!(mixture.permanence(), Permanence::Permanent);
+    }
+
+    #[test]
+    fn mix_across_property_sets() {
+        let watercolours = watercolour_series();
+        let mut series_spec = test_series_spec("enamels");
+        let mut blue = BasicPaintSpec::new(&HCV::BLUE, "blue");
+        blue.set_property(Property::Finish(Finish::Flat));
+        blue.set_property(Property::Transparency(Transparency::Opaque));
+        series_spec.add(&blue);
+        let enamels = SeriesPaintSeries::from(&series_spec);
+        let mixture = MixtureBuilder::new("#001")
+            .series_paint_component((Rc::clone(watercolours.find("red").unwrap()), 1))
+            .series_paint_component((Rc::clone(enamels.find("blue").unwrap()), 1))
+            .build();
+        assert_eq!(
+            mixture.recorded_property(PropertyType::Transparency),
+            Some(Property::Transparency(Transparency::Opaque))
+        );
+        assert_eq!(mixture.recorded_property(PropertyType::Finish), None);
+        assert_eq!(mixture.recorded_property(PropertyType::Staining), None);
+        let property_types: Vec<PropertyType> = mixture
+            .recorded_properties()
+            .iter()
+            .map(|property| property.property_type())
+            .collect();
+        assert_eq!(
+            property_types,
+            vec![PropertyType::Transparency, PropertyType::Fluorescence]
+        );
+    }
+
+    #[test]
+    fn mix_custom_properties() {
+        let mut series_spec = test_series_spec("enamels");
+        let thinning = CustomPropertyType::new(
+            "Thinning",
+            &[("light", "L"), ("medium", "M"), ("heavy", "H")],
+            true,
+        )
+        .unwrap();
+        let in_shop =
+            CustomPropertyType::new("In Shop", &[("yes", "Y"), ("no", "N")], false).unwrap();
+        series_spec.add_custom_property_type(thinning).unwrap();
+        series_spec.add_custom_property_type(in_shop).unwrap();
+        let mut red = BasicPaintSpec::new(&HCV::RED, "red");
+        red.set_custom_property("Thinning", "H");
+        series_spec.add(&red);
+        series_spec.add(&BasicPaintSpec::new(&HCV::YELLOW, "yellow"));
+        let series = SeriesPaintSeries::from(&series_spec);
+        let red = series.find("red").unwrap();
+        assert_eq!(red.custom_values().len(), 2);
+        let mixture = MixtureBuilder::new("#001")
+            .series_paint_component((Rc::clone(red), 2))
+            .series_paint_component((Rc::clone(series.find("yellow").unwrap()), 1))
+            .build();
+        let custom_values = mixture.custom_values();
+        assert_eq!(custom_values.len(), 1);
+        assert_eq!(custom_values[0].name(), "Thinning");
+        assert_eq!(custom_values[0].full(), "medium");
+    }
+
+    #[test]
+    fn mix_pigments() {
+        let mut series_spec = test_series_spec("acrylics");
+        for (id, notes) in [
+            ("umber", "PBr 7"),
+            ("sienna", "PBr 7"),
+            ("green", "PB 15.3, PG 7"),
+        ] {
+            let mut paint_spec = BasicPaintSpec::new(&HCV::RED, id);
+            paint_spec.pigments = extract_pigments(notes);
+            series_spec.add(&paint_spec);
+        }
+        let series = SeriesPaintSeries::from(&series_spec);
+        let browns = MixtureBuilder::new("#001")
+            .series_paint_component((Rc::clone(series.find("umber").unwrap()), 1))
+            .series_paint_component((Rc::clone(series.find("sienna").unwrap()), 1))
+            .build();
+        assert!(browns.is_single_pigment());
+        let mixture = MixtureBuilder::new("#002")
+            .mixed_paint_component((Rc::clone(&browns), 1))
+            .series_paint_component((Rc::clone(series.find("green").unwrap()), 1))
+            .build();
+        let codes: Vec<&str> = mixture.pigments().iter().map(|p| p.code()).collect();
+        assert_eq!(codes, vec!["PB15:3", "PBr7", "PG7"]);
+        assert!(!mixture.is_single_pigment());
+    }
+
+    #[test]
+    fn edit_and_delete_mixtures() {
+        let series = test_series();
+        let red = series.find("red").unwrap();
+        let yellow = series.find("yellow").unwrap();
+        let orange = MixtureBuilder::new("#001")
+            .series_paint_component((Rc::clone(red), 1))
+            .series_paint_component((Rc::clone(yellow), 1))
+            .build();
+        let dark_orange = MixtureBuilder::new("#002")
+            .mixed_paint_component((Rc::clone(&orange), 2))
+            .series_paint_component((Rc::clone(red), 1))
+            .build();
+        let darker_orange = MixtureBuilder::new("#003")
+            .mixed_paint_component((Rc::clone(&dark_orange), 1))
+            .series_paint_component((Rc::clone(red), 1))
+            .build();
+        let mut session = MixingSession::new();
+        for mixture in [&orange, &dark_orange, &darker_orange] {
+            session.add_mixture(mixture);
+        }
+        let ids = |mixtures: Vec<Rc<Mixture>>| -> Vec<String> {
+            mixtures.iter().map(|m| m.id().to_string()).collect()
+        };
+        assert_eq!(ids(session.dependents("#001")), vec!["#002", "#003"]);
+        assert!(session.dependents("#003").is_empty());
+
+        let yellower = MixtureBuilder::new("#001")
+            .name("yellowish orange")
+            .series_paint_component((Rc::clone(red), 1))
+            .series_paint_component((Rc::clone(yellow), 2))
+            .build();
+        match session.edit_mixture(&yellower, Dependents::Refuse) {
+            Err(crate::Error::MixtureInUse(id, users)) => {
+                assert_eq!(id, "#001");
+                assert_eq!(users, vec!["#002", "#003"]);
+            }
+            _ => panic!("edit should be refused"),
+        }
+        assert_eq!(session.mixture("#001").unwrap().name(), None);
+
+        let old_colour = session.mixture("#003").unwrap().hcv();
+        let changes = session
+            .edit_mixture(&yellower, Dependents::Cascade)
+            .unwrap();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(
+            session.mixture("#001").unwrap().name(),
+            Some("yellowish orange")
+        );
+        let darker_orange = session.mixture("#003").unwrap();
+        assert_ne!(darker_orange.hcv(), old_colour);
+        assert!(darker_orange.uses("#001"));
+        assert_eq!(session.dependents("#001").len(), 2);
+
+        let cyclic = MixtureBuilder::new("#001")
+            .mixed_paint_component((Rc::clone(darker_orange), 1))
+            .build();
+        assert!(matches!(
+            session.edit_mixture(&cyclic, Dependents::Cascade),
+            Err(crate::Error::CyclicMixtureDependency(_))
+        ));
+
+        assert!(session.delete_mixture("#002").is_err());
+        assert_eq!(session.delete_mixture("#003").unwrap().id(), "#003");
+        assert_eq!(session.delete_mixture("#002").unwrap().id(), "#002");
+        assert!(session.delete_mixture("#002").is_err());
+        assert_eq!(ids(session.mixtures().cloned().collect()), vec!["#001"]);
+    }
+
+    #[test]
+    fn import_mixtures() {
+        let series = test_series();
+        let other = MixingSession::read(&mut OUT_OF_ORDER_SESSION.as_bytes(), &series).unwrap();
+        let mut session = MixingSession::new();
+        let existing = MixtureBuilder::new("MIX#001")
+            .series_paint_component((Rc::clone(series.find("red").unwrap()), 1))
+            .build();
+        session.add_mixture(&existing);
+        let mut count = 0;
+        let imported = session
+            .import_mixtures(&other, &["#002"], || {
+                count += 1;
+                format!("MIX#{:03}", count)
+            })
+            .unwrap();
+        let ids: Vec<(&str, &str)> = imported
+            .iter()
+            .map(|(old_id, mixture)| (old_id.as_str(), mixture.id()))
+            .collect();
+        assert_eq!(ids, vec![("#001", "MIX#002"), ("#002", "MIX#003")]);
+        assert_eq!(session.mixtures().count(), 3);
+        let dark_orange = session.mixture("MIX#003").unwrap();
+        assert_eq!(dark_orange.name(), Some("dark orange"));
+        assert!(dark_orange.uses("MIX#002"));
+        assert!(!dark_orange.uses("#001"));
+        assert_eq!(dark_orange.hcv(), other.mixture("#002").unwrap().hcv());
+        assert!(session
+            .import_mixtures(&other, &["#999"], String::new)
+            .is_err());
+        // "#001" gets "MIX#004" but there's no id left for "#002" so neither is imported
+        let mut new_ids = vec!["MIX#004"].into_iter();
+        let result = session.import_mixtures(&other, &["#002"], || {
+            new_ids.next().unwrap_or("MIX#001").to_string()
+        });
+        match result {
+            Err(crate::Error::DuplicateMixture(id)) => assert_eq!(id, "MIX#001"),
+            _ => panic!("expected a duplicate mixture error"),
+        }
+        assert_eq!(session.mixtures().count(), 3);
+    }
 }
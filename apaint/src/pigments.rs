@@ -0,0 +1,207 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Colour Index pigment codes (e.g. "PB29" or "PB15:3") for the pigments in artists' paints.
+
+use std::{convert::TryFrom, fmt, rc::Rc, str::FromStr};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{
+    series::{SeriesPaint, SeriesPaintSeriesSpec},
+    BasicPaintIfce,
+};
+
+const PIGMENT_PATTERN: &str =
+    r"P(?P<class>Bk|Br|B|G|M|O|R|V|W|Y)\s?(?P<number>\d+)(?:[.:](?P<variant>\d+))?";
+
+lazy_static! {
+    static ref PIGMENT_RE: Regex =
+        Regex::new(&format!(r"\b{PIGMENT_PATTERN}")).expect("programmer error");
+    // NB: manufacturers' suffixes (e.g. "LF" in "PY 74LF") are not part of the code
+    static ref PIGMENT_CODE_RE: Regex =
+        Regex::new(&format!(r"(?i)^{PIGMENT_PATTERN}[a-z]*$")).expect("programmer error");
+}
+
+/// A Colour Index generic name in canonical form: "P", the hue class (B, Bk, Br, G, M, O,
+/// R, V, W or Y), the number and (optionally) a colon and variant number.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pigment(String);
+
+impl Pigment {
+    fn from_captures(captures: &regex::Captures) -> Self {
+        let class = match captures["class"].to_lowercase().as_str() {
+            "bk" => "Bk".to_string(),
+            "br" => "Br".to_string(),
+            class => class.to_uppercase(),
+        };
+        let number: u32 = captures["number"].parse().expect("regex guarantees digits");
+        match captures.name("variant") {
+            Some(variant) => Self(format!("P{class}{number}:{}", variant.as_str())),
+            None => Self(format!("P{class}{number}")),
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Pigment {
+    type Err = crate::Error;
+
+    /// Accepts the forms used by manufacturers e.g. "PBr7", "PBr 7", "PB 15.3" and "PY 74LF".
+    fn from_str(string: &str) -> Result<Self, crate::Error> {
+        match PIGMENT_CODE_RE.captures(string.trim()) {
+            Some(captures) => Ok(Self::from_captures(&captures)),
+            None => Err(crate::Error::NotAValidPigment(string.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for Pigment {
+    type Error = crate::Error;
+
+    fn try_from(string: String) -> Result<Self, crate::Error> {
+        Self::from_str(&string)
+    }
+}
+
+impl From<Pigment> for String {
+    fn from(pigment: Pigment) -> Self {
+        pigment.0
+    }
+}
+
+impl fmt::Display for Pigment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Return the (sorted and deduplicated) pigments mentioned in `text` (e.g. paint notes
+/// such as "PW 6, PY 74LF, Lightfastness: 1").
+pub fn extract_pigments(text: &str) -> Vec<Pigment> {
+    let mut pigments: Vec<Pigment> = PIGMENT_RE
+        .captures_iter(text)
+        .map(|captures| Pigment::from_captures(&captures))
+        .collect();
+    pigments.sort();
+    pigments.dedup();
+    pigments
+}
+
+/// Return the (sorted and deduplicated) pigments in `text`, a comma separated list of
+/// pigment codes, or an error naming the first item that isn't a valid code.
+pub fn parse_pigments(text: &str) -> Result<Vec<Pigment>, crate::Error> {
+    let mut pigments = vec![];
+    for item in text
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        pigments.push(Pigment::from_str(item)?);
+    }
+    pigments.sort();
+    pigments.dedup();
+    Ok(pigments)
+}
+
+/// Set the pigments of each paint that has none recorded to those found in its notes and
+/// return the number of paints that gained any.
+pub fn import_pigments(series_spec: &mut SeriesPaintSeriesSpec) -> usize {
+    let mut count = 0;
+    for paint_spec in series_spec.paint_list.iter_mut() {
+        if paint_spec.pigments.is_empty() {
+            paint_spec.pigments = extract_pigments(&paint_spec.notes);
+            if !paint_spec.pigments.is_empty() {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// The paints in `paints` that contain `pigment`.
+pub fn paints_containing<'a>(
+    pigment: &'a Pigment,
+    paints: impl IntoIterator<Item = &'a Rc<SeriesPaint>> + 'a,
+) -> impl Iterator<Item = &'a Rc<SeriesPaint>> + 'a {
+    paints
+        .into_iter()
+        .filter(move |paint| paint.pigments().contains(pigment))
+}
+
+/// The paints in `paints` that are made from a single pigment.
+pub fn single_pigment_paints<'a>(
+    paints: impl IntoIterator<Item = &'a Rc<SeriesPaint>> + 'a,
+) -> impl Iterator<Item = &'a Rc<SeriesPaint>> + 'a {
+    paints.into_iter().filter(|paint| paint.is_single_pigment())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::series::{BasicPaintSpec, SeriesPaintSeries};
+    use colour_math::{HueConstants, HCV};
+
+    fn pigment(code: &str) -> Pigment {
+        Pigment::from_str(code).unwrap()
+    }
+
+    #[test]
+    fn parse_pigments() {
+        assert_eq!(pigment("PBr 7").code(), "PBr7");
+        assert_eq!(pigment("pbk7").code(), "PBk7");
+        assert_eq!(pigment("PB 15.3").code(), "PB15:3");
+        assert_eq!(pigment("PY 74LF").code(), "PY74");
+        assert!(Pigment::from_str("Lightfastness: 1").is_err());
+        assert!(Pigment::from_str("PB 29, PR 101").is_err());
+        assert_eq!(
+            extract_pigments("PW 6, PG 7, PB 15.3, Lightfastness: 1"),
+            vec![pigment("PB15:3"), pigment("PG7"), pigment("PW6")]
+        );
+        assert!(extract_pigments("FS37925-RAL9016-RLM21").is_empty());
+    }
+
+    #[test]
+    fn pigment_lists() {
+        assert_eq!(
+            parse_pigments(" PW 6,PB 15.3, , pbk7, PW6").unwrap(),
+            vec![pigment("PB15:3"), pigment("PBk7"), pigment("PW6")]
+        );
+        assert!(parse_pigments("").unwrap().is_empty());
+        match parse_pigments("PB 29, PR101 PY3, Lightfastness: 1") {
+            Err(crate::Error::NotAValidPigment(code)) => assert_eq!(code, "PR101 PY3"),
+            _ => panic!("invalid pigment code not reported"),
+        }
+    }
+
+    #[test]
+    fn pigment_queries() {
+        let mut series_spec = SeriesPaintSeriesSpec::default();
+        for (id, notes) in [
+            ("0005", "PBr 7, Lightfastness: 1"),
+            ("0016", "PY 74LF, PBr 7, Lightfastness: 1"),
+            ("0020", "PB 29, Lightfastness: 1"),
+            ("0099", ""),
+        ] {
+            let mut paint_spec = BasicPaintSpec::new(&HCV::RED, id);
+            paint_spec.notes = notes.to_string();
+            series_spec.add(&paint_spec);
+        }
+        assert_eq!(import_pigments(&mut series_spec), 3);
+        assert_eq!(import_pigments(&mut series_spec), 0);
+        let series = SeriesPaintSeries::from(&series_spec);
+        let umber = pigment("PBr7");
+        let ids: Vec<&str> = paints_containing(&umber, series.paints())
+            .map(|paint| paint.id())
+            .collect();
+        assert_eq!(ids, vec!["0005", "0016"]);
+        let ids: Vec<&str> = single_pigment_paints(series.paints())
+            .map(|paint| paint.id())
+            .collect();
+        assert_eq!(ids, vec!["0005", "0020"]);
+    }
+}
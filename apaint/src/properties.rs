@@ -13,6 +13,8 @@ use std::{fmt, str::FromStr};
 use apaint_boilerplate::Property;
 use std::marker::PhantomData;
 
+pub mod custom;
+
 pub trait PropertyIfce:
     FromStr<Err = String> + PartialEq + PartialOrd + Default + fmt::Debug
 {
@@ -89,6 +91,18 @@ pub enum Transparency {
     Clear,
 }
 
+impl From<Transparency> for Opacity {
+    fn from(transparency: Transparency) -> Self {
+        match transparency {
+            Transparency::Opaque => Opacity::Opaque,
+            Transparency::SemiOpaque => Opacity::SemiOpaque,
+            Transparency::SemiTransparent => Opacity::SemiTransparent,
+            Transparency::Transparent => Opacity::Transparent,
+            Transparency::Clear => Opacity::Clear,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Property)]
 pub enum Permanence {
     ExtremelyPermanent,
@@ -156,6 +170,35 @@ pub enum Property {
 }
 
 impl Property {
+    pub fn property_type(self) -> PropertyType {
+        match self {
+            Self::Finish(_) => PropertyType::Finish,
+            Self::Transparency(_) => PropertyType::Transparency,
+            Self::Permanence(_) => PropertyType::Permanence,
+            Self::Fluorescence(_) => PropertyType::Fluorescence,
+            Self::Metallicness(_) => PropertyType::Metallicness,
+            Self::LightFastness(_) => PropertyType::LightFastness,
+            Self::Opacity(_) => PropertyType::Opacity,
+            Self::Staining(_) => PropertyType::Staining,
+            Self::Granulation(_) => PropertyType::Granulation,
+        }
+    }
+
+    /// The position (starting at 1) of the value in its property's list of values.
+    pub fn ordinal(self) -> f64 {
+        match self {
+            Self::Finish(value) => value.into(),
+            Self::Transparency(value) => value.into(),
+            Self::Permanence(value) => value.into(),
+            Self::Fluorescence(value) => value.into(),
+            Self::Metallicness(value) => value.into(),
+            Self::LightFastness(value) => value.into(),
+            Self::Opacity(value) => value.into(),
+            Self::Staining(value) => value.into(),
+            Self::Granulation(value) => value.into(),
+        }
+    }
+
     pub fn name(self) -> &'static str {
         use PropertyIfce;
         match self {
@@ -241,7 +284,7 @@ impl Property {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum PropertyType {
     Finish,
     Transparency,
@@ -255,6 +298,80 @@ pub enum PropertyType {
 }
 
 impl PropertyType {
+    pub const ALL: [PropertyType; 9] = [
+        PropertyType::Finish,
+        PropertyType::Transparency,
+        PropertyType::Permanence,
+        PropertyType::Fluorescence,
+        PropertyType::Metallicness,
+        PropertyType::LightFastness,
+        PropertyType::Opacity,
+        PropertyType::Staining,
+        PropertyType::Granulation,
+    ];
+
+    /// The properties recorded by series that don't declare their own.
+    pub const BASIC: [PropertyType; 5] = [
+        PropertyType::Finish,
+        PropertyType::Transparency,
+        PropertyType::Permanence,
+        PropertyType::Fluorescence,
+        PropertyType::Metallicness,
+    ];
+
+    /// The properties usually recorded for watercolours.
+    pub const WATERCOLOUR: [PropertyType; 5] = [
+        PropertyType::Transparency,
+        PropertyType::Fluorescence,
+        PropertyType::LightFastness,
+        PropertyType::Staining,
+        PropertyType::Granulation,
+    ];
+
+    pub fn default_value(self) -> Property {
+        match self {
+            Self::Finish => Property::Finish(Finish::default()),
+            Self::Transparency => Property::Transparency(Transparency::default()),
+            Self::Permanence => Property::Permanence(Permanence::default()),
+            Self::Fluorescence => Property::Fluorescence(Fluorescence::default()),
+            Self::Metallicness => Property::Metallicness(Metallicness::default()),
+            Self::LightFastness => Property::LightFastness(LightFastness::default()),
+            Self::Opacity => Property::Opacity(Opacity::default()),
+            Self::Staining => Property::Staining(Staining::default()),
+            Self::Granulation => Property::Granulation(Granulation::default()),
+        }
+    }
+
+    /// The value whose ordinal is nearest to `ordinal` (e.g. the mean of a mixture's values).
+    pub fn value_from_ordinal(self, ordinal: f64) -> Property {
+        match self {
+            Self::Finish => Property::Finish(ordinal.into()),
+            Self::Transparency => Property::Transparency(ordinal.into()),
+            Self::Permanence => Property::Permanence(ordinal.into()),
+            Self::Fluorescence => Property::Fluorescence(ordinal.into()),
+            Self::Metallicness => Property::Metallicness(ordinal.into()),
+            Self::LightFastness => Property::LightFastness(ordinal.into()),
+            Self::Opacity => Property::Opacity(ordinal.into()),
+            Self::Staining => Property::Staining(ordinal.into()),
+            Self::Granulation => Property::Granulation(ordinal.into()),
+        }
+    }
+
+    pub fn value_from_str(self, string: &str) -> Result<Property, String> {
+        let property = match self {
+            Self::Finish => Property::Finish(Finish::from_str(string)?),
+            Self::Transparency => Property::Transparency(Transparency::from_str(string)?),
+            Self::Permanence => Property::Permanence(Permanence::from_str(string)?),
+            Self::Fluorescence => Property::Fluorescence(Fluorescence::from_str(string)?),
+            Self::Metallicness => Property::Metallicness(Metallicness::from_str(string)?),
+            Self::LightFastness => Property::LightFastness(LightFastness::from_str(string)?),
+            Self::Opacity => Property::Opacity(Opacity::from_str(string)?),
+            Self::Staining => Property::Staining(Staining::from_str(string)?),
+            Self::Granulation => Property::Granulation(Granulation::from_str(string)?),
+        };
+        Ok(property)
+    }
+
     pub fn name(self) -> &'static str {
         match self {
             Self::Finish => Finish::NAME,
@@ -311,146 +428,171 @@ impl PropertyType {
         }
     }
 }
+
 impl std::fmt::Display for PropertyType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match *self {
-            Self::Finish => write!(f, "Finish"),
-            Self::Transparency => write!(f, "Transparency"),
-            Self::Permanence => write!(f, "Permanence"),
-            Self::Fluorescence => write!(f, "Fluorescence"),
-            Self::Metallicness => write!(f, " Metallicness"),
-            Self::Granulation => write!(f, " Granulation"),
-            Self::Opacity => write!(f, " Opacity"),
-            Self::Staining => write!(f, " Staining"),
-            Self::LightFastness => write!(f, " LightFastness"),
-        }
+        write!(f, "{}", self.name())
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-pub enum WatercolourProperty {
-    LightFastness(LightFastness),
-    Transparency(Transparency),
-    Staining(Staining),
-    Granulation(Granulation),
-    Fluorescence(Fluorescence),
+/// How the values of a property are combined when paints are mixed.  Property values are
+/// ordered from the strongest (e.g. most permanent or most metallic) to the weakest so the
+/// rules are expressed in terms of their ordinals.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum PropertyMixRule {
+    /// The mean of the components' ordinals weighted by their parts.
+    WeightedMean,
+    /// The value with the highest ordinal e.g. a mixture is no more permanent than its
+    /// least permanent component.
+    WorstCase,
+    /// The value with the lowest ordinal e.g. metallic flake shows even at low ratios.
+    BestCase,
+    /// The lowest ordinal whose value (or a stronger one) accounts for at least the given
+    /// percentage of the parts e.g. a little fluorescent paint doesn't make a mixture
+    /// fluorescent.
+    Threshold(u8),
 }
 
-impl WatercolourProperty {
-    pub fn name(self) -> &'static str {
+impl PropertyMixRule {
+    /// Combine the ordinals (with their parts) of a mixture's components.
+    pub fn mix(self, values: &[(f64, u64)]) -> Option<f64> {
+        let total_parts: u64 = values.iter().map(|(_, parts)| parts).sum();
+        if total_parts == 0 {
+            return None;
+        }
+        let present = values.iter().filter(|(_, parts)| *parts > 0);
         match self {
-            WatercolourProperty::LightFastness(_) => LightFastness::NAME,
-            WatercolourProperty::Transparency(_) => Transparency::NAME,
-            WatercolourProperty::Staining(_) => Staining::NAME,
-            WatercolourProperty::Granulation(_) => Granulation::NAME,
-            WatercolourProperty::Fluorescence(_) => Fluorescence::NAME,
+            Self::WeightedMean => {
+                let sum: f64 = present.map(|(value, parts)| value * *parts as f64).sum();
+                Some(sum / total_parts as f64)
+            }
+            Self::WorstCase => present.map(|(value, _)| *value).reduce(f64::max),
+            Self::BestCase => present.map(|(value, _)| *value).reduce(f64::min),
+            Self::Threshold(percentage) => {
+                let mut sorted: Vec<(f64, u64)> = present.copied().collect();
+                sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).expect("ordinals are numbers"));
+                let required = percentage.min(100) as f64 * total_parts as f64 / 100.0;
+                let mut parts = 0;
+                for (value, value_parts) in sorted.iter() {
+                    parts += value_parts;
+                    if parts as f64 >= required {
+                        return Some(*value);
+                    }
+                }
+                sorted.last().map(|(value, _)| *value)
+            }
         }
     }
+}
 
-    pub fn prompt(self) -> &'static str {
+impl PropertyType {
+    /// The rule used to mix the property unless a series or session overrides it.
+    pub fn default_mix_rule(self) -> PropertyMixRule {
         match self {
-            WatercolourProperty::LightFastness(_) => LightFastness::PROMPT,
-            WatercolourProperty::Transparency(_) => Transparency::PROMPT,
-            WatercolourProperty::Staining(_) => Staining::PROMPT,
-            WatercolourProperty::Granulation(_) => Granulation::PROMPT,
-            WatercolourProperty::Fluorescence(_) => Fluorescence::PROMPT,
+            Self::Permanence | Self::LightFastness => PropertyMixRule::WorstCase,
+            Self::Metallicness | Self::Staining => PropertyMixRule::BestCase,
+            Self::Fluorescence => PropertyMixRule::Threshold(50),
+            _ => PropertyMixRule::WeightedMean,
         }
     }
+}
 
-    pub fn list_header_name(self) -> &'static str {
-        match self {
-            WatercolourProperty::LightFastness(_) => LightFastness::LIST_HEADER_NAME,
-            WatercolourProperty::Transparency(_) => Transparency::LIST_HEADER_NAME,
-            WatercolourProperty::Staining(_) => Staining::LIST_HEADER_NAME,
-            WatercolourProperty::Granulation(_) => Granulation::LIST_HEADER_NAME,
-            WatercolourProperty::Fluorescence(_) => Fluorescence::LIST_HEADER_NAME,
-        }
+/// Overrides of the property types' default mixing rules.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct PropertyMixRules(Vec<(PropertyType, PropertyMixRule)>);
+
+impl PropertyMixRules {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    pub fn str_values(self) -> Vec<&'static str> {
-        match self {
-            WatercolourProperty::LightFastness(_) => LightFastness::str_values(),
-            WatercolourProperty::Transparency(_) => Transparency::str_values(),
-            WatercolourProperty::Staining(_) => Staining::str_values(),
-            WatercolourProperty::Granulation(_) => Granulation::str_values(),
-            WatercolourProperty::Fluorescence(_) => Fluorescence::str_values(),
-        }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 
-    pub fn abbrev(self) -> &'static str {
-        match self {
-            WatercolourProperty::LightFastness(value) => value.abbrev(),
-            WatercolourProperty::Transparency(value) => value.abbrev(),
-            WatercolourProperty::Staining(value) => value.abbrev(),
-            WatercolourProperty::Granulation(value) => value.abbrev(),
-            WatercolourProperty::Fluorescence(value) => value.abbrev(),
-        }
+    /// The overriding rule for `property_type` (if any).
+    pub fn overriding_rule(&self, property_type: PropertyType) -> Option<PropertyMixRule> {
+        self.0
+            .iter()
+            .find(|(pt, _)| *pt == property_type)
+            .map(|(_, rule)| *rule)
     }
 
-    pub fn full(self) -> &'static str {
-        match self {
-            WatercolourProperty::LightFastness(value) => value.full(),
-            WatercolourProperty::Transparency(value) => value.full(),
-            WatercolourProperty::Staining(value) => value.full(),
-            WatercolourProperty::Granulation(value) => value.full(),
-            WatercolourProperty::Fluorescence(value) => value.full(),
-        }
+    /// The rule to use for `property_type`.
+    pub fn rule(&self, property_type: PropertyType) -> PropertyMixRule {
+        self.overriding_rule(property_type)
+            .unwrap_or_else(|| property_type.default_mix_rule())
+    }
+
+    pub fn overrides(&self) -> impl Iterator<Item = &(PropertyType, PropertyMixRule)> {
+        self.0.iter()
+    }
+
+    pub fn set_rule(
+        &mut self,
+        property_type: PropertyType,
+        rule: PropertyMixRule,
+    ) -> Option<PropertyMixRule> {
+        let old = self.unset_rule(property_type);
+        let index = self.0.partition_point(|(pt, _)| *pt < property_type);
+        self.0.insert(index, (property_type, rule));
+        old
+    }
+
+    pub fn unset_rule(&mut self, property_type: PropertyType) -> Option<PropertyMixRule> {
+        let index = self.0.iter().position(|(pt, _)| *pt == property_type)?;
+        Some(self.0.remove(index).1)
     }
-}
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-pub enum WatercolourPropertyType {
-    LightFastness,
-    Transparency,
-    Staining,
-    Granulation,
-    Fluorescence,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct PropertyMixer<C: PropertyIfce> {
-    sum: f64,
-    total_parts: u64,
+    rule: PropertyMixRule,
+    values: Vec<(f64, u64)>,
     phantom: std::marker::PhantomData<C>,
 }
 
+impl<C: PropertyIfce> Default for PropertyMixer<C> {
+    fn default() -> Self {
+        Self::with_rule(PropertyMixRule::WeightedMean)
+    }
+}
+
+impl<C: PropertyIfce> PropertyMixer<C> {
+    pub fn with_rule(rule: PropertyMixRule) -> Self {
+        Self {
+            rule,
+            values: vec![],
+            phantom: PhantomData,
+        }
+    }
+}
+
 impl<C: PropertyIfce + From<f64> + Into<f64>> PropertyMixer<C> {
     pub fn new() -> Self {
         Self::default()
     }
 
     pub fn reset(&mut self) {
-        self.sum = 0.0;
-        self.total_parts = 0;
+        self.values.clear();
     }
 
     pub fn property(&self) -> Option<C> {
-        if self.total_parts > 0 {
-            let mixture = self.sum / self.total_parts as f64;
-            Some(C::from(mixture))
-        } else {
-            None
-        }
+        self.rule.mix(&self.values).map(C::from)
     }
 
     pub fn property_value(&self) -> Option<FuzzyProperty<C>> {
-        if self.total_parts > 0 {
-            Some(FuzzyProperty(
-                self.sum / self.total_parts as f64,
-                PhantomData,
-            ))
-        } else {
-            None
-        }
+        self.rule
+            .mix(&self.values)
+            .map(|value| FuzzyProperty(value, PhantomData))
     }
 
     pub fn add(&mut self, property: C, parts: u64) {
-        self.total_parts += parts;
-        self.sum += property.into() * parts as f64;
+        self.values.push((property.into(), parts));
     }
 
     pub fn add_value(&mut self, characteristic_value: FuzzyProperty<C>, parts: u64) {
-        self.total_parts += parts;
-        self.sum += characteristic_value.0 * parts as f64;
+        self.values.push((characteristic_value.0, parts));
     }
 }
 
@@ -497,4 +639,61 @@ mod tests {
         mixer.add(Finish::Gloss, 6);
         assert_eq!(mixer.property(), Some(Finish::SemiFlat));
     }
+
+    #[test]
+    fn mix_rules() {
+        let values = [(1.0, 1), (4.0, 9)];
+        assert_eq!(PropertyMixRule::WeightedMean.mix(&values), Some(3.7));
+        assert_eq!(PropertyMixRule::WorstCase.mix(&values), Some(4.0));
+        assert_eq!(PropertyMixRule::BestCase.mix(&values), Some(1.0));
+        assert_eq!(PropertyMixRule::Threshold(50).mix(&values), Some(4.0));
+        assert_eq!(PropertyMixRule::Threshold(10).mix(&values), Some(1.0));
+        assert_eq!(PropertyMixRule::BestCase.mix(&[]), None);
+
+        let mut mixer =
+            PropertyMixer::<Permanence>::with_rule(PropertyType::Permanence.default_mix_rule());
+        mixer.add(Permanence::ExtremelyPermanent, 10);
+        mixer.add(Permanence::Fugitive, 1);
+        assert_eq!(mixer.property(), Some(Permanence::Fugitive));
+
+        let mut rules = PropertyMixRules::new();
+        assert_eq!(
+            rules.rule(PropertyType::Metallicness),
+            PropertyMixRule::BestCase
+        );
+        rules.set_rule(PropertyType::Metallicness, PropertyMixRule::WeightedMean);
+        assert_eq!(
+            rules.rule(PropertyType::Metallicness),
+            PropertyMixRule::WeightedMean
+        );
+        assert_eq!(
+            rules.unset_rule(PropertyType::Metallicness),
+            Some(PropertyMixRule::WeightedMean)
+        );
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn property_values() {
+        for property_type in PropertyType::ALL.iter() {
+            let default = property_type.default_value();
+            assert_eq!(default.property_type(), *property_type);
+            assert_eq!(property_type.value_from_ordinal(default.ordinal()), default);
+            assert_eq!(property_type.value_from_str(default.full()), Ok(default));
+        }
+        assert_eq!(
+            PropertyType::Finish.value_from_ordinal(2.4),
+            Property::Finish(Finish::SemiGloss)
+        );
+        assert!(PropertyType::Staining.value_from_str("gloss").is_err());
+    }
+
+    #[test]
+    fn property_type_names() {
+        for property_type in PropertyType::ALL.iter() {
+            assert_eq!(property_type.to_string(), property_type.name());
+            assert!(!property_type.name().starts_with(' '));
+        }
+        assert_eq!(PropertyType::LightFastness.to_string(), "LightFastness");
+    }
 }
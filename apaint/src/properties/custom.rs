@@ -0,0 +1,199 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Enumerated paint properties that are declared by paint series rather than built in.
+
+use std::rc::Rc;
+
+use crate::properties::PropertyType;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomValue {
+    pub full: String,
+    pub abbrev: String,
+}
+
+/// A property with an ordered list of values (the first being the default).  If the order
+/// is meaningful (i.e. the property is ordinal) the property can be mixed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomPropertyType {
+    name: String,
+    values: Vec<CustomValue>,
+    #[serde(default)]
+    ordinal: bool,
+}
+
+impl CustomPropertyType {
+    /// A new property whose values are given as (full, abbreviation) pairs.
+    pub fn new(name: &str, values: &[(&str, &str)], ordinal: bool) -> Result<Self, crate::Error> {
+        let property_type = Self {
+            name: name.to_string(),
+            values: values
+                .iter()
+                .map(|(full, abbrev)| CustomValue {
+                    full: full.to_string(),
+                    abbrev: abbrev.to_string(),
+                })
+                .collect(),
+            ordinal,
+        };
+        property_type.validate()?;
+        Ok(property_type)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), crate::Error> {
+        let invalid =
+            |reason: &str| crate::Error::InvalidCustomProperty(self.name.clone(), reason.into());
+        if self.name.trim().is_empty() {
+            return Err(invalid("no name"));
+        }
+        if PropertyType::ALL.iter().any(|pt| pt.name() == self.name) {
+            return Err(invalid("name of a built in property"));
+        }
+        if self.values.is_empty() {
+            return Err(invalid("no values"));
+        }
+        for (i, value) in self.values.iter().enumerate() {
+            if value.full.is_empty() || value.abbrev.is_empty() {
+                return Err(invalid("empty value"));
+            }
+            for other in self.values[i + 1..].iter() {
+                if [&other.full, &other.abbrev].contains(&&value.full)
+                    || [&other.full, &other.abbrev].contains(&&value.abbrev)
+                {
+                    return Err(invalid("ambiguous values"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn prompt(&self) -> String {
+        format!("{}:", self.name)
+    }
+
+    /// Abbreviated in the same way as the built in properties' names.
+    pub fn list_header_name(&self) -> String {
+        let mut header: String = self.name.chars().take(2).collect();
+        header.push('.');
+        header
+    }
+
+    pub fn is_ordinal(&self) -> bool {
+        self.ordinal
+    }
+
+    pub fn values(&self) -> &[CustomValue] {
+        &self.values
+    }
+
+    pub fn str_values(&self) -> Vec<&str> {
+        self.values
+            .iter()
+            .map(|value| value.full.as_str())
+            .collect()
+    }
+
+    /// The index of the value whose full name or abbreviation is `string`.
+    pub fn index_of(&self, string: &str) -> Option<usize> {
+        self.values
+            .iter()
+            .position(|value| value.full == string || value.abbrev == string)
+    }
+}
+
+/// A paint's value for a custom property as recorded in a series file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CustomProperty {
+    pub name: String,
+    pub value: String,
+}
+
+/// A custom property value resolved against its series' declaration of the property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomPropertyValue {
+    property_type: Rc<CustomPropertyType>,
+    index: usize,
+}
+
+impl CustomPropertyValue {
+    pub fn new(property_type: &Rc<CustomPropertyType>, index: usize) -> Self {
+        debug_assert!(index < property_type.values.len());
+        Self {
+            property_type: Rc::clone(property_type),
+            index,
+        }
+    }
+
+    pub fn from_str(property_type: &Rc<CustomPropertyType>, string: &str) -> Option<Self> {
+        property_type
+            .index_of(string)
+            .map(|index| Self::new(property_type, index))
+    }
+
+    /// The value whose ordinal is nearest to `ordinal` (e.g. the mean of a mixture's values).
+    pub fn from_ordinal(property_type: &Rc<CustomPropertyType>, ordinal: f64) -> Self {
+        let last = property_type.values.len() - 1;
+        let index = (ordinal.round().max(1.0) as usize - 1).min(last);
+        Self::new(property_type, index)
+    }
+
+    pub fn property_type(&self) -> &Rc<CustomPropertyType> {
+        &self.property_type
+    }
+
+    pub fn name(&self) -> &str {
+        &self.property_type.name
+    }
+
+    pub fn full(&self) -> &str {
+        &self.property_type.values[self.index].full
+    }
+
+    pub fn abbrev(&self) -> &str {
+        &self.property_type.values[self.index].abbrev
+    }
+
+    /// The position (starting at 1) of the value in its property's list of values.
+    pub fn ordinal(&self) -> f64 {
+        (self.index + 1) as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn custom_property() {
+        let medium = Rc::new(
+            CustomPropertyType::new(
+                "Medium",
+                &[("acrylic", "A"), ("enamel", "E"), ("lacquer", "L")],
+                false,
+            )
+            .unwrap(),
+        );
+        assert_eq!(medium.list_header_name(), "Me.");
+        assert_eq!(medium.prompt(), "Medium:");
+        let enamel = CustomPropertyValue::from_str(&medium, "E").unwrap();
+        assert_eq!(enamel.full(), "enamel");
+        assert_eq!(enamel.ordinal(), 2.0);
+        assert!(CustomPropertyValue::from_str(&medium, "oil").is_none());
+        assert_eq!(
+            CustomPropertyValue::from_ordinal(&medium, 2.6).abbrev(),
+            "L"
+        );
+        assert_eq!(
+            CustomPropertyValue::from_ordinal(&medium, 9.0).abbrev(),
+            "L"
+        );
+
+        assert!(CustomPropertyType::new("Finish", &[("x", "X")], false).is_err());
+        assert!(CustomPropertyType::new("In Shop", &[], false).is_err());
+        assert!(CustomPropertyType::new("In Shop", &[("yes", "Y"), ("no", "yes")], false).is_err());
+    }
+}
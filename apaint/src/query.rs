@@ -0,0 +1,472 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Filtering paints by their properties and colour attributes.
+//!
+//! A query combines comparisons such as `transparency <= SemiOpaque`, `value > 0.6` or
+//! `hue in 180..240` using `&&`, `||`, `!` and parentheses.  Properties are compared using
+//! the order of their values (strongest first) and a value may be given by its name (e.g.
+//! `SemiOpaque`), its full name (`semi-opaque`) or its abbreviation (`SO`).  The colour
+//! attributes (value, chroma, greyness and warmth) lie between 0 and 1 and hue angles are
+//! in degrees from 0 to 360 (a hue range may wrap around e.g. `hue in 330..30`).  Ranges
+//! include their ends.  Comparisons involving a property that a paint doesn't record, or
+//! the hue of a grey, are false.
+
+use std::{rc::Rc, str::FromStr};
+
+use colour_math::ScalarAttribute;
+
+use crate::{
+    mixtures::{MixingSession, Mixture},
+    properties::PropertyType,
+    series::{SeriesPaint, SeriesPaintSeries},
+    BasicPaintIfce,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn compare(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Self::Eq => lhs == rhs,
+            Self::Ne => lhs != rhs,
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Gt => lhs > rhs,
+            Self::Ge => lhs >= rhs,
+        }
+    }
+}
+
+/// The characteristic of a paint that a comparison examines.
+#[derive(Debug, Clone, Copy)]
+pub enum Subject {
+    Property(PropertyType),
+    Attribute(ScalarAttribute),
+    Hue,
+}
+
+impl Subject {
+    fn from_name(name: &str) -> Option<Self> {
+        let name: String = name
+            .chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .collect::<String>()
+            .to_lowercase();
+        let subject = match name.as_str() {
+            "hue" => Self::Hue,
+            "value" => Self::Attribute(ScalarAttribute::Value),
+            "chroma" => Self::Attribute(ScalarAttribute::Chroma),
+            "greyness" | "grayness" => Self::Attribute(ScalarAttribute::Greyness),
+            "warmth" => Self::Attribute(ScalarAttribute::Warmth),
+            _ => Self::Property(
+                *PropertyType::ALL
+                    .iter()
+                    .find(|pt| pt.name().to_lowercase() == name)?,
+            ),
+        };
+        Some(subject)
+    }
+
+    fn value(self, paint: &impl BasicPaintIfce) -> Option<f64> {
+        match self {
+            Self::Property(property_type) => paint
+                .recorded_property(property_type)
+                .map(|property| property.ordinal()),
+            Self::Attribute(attribute) => Some(f64::from(paint.scalar_attribute(attribute))),
+            Self::Hue => paint
+                .hue_angle()
+                .map(|angle| normalised_degrees(f64::from(angle))),
+        }
+    }
+
+    /// The number that `operand` stands for when compared with this subject.
+    fn operand(self, operand: &Token) -> Result<f64, String> {
+        match (self, operand) {
+            (Self::Hue, Token::Number(number)) => Ok(normalised_bound(*number)),
+            (_, Token::Number(number)) => Ok(*number),
+            (Self::Property(property_type), Token::Word(word)) => {
+                match property_type.value_from_str(word) {
+                    Ok(property) => Ok(property.ordinal()),
+                    Err(_) => match property_type.value_from_str(&kebab_case(word)) {
+                        Ok(property) => Ok(property.ordinal()),
+                        Err(_) => Err(format!("\"{word}\": not a {property_type} value")),
+                    },
+                }
+            }
+            (_, token) => Err(format!("expected a number but found \"{token}\"")),
+        }
+    }
+}
+
+fn normalised_degrees(degrees: f64) -> f64 {
+    degrees.rem_euclid(360.0)
+}
+
+/// Like `normalised_degrees()` but a whole (positive) turn stays at 360 so that the upper
+/// bounds in `hue <= 360` and `hue in 0..360` include every hue.
+fn normalised_bound(degrees: f64) -> f64 {
+    match normalised_degrees(degrees) {
+        angle if angle == 0.0 && degrees > 0.0 => 360.0,
+        angle => angle,
+    }
+}
+
+/// "SemiOpaque" becomes "semi-opaque".
+fn kebab_case(word: &str) -> String {
+    let mut string = String::new();
+    for (i, c) in word.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            string.push('-');
+        }
+        string.extend(c.to_lowercase());
+    }
+    string
+}
+
+/// A parsed query.  The empty query matches every paint.
+#[derive(Debug, Clone, Default)]
+pub enum PaintQuery {
+    #[default]
+    All,
+    Compare(Subject, Comparison, f64),
+    InRange(Subject, f64, f64),
+    Not(Box<PaintQuery>),
+    And(Box<PaintQuery>, Box<PaintQuery>),
+    Or(Box<PaintQuery>, Box<PaintQuery>),
+}
+
+impl PaintQuery {
+    pub fn matches(&self, paint: &impl BasicPaintIfce) -> bool {
+        match self {
+            Self::All => true,
+            Self::Compare(subject, comparison, rhs) => match subject.value(paint) {
+                Some(lhs) => comparison.compare(lhs, *rhs),
+                None => false,
+            },
+            Self::InRange(subject, low, high) => match subject.value(paint) {
+                Some(value) => match subject {
+                    Subject::Hue if low > high => value >= *low || value <= *high,
+                    _ => value >= *low && value <= *high,
+                },
+                None => false,
+            },
+            Self::Not(query) => !query.matches(paint),
+            Self::And(lhs, rhs) => lhs.matches(paint) && rhs.matches(paint),
+            Self::Or(lhs, rhs) => lhs.matches(paint) || rhs.matches(paint),
+        }
+    }
+
+    /// The paints in `paints` that match the query.
+    pub fn filter<'a, P: BasicPaintIfce + 'a>(
+        &'a self,
+        paints: impl IntoIterator<Item = &'a Rc<P>> + 'a,
+    ) -> impl Iterator<Item = &'a Rc<P>> + 'a {
+        paints
+            .into_iter()
+            .filter(move |paint| self.matches(paint.as_ref()))
+    }
+}
+
+impl FromStr for PaintQuery {
+    type Err = crate::Error;
+
+    fn from_str(string: &str) -> Result<Self, crate::Error> {
+        let invalid = |reason: String| crate::Error::NotAValidQuery(string.to_string(), reason);
+        let tokens = tokenize(string).map_err(invalid)?;
+        if tokens.is_empty() {
+            return Ok(Self::All);
+        }
+        let mut parser = Parser { tokens, index: 0 };
+        let query = parser.expression().map_err(invalid)?;
+        match parser.next() {
+            None => Ok(query),
+            Some(token) => Err(invalid(format!("unexpected \"{token}\""))),
+        }
+    }
+}
+
+/// The paints in the given series that match `query`.
+pub fn filter_series<'a>(
+    query: &PaintQuery,
+    series: impl IntoIterator<Item = &'a SeriesPaintSeries>,
+) -> Vec<Rc<SeriesPaint>> {
+    series
+        .into_iter()
+        .flat_map(|series| query.filter(series.paints()).cloned().collect::<Vec<_>>())
+        .collect()
+}
+
+/// The mixtures in `session` that match `query`.
+pub fn filter_session(query: &PaintQuery, session: &MixingSession) -> Vec<Rc<Mixture>> {
+    query.filter(session.mixtures()).cloned().collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Compare(Comparison),
+    In,
+    To,
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "{word}"),
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Compare(comparison) => match comparison {
+                Comparison::Eq => write!(f, "=="),
+                Comparison::Ne => write!(f, "!="),
+                Comparison::Lt => write!(f, "<"),
+                Comparison::Le => write!(f, "<="),
+                Comparison::Gt => write!(f, ">"),
+                Comparison::Ge => write!(f, ">="),
+            },
+            Self::In => write!(f, "in"),
+            Self::To => write!(f, ".."),
+            Self::And => write!(f, "&&"),
+            Self::Or => write!(f, "||"),
+            Self::Not => write!(f, "!"),
+            Self::Open => write!(f, "("),
+            Self::Close => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(string: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = string.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let (token, length) = match (c, next) {
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Compare(Comparison::Eq), 2),
+            ('!', Some('=')) => (Token::Compare(Comparison::Ne), 2),
+            ('<', Some('=')) => (Token::Compare(Comparison::Le), 2),
+            ('>', Some('=')) => (Token::Compare(Comparison::Ge), 2),
+            ('.', Some('.')) => (Token::To, 2),
+            ('=', _) => (Token::Compare(Comparison::Eq), 1),
+            ('<', _) => (Token::Compare(Comparison::Lt), 1),
+            ('>', _) => (Token::Compare(Comparison::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            _ if c.is_ascii_digit() || c == '.' => {
+                let mut end = i;
+                while end < chars.len()
+                    && (chars[end].is_ascii_digit()
+                        || (chars[end] == '.' && chars.get(end + 1) != Some(&'.')))
+                {
+                    end += 1;
+                }
+                let text: String = chars[i..end].iter().collect();
+                match text.parse::<f64>() {
+                    Ok(number) => (Token::Number(number), end - i),
+                    Err(_) => return Err(format!("\"{text}\": malformed number")),
+                }
+            }
+            _ if c.is_alphabetic() => {
+                let mut end = i;
+                while end < chars.len()
+                    && (chars[end].is_alphanumeric() || chars[end] == '_' || chars[end] == '-')
+                {
+                    end += 1;
+                }
+                let word: String = chars[i..end].iter().collect();
+                match word.to_lowercase().as_str() {
+                    "in" => (Token::In, end - i),
+                    "and" => (Token::And, end - i),
+                    "or" => (Token::Or, end - i),
+                    "not" => (Token::Not, end - i),
+                    _ => (Token::Word(word), end - i),
+                }
+            }
+            _ => return Err(format!("unexpected \"{c}\"")),
+        };
+        tokens.push(token);
+        i += length;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn expect_operand(&mut self) -> Result<Token, String> {
+        match self.next() {
+            Some(token @ Token::Word(_)) | Some(token @ Token::Number(_)) => Ok(token),
+            Some(token) => Err(format!("expected a value but found \"{token}\"")),
+            None => Err("expected a value but found the end".to_string()),
+        }
+    }
+
+    // expression := term ("||" term)*
+    fn expression(&mut self) -> Result<PaintQuery, String> {
+        let mut query = self.term()?;
+        while self.peek() == Some(&Token::Or) {
+            self.index += 1;
+            query = PaintQuery::Or(Box::new(query), Box::new(self.term()?));
+        }
+        Ok(query)
+    }
+
+    // term := factor ("&&" factor)*
+    fn term(&mut self) -> Result<PaintQuery, String> {
+        let mut query = self.factor()?;
+        while self.peek() == Some(&Token::And) {
+            self.index += 1;
+            query = PaintQuery::And(Box::new(query), Box::new(self.factor()?));
+        }
+        Ok(query)
+    }
+
+    // factor := "!" factor | "(" expression ")" | subject comparison operand
+    //         | subject "in" operand ".." operand
+    fn factor(&mut self) -> Result<PaintQuery, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(PaintQuery::Not(Box::new(self.factor()?))),
+            Some(Token::Open) => {
+                let query = self.expression()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(query),
+                    _ => Err("missing \")\"".to_string()),
+                }
+            }
+            Some(Token::Word(name)) => {
+                let subject = Subject::from_name(&name)
+                    .ok_or_else(|| format!("\"{name}\": unknown property or attribute"))?;
+                match self.next() {
+                    Some(Token::Compare(comparison)) => {
+                        let operand = subject.operand(&self.expect_operand()?)?;
+                        Ok(PaintQuery::Compare(subject, comparison, operand))
+                    }
+                    Some(Token::In) => {
+                        let low = subject.operand(&self.expect_operand()?)?;
+                        if self.next() != Some(Token::To) {
+                            return Err("expected \"..\" in range".to_string());
+                        }
+                        let high = subject.operand(&self.expect_operand()?)?;
+                        Ok(PaintQuery::InRange(subject, low, high))
+                    }
+                    _ => Err(format!("expected a comparison after \"{name}\"")),
+                }
+            }
+            Some(token) => Err(format!("unexpected \"{token}\"")),
+            None => Err("unexpected end".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        properties::{Finish, Property, Transparency},
+        series::{test_series_spec, BasicPaintSpec},
+    };
+    use colour_math::{HueConstants, RGBConstants, HCV};
+
+    fn test_series() -> SeriesPaintSeries {
+        let mut series_spec = test_series_spec("series name");
+        for (colour, id, finish, transparency) in [
+            (HCV::RED, "red", Finish::Flat, Transparency::Opaque),
+            (
+                HCV::YELLOW,
+                "yellow",
+                Finish::Gloss,
+                Transparency::SemiOpaque,
+            ),
+            (HCV::BLUE, "blue", Finish::Flat, Transparency::Transparent),
+            (HCV::WHITE, "white", Finish::Flat, Transparency::Opaque),
+        ] {
+            let mut paint_spec = BasicPaintSpec::new(&colour, id);
+            paint_spec.set_property(Property::Finish(finish));
+            paint_spec.set_property(Property::Transparency(transparency));
+            series_spec.add(&paint_spec);
+        }
+        SeriesPaintSeries::from(&series_spec)
+    }
+
+    fn ids(query: &str, series: &SeriesPaintSeries) -> Vec<String> {
+        let query = PaintQuery::from_str(query).unwrap();
+        filter_series(&query, [series])
+            .iter()
+            .map(|paint| paint.id().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn filter_paints() {
+        let series = test_series();
+        assert_eq!(ids("", &series).len(), 4);
+        assert_eq!(
+            ids("transparency <= SemiOpaque && finish == Flat", &series),
+            vec!["red", "white"]
+        );
+        assert_eq!(ids("transparency == semi-opaque", &series), vec!["yellow"]);
+        assert_eq!(ids("finish != F", &series), vec!["yellow"]);
+        assert_eq!(ids("value > 0.9", &series), vec!["white"]);
+        assert_eq!(ids("hue in 180..300", &series), vec!["blue"]);
+        assert_eq!(ids("hue in 330..30", &series), vec!["red"]);
+        assert_eq!(ids("hue in 360..30", &series), vec!["red"]);
+        assert_eq!(ids("hue in 0..360", &series).len(), 3);
+        assert_eq!(ids("hue <= 360", &series).len(), 3);
+        assert_eq!(ids("hue < 360", &series).len(), 3);
+        assert_eq!(ids("hue > 360", &series).len(), 0);
+        assert_eq!(
+            ids("!(finish == Flat) || chroma < 0.5", &series),
+            vec!["white", "yellow"]
+        );
+        assert_eq!(ids("light_fastness == Fair", &series).len(), 0);
+    }
+
+    #[test]
+    fn bad_queries() {
+        for query in [
+            "finish ==",
+            "finish == Shiny",
+            "colour > 0.5",
+            "value > Flat",
+            "(value > 0.5",
+            "value in 0.5",
+            "value > 0.5 0.6",
+            "value ~ 0.5",
+        ] {
+            assert!(PaintQuery::from_str(query).is_err(), "{query}");
+        }
+    }
+}
@@ -0,0 +1,389 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Printable recipe sheets: a mixing session's mixtures written as a standalone HTML or
+//! Markdown document for use at the bench.
+
+use std::io::Write;
+
+#[cfg(feature = "targeted_mixtures")]
+use colour_math::HCV;
+
+#[cfg(feature = "targeted_mixtures")]
+use crate::delta_e::delta_e;
+use crate::{
+    mixtures::{MixingSession, Mixture, Paint},
+    series::SeriesPaint,
+    svg::{escape, fill},
+    BasicPaintIfce,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecipeFormat {
+    Html,
+    Markdown,
+}
+
+impl RecipeFormat {
+    pub const ALL: [RecipeFormat; 2] = [RecipeFormat::Html, RecipeFormat::Markdown];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Html => "HTML",
+            Self::Markdown => "Markdown",
+        }
+    }
+
+    /// The usual file name extension for documents in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+impl std::fmt::Display for RecipeFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A component of a mixture as it appears on the sheet.
+struct ComponentLine {
+    label: String,
+    fill: String,
+    parts: u64,
+    percentage: f64,
+}
+
+/// The information about a mixture that appears on the sheet.
+struct Recipe {
+    id: String,
+    name: Option<String>,
+    notes: Option<String>,
+    fill: String,
+    components: Vec<ComponentLine>,
+    properties: Vec<(String, String)>,
+    // the target's fill and its ΔE from the mixture
+    target: Option<(String, f64)>,
+}
+
+impl Recipe {
+    fn new(mixture: &Mixture) -> Self {
+        let total_parts: u64 = mixture.components().map(|(_, parts)| parts).sum();
+        let components = mixture
+            .components()
+            .map(|(paint, parts)| ComponentLine {
+                label: match paint {
+                    Paint::Series(paint) => format!("{} ({})", paint.id(), paint.series_id()),
+                    Paint::Mixed(paint) => match paint.name() {
+                        Some(name) => format!("{}: {}", paint.id(), name),
+                        None => paint.id().to_string(),
+                    },
+                },
+                fill: fill(paint),
+                parts: *parts,
+                percentage: *parts as f64 * 100.0 / total_parts.max(1) as f64,
+            })
+            .collect();
+        let mut properties: Vec<(String, String)> = mixture
+            .recorded_properties()
+            .into_iter()
+            .map(|property| (property.name().to_string(), property.full().to_string()))
+            .collect();
+        properties.extend(
+            mixture
+                .custom_values()
+                .iter()
+                .map(|value| (value.name().to_string(), value.full().to_string())),
+        );
+        #[cfg(feature = "targeted_mixtures")]
+        let target = mixture
+            .targeted_colour()
+            .map(|target: HCV| (fill(&target), delta_e(&target, mixture)));
+        #[cfg(not(feature = "targeted_mixtures"))]
+        let target = None;
+        Self {
+            id: mixture.id().to_string(),
+            name: mixture.name().map(str::to_string),
+            notes: mixture.notes().map(str::to_string),
+            fill: fill(mixture),
+            components,
+            properties,
+            target,
+        }
+    }
+}
+
+/// Escape the characters that Markdown would otherwise interpret.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, Clone)]
+pub struct RecipeSheetBuilder {
+    title: String,
+    format: RecipeFormat,
+}
+
+impl Default for RecipeSheetBuilder {
+    fn default() -> Self {
+        Self {
+            title: "Mixing Recipes".to_string(),
+            format: RecipeFormat::Html,
+        }
+    }
+}
+
+impl RecipeSheetBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(&mut self, title: &str) -> &mut Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn format(&mut self, format: RecipeFormat) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    /// Write a sheet with the recipes of all of the `session`'s mixtures.
+    pub fn write<W: Write>(
+        &self,
+        session: &MixingSession,
+        writer: &mut W,
+    ) -> Result<(), crate::Error> {
+        let recipes: Vec<Recipe> = session
+            .mixtures()
+            .map(|mixture| Recipe::new(mixture.as_ref()))
+            .collect();
+        match self.format {
+            RecipeFormat::Html => self.write_html(session.notes(), &recipes, writer),
+            RecipeFormat::Markdown => self.write_markdown(session.notes(), &recipes, writer),
+        }
+    }
+
+    fn write_html<W: Write>(
+        &self,
+        notes: &str,
+        recipes: &[Recipe],
+        writer: &mut W,
+    ) -> Result<(), crate::Error> {
+        let swatch = |fill: &str, size: u32| {
+            format!(
+                r#"<span class="swatch" style="background: {fill}; width: {size}px; height: {size}px;" title="{fill}"></span>"#
+            )
+        };
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, r#"<html><head><meta charset="utf-8">"#)?;
+        writeln!(writer, "<title>{}</title>", escape(&self.title))?;
+        writeln!(writer, "<style>")?;
+        writeln!(writer, "body {{ font-family: sans-serif; }}")?;
+        writeln!(
+            writer,
+            ".recipe {{ border: 1px solid black; margin: 1em 0; padding: 0.5em; page-break-inside: avoid; }}"
+        )?;
+        writeln!(
+            writer,
+            ".swatch {{ display: inline-block; border: 1px solid black; vertical-align: middle; print-color-adjust: exact; -webkit-print-color-adjust: exact; }}"
+        )?;
+        writeln!(
+            writer,
+            "table {{ border-collapse: collapse; }} td, th {{ border: 1px solid gray; padding: 2px 6px; }}"
+        )?;
+        writeln!(writer, "</style></head><body>")?;
+        writeln!(writer, "<h1>{}</h1>", escape(&self.title))?;
+        if !notes.is_empty() {
+            writeln!(writer, "<p>{}</p>", escape(notes))?;
+        }
+        for recipe in recipes {
+            writeln!(writer, r#"<div class="recipe">"#)?;
+            write!(
+                writer,
+                "<h2>{} {}",
+                swatch(&recipe.fill, 32),
+                escape(&recipe.id)
+            )?;
+            if let Some(name) = &recipe.name {
+                write!(writer, ": {}", escape(name))?;
+            }
+            writeln!(writer, "</h2>")?;
+            if let Some(notes) = &recipe.notes {
+                writeln!(writer, "<p>{}</p>", escape(notes))?;
+            }
+            if let Some((target_fill, delta_e)) = &recipe.target {
+                writeln!(
+                    writer,
+                    "<p>Target: {} {} ΔE {:.2}</p>",
+                    swatch(target_fill, 24),
+                    target_fill,
+                    delta_e
+                )?;
+            }
+            writeln!(
+                writer,
+                "<table><tr><th></th><th>Component</th><th>Parts</th><th>%</th></tr>"
+            )?;
+            for component in recipe.components.iter() {
+                writeln!(
+                    writer,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.1}</td></tr>",
+                    swatch(&component.fill, 16),
+                    escape(&component.label),
+                    component.parts,
+                    component.percentage
+                )?;
+            }
+            writeln!(writer, "</table>")?;
+            if !recipe.properties.is_empty() {
+                let properties: Vec<String> = recipe
+                    .properties
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", escape(name), escape(value)))
+                    .collect();
+                writeln!(writer, "<p>Properties: {}</p>", properties.join("; "))?;
+            }
+            writeln!(writer, "</div>")?;
+        }
+        writeln!(writer, "</body></html>")?;
+        Ok(())
+    }
+
+    fn write_markdown<W: Write>(
+        &self,
+        notes: &str,
+        recipes: &[Recipe],
+        writer: &mut W,
+    ) -> Result<(), crate::Error> {
+        // Markdown has no colours of its own so swatches are inline HTML (where the renderer
+        // allows it) followed by the colour's code.
+        let swatch = |fill: &str| {
+            format!(
+                r#"<span style="background: {fill}; border: 1px solid black;">&nbsp;&nbsp;&nbsp;&nbsp;</span> `{fill}`"#
+            )
+        };
+        writeln!(writer, "# {}", escape_markdown(&self.title))?;
+        if !notes.is_empty() {
+            writeln!(writer)?;
+            writeln!(writer, "{}", escape_markdown(notes))?;
+        }
+        for recipe in recipes {
+            writeln!(writer)?;
+            write!(writer, "## {}", escape_markdown(&recipe.id))?;
+            if let Some(name) = &recipe.name {
+                write!(writer, ": {}", escape_markdown(name))?;
+            }
+            writeln!(writer)?;
+            writeln!(writer)?;
+            writeln!(writer, "Colour: {}", swatch(&recipe.fill))?;
+            if let Some((target_fill, delta_e)) = &recipe.target {
+                writeln!(writer)?;
+                writeln!(writer, "Target: {} ΔE {:.2}", swatch(target_fill), delta_e)?;
+            }
+            if let Some(notes) = &recipe.notes {
+                writeln!(writer)?;
+                writeln!(writer, "{}", escape_markdown(notes))?;
+            }
+            writeln!(writer)?;
+            writeln!(writer, "| Colour | Component | Parts | % |")?;
+            writeln!(writer, "|---|---|---:|---:|")?;
+            for component in recipe.components.iter() {
+                writeln!(
+                    writer,
+                    "| {} | {} | {} | {:.1} |",
+                    swatch(&component.fill),
+                    escape_markdown(&component.label),
+                    component.parts,
+                    component.percentage
+                )?;
+            }
+            if !recipe.properties.is_empty() {
+                writeln!(writer)?;
+                let properties: Vec<String> = recipe
+                    .properties
+                    .iter()
+                    .map(|(name, value)| {
+                        format!("{}: {}", escape_markdown(name), escape_markdown(value))
+                    })
+                    .collect();
+                writeln!(writer, "Properties: {}", properties.join("; "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write a sheet with the recipes of all of `session`'s mixtures in `format`.
+pub fn write_recipe_sheet<W: Write>(
+    session: &MixingSession,
+    format: RecipeFormat,
+    writer: &mut W,
+) -> Result<(), crate::Error> {
+    RecipeSheetBuilder::new()
+        .format(format)
+        .write(session, writer)
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{
+        mixtures::MixtureBuilder,
+        properties::{Property, Transparency},
+        series::{test_series_of_specs, BasicPaintSpec},
+    };
+    use colour_math::{HueConstants, HCV};
+
+    #[test]
+    fn recipe_sheets() {
+        let mut red = BasicPaintSpec::new(&HCV::RED, "red");
+        red.set_property(Property::Transparency(Transparency::Opaque));
+        let mut yellow = BasicPaintSpec::new(&HCV::YELLOW, "yellow");
+        yellow.set_property(Property::Transparency(Transparency::Opaque));
+        let series = test_series_of_specs(&[red, yellow]);
+        let orange = MixtureBuilder::new("MIX#001")
+            .name("orange <warm>")
+            .series_paint_component((Rc::clone(series.find("red").unwrap()), 1))
+            .series_paint_component((Rc::clone(series.find("yellow").unwrap()), 3))
+            .build();
+        let mut session = MixingSession::new();
+        session.set_notes("bench | session");
+        session.add_mixture(&orange);
+
+        let mut html = vec![];
+        write_recipe_sheet(&session, RecipeFormat::Html, &mut html).unwrap();
+        let html = String::from_utf8(html).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("MIX#001: orange &lt;warm&gt;"));
+        assert!(html.contains("<td>75.0</td>"));
+        assert!(html.contains("Transparency: opaque"));
+
+        let mut markdown = vec![];
+        RecipeSheetBuilder::new()
+            .title("Orange")
+            .format(RecipeFormat::Markdown)
+            .write(&session, &mut markdown)
+            .unwrap();
+        let markdown = String::from_utf8(markdown).unwrap();
+        assert!(markdown.starts_with("# Orange\n\nbench \\| session\n"));
+        assert!(markdown.contains("## MIX#001: orange \\<warm\\>"));
+        assert!(markdown.contains("| 1 | 25.0 |"));
+        assert!(markdown.contains("| 3 | 75.0 |"));
+    }
+}
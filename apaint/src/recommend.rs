@@ -0,0 +1,294 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Recommend a small palette of paints from which a set of target colours can be mixed.
+//!
+//! Finding the smallest such palette is a set cover problem so a greedy approximation is
+//! used: at each step the paint that brings the most targets within tolerance (using
+//! single paints and two paint mixes of the paints already chosen) is added.  If no paint
+//! brings a new target within tolerance the one that gets the uncovered targets closest
+//! is chosen instead.
+
+use std::rc::Rc;
+
+use gcd::Gcd;
+
+use colour_math::{mixing::SubtractiveMixer, ColourBasics, HCV};
+
+use crate::{
+    conversion::{ColourMatch, TwoPaintMix},
+    delta_e::delta_e,
+    series::SeriesPaint,
+};
+
+#[derive(Debug, Clone)]
+pub struct PaletteRecommenderBuilder {
+    tolerance: f64,
+    max_paints: usize,
+    max_parts: u64,
+}
+
+impl Default for PaletteRecommenderBuilder {
+    fn default() -> Self {
+        Self {
+            tolerance: 5.0,
+            max_paints: 12,
+            max_parts: 3,
+        }
+    }
+}
+
+/// A colour that can be made from the candidate paints (identified by index).
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    colour: HCV,
+    first: (usize, u64),
+    second: Option<(usize, u64)>,
+}
+
+impl PaletteRecommenderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A target is covered if it can be matched within this ΔE.
+    pub fn tolerance(&mut self, tolerance: f64) -> &mut Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The largest number of paints to recommend.
+    pub fn max_paints(&mut self, max_paints: usize) -> &mut Self {
+        self.max_paints = max_paints;
+        self
+    }
+
+    /// The largest number of parts of either component tried in mixes.
+    pub fn max_parts(&mut self, max_parts: u64) -> &mut Self {
+        self.max_parts = max_parts.max(1);
+        self
+    }
+
+    /// The colours that become available if `candidate` is added to `chosen`.
+    fn samples(&self, colours: &[HCV], candidate: usize, chosen: &[usize]) -> Vec<Sample> {
+        let mut samples = vec![Sample {
+            colour: colours[candidate],
+            first: (candidate, 1),
+            second: None,
+        }];
+        for other in chosen.iter() {
+            for first_parts in 1..=self.max_parts {
+                for second_parts in 1..=self.max_parts {
+                    if first_parts.gcd(second_parts) != 1 {
+                        continue;
+                    }
+                    let mut mixer = SubtractiveMixer::new();
+                    mixer.add(&colours[candidate], first_parts);
+                    mixer.add(&colours[*other], second_parts);
+                    if let Some(colour) = mixer.mixed_colour() {
+                        samples.push(Sample {
+                            colour,
+                            first: (candidate, first_parts),
+                            second: Some((*other, second_parts)),
+                        });
+                    }
+                }
+            }
+        }
+        samples
+    }
+
+    /// The number of targets that would be newly covered by `samples` and the total ΔE of
+    /// those that would remain uncovered.
+    fn evaluate(
+        &self,
+        targets: &[HCV],
+        best: &[Option<(f64, Sample)>],
+        samples: &[Sample],
+    ) -> (usize, f64) {
+        let mut covered = 0;
+        let mut residual = 0.0;
+        for (target, best) in targets.iter().zip(best.iter()) {
+            let current = best.map(|(distance, _)| distance).unwrap_or(f64::MAX);
+            if current <= self.tolerance {
+                continue;
+            }
+            let distance = samples
+                .iter()
+                .map(|sample| delta_e(target, &sample.colour))
+                .fold(current, f64::min);
+            if distance <= self.tolerance {
+                covered += 1;
+            } else {
+                residual += distance;
+            }
+        }
+        (covered, residual)
+    }
+
+    /// Choose paints from `candidates` with which to mix `targets`.
+    pub fn build(
+        &self,
+        targets: &[Rc<SeriesPaint>],
+        candidates: &[Rc<SeriesPaint>],
+    ) -> PaletteRecommendation {
+        let target_colours: Vec<HCV> = targets.iter().map(|target| target.hcv()).collect();
+        let colours: Vec<HCV> = candidates.iter().map(|paint| paint.hcv()).collect();
+        let mut chosen: Vec<usize> = vec![];
+        let mut best: Vec<Option<(f64, Sample)>> = vec![None; targets.len()];
+        let mut residual = f64::MAX;
+        while chosen.len() < self.max_paints
+            && best
+                .iter()
+                .any(|best| !matches!(best, Some((distance, _)) if *distance <= self.tolerance))
+        {
+            let mut pick: Option<(usize, usize, f64, Vec<Sample>)> = None;
+            for candidate in 0..candidates.len() {
+                if chosen.contains(&candidate) {
+                    continue;
+                }
+                let samples = self.samples(&colours, candidate, &chosen);
+                let (covered, remaining) = self.evaluate(&target_colours, &best, &samples);
+                let better = match &pick {
+                    Some((_, best_covered, best_remaining, _)) => {
+                        covered > *best_covered
+                            || (covered == *best_covered && remaining < *best_remaining)
+                    }
+                    None => true,
+                };
+                if better {
+                    pick = Some((candidate, covered, remaining, samples));
+                }
+            }
+            match pick {
+                Some((candidate, covered, remaining, samples))
+                    if covered > 0 || remaining < residual =>
+                {
+                    chosen.push(candidate);
+                    residual = remaining;
+                    for (target, best) in target_colours.iter().zip(best.iter_mut()) {
+                        for sample in samples.iter() {
+                            let distance = delta_e(target, &sample.colour);
+                            if !matches!(best, Some((current, _)) if *current <= distance) {
+                                *best = Some((distance, *sample));
+                            }
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+        let coverage = targets
+            .iter()
+            .zip(best)
+            .map(|(target, best)| TargetCoverage {
+                target: Rc::clone(target),
+                best_match: best.map(|(distance, sample)| match sample.second {
+                    None => ColourMatch::Paint(Rc::clone(&candidates[sample.first.0]), distance),
+                    Some((second, second_parts)) => ColourMatch::Mix(TwoPaintMix {
+                        components: [
+                            (Rc::clone(&candidates[sample.first.0]), sample.first.1),
+                            (Rc::clone(&candidates[second]), second_parts),
+                        ],
+                        colour: sample.colour,
+                        delta_e: distance,
+                    }),
+                }),
+            })
+            .collect();
+        PaletteRecommendation {
+            paints: chosen
+                .into_iter()
+                .map(|index| Rc::clone(&candidates[index]))
+                .collect(),
+            coverage,
+            tolerance: self.tolerance,
+        }
+    }
+}
+
+/// A target and the best match that can be made from the recommended paints.
+#[derive(Debug)]
+pub struct TargetCoverage {
+    pub target: Rc<SeriesPaint>,
+    pub best_match: Option<ColourMatch>,
+}
+
+#[derive(Debug)]
+pub struct PaletteRecommendation {
+    paints: Vec<Rc<SeriesPaint>>,
+    coverage: Vec<TargetCoverage>,
+    tolerance: f64,
+}
+
+impl PaletteRecommendation {
+    /// The recommended paints in the order that they were chosen.
+    pub fn paints(&self) -> &[Rc<SeriesPaint>] {
+        &self.paints
+    }
+
+    /// How each target (in the order given) can be matched.
+    pub fn coverage(&self) -> &[TargetCoverage] {
+        &self.coverage
+    }
+
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    fn is_covered(&self, coverage: &TargetCoverage) -> bool {
+        matches!(&coverage.best_match, Some(best_match) if best_match.delta_e() <= self.tolerance)
+    }
+
+    /// The targets that can't be matched within tolerance by the recommended paints.
+    pub fn uncovered(&self) -> impl Iterator<Item = &TargetCoverage> {
+        self.coverage
+            .iter()
+            .filter(move |coverage| !self.is_covered(coverage))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.uncovered().next().is_none()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{series::test_series, BasicPaintIfce};
+    use colour_math::{HueConstants, RGBConstants};
+
+    fn paints(colours: &[(HCV, &str)]) -> Vec<Rc<SeriesPaint>> {
+        test_series(colours).paints().cloned().collect()
+    }
+
+    #[test]
+    fn recommend_palette() {
+        let mut mixer = SubtractiveMixer::new();
+        mixer.add(&HCV::RED, 1);
+        mixer.add(&HCV::YELLOW, 1);
+        let orange = mixer.mixed_colour().unwrap();
+        let targets = paints(&[(HCV::RED, "t1"), (orange, "t2"), (HCV::YELLOW, "t3")]);
+        let candidates = paints(&[
+            (HCV::BLUE, "blue"),
+            (HCV::RED, "red"),
+            (HCV::WHITE, "white"),
+            (HCV::YELLOW, "yellow"),
+        ]);
+        let recommendation = PaletteRecommenderBuilder::new().build(&targets, &candidates);
+        assert!(recommendation.is_complete());
+        let mut ids: Vec<&str> = recommendation.paints().iter().map(|p| p.id()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec!["red", "yellow"]);
+        assert!(matches!(
+            recommendation.coverage()[1].best_match,
+            Some(ColourMatch::Mix(_))
+        ));
+
+        let recommendation = PaletteRecommenderBuilder::new()
+            .max_paints(1)
+            .build(&targets, &candidates);
+        assert_eq!(recommendation.paints().len(), 1);
+        assert!(!recommendation.is_complete());
+        assert_eq!(recommendation.uncovered().count(), 2);
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Fuzzy searching of paints by id, name and notes.
+//!
+//! A query is split into whitespace separated terms and every term has to match (case
+//! insensitively) at least one of a paint's id, name or notes.  A term matches a field if
+//! it is a substring of the field or, less favourably, if its characters appear in the
+//! field in order.  Matches in the id count for more than those in the name which count
+//! for more than those in the notes.
+
+use std::rc::Rc;
+
+use crate::{
+    series::{SeriesPaint, SeriesPaintSeries},
+    BasicPaintIfce,
+};
+
+const ID_WEIGHT: u32 = 3;
+const NAME_WEIGHT: u32 = 2;
+const NOTES_WEIGHT: u32 = 1;
+
+/// Score how well the (lower case) `term` matches `text`.
+fn term_score(term: &str, text: &str) -> Option<u32> {
+    let text = text.to_lowercase();
+    if text == term {
+        Some(100)
+    } else if text.starts_with(term) {
+        Some(80)
+    } else if let Some(posn) = text.find(term) {
+        let at_word_start = !matches!(text[..posn].chars().last(), Some(c) if c.is_alphanumeric());
+        if at_word_start {
+            Some(70)
+        } else {
+            Some(60)
+        }
+    } else {
+        // Look for the characters in order and penalise the gaps between them.
+        let mut chars = text.char_indices();
+        let mut first: Option<usize> = None;
+        let mut last = 0;
+        for tc in term.chars() {
+            let (index, _) = chars.find(|(_, c)| *c == tc)?;
+            first.get_or_insert(index);
+            last = index;
+        }
+        let span = (last - first? + 1) as u32;
+        Some((40 * term.len() as u32 / span).max(1))
+    }
+}
+
+/// Return the score for `paint` against `query` or `None` if it doesn't match.  An empty
+/// query matches everything with a score of zero.
+pub fn paint_match_score(query: &str, paint: &impl BasicPaintIfce) -> Option<u32> {
+    let mut score = 0;
+    for term in query.split_whitespace() {
+        let term = term.to_lowercase();
+        let best = [
+            term_score(&term, paint.id()).map(|s| s * ID_WEIGHT),
+            paint
+                .name()
+                .and_then(|name| term_score(&term, name).map(|s| s * NAME_WEIGHT)),
+            paint
+                .notes()
+                .and_then(|notes| term_score(&term, notes).map(|s| s * NOTES_WEIGHT)),
+        ]
+        .iter()
+        .flatten()
+        .max()
+        .copied()?;
+        score += best;
+    }
+    Some(score)
+}
+
+/// Search the given series for paints matching `query` and return them (with their
+/// scores) best match first.
+pub fn search_series<'a>(
+    query: &str,
+    series: impl IntoIterator<Item = &'a SeriesPaintSeries>,
+) -> Vec<(Rc<SeriesPaint>, u32)> {
+    let mut results: Vec<(Rc<SeriesPaint>, u32)> = vec![];
+    for series in series {
+        for paint in series.paints() {
+            if let Some(score) = paint_match_score(query, paint.as_ref()) {
+                results.push((Rc::clone(paint), score));
+            }
+        }
+    }
+    results.sort_by(|(a, a_score), (b, b_score)| b_score.cmp(a_score).then_with(|| a.cmp(b)));
+    results
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::series::{test_series_spec, BasicPaintSpec};
+    use colour_math::{HueConstants, HCV};
+
+    fn test_series() -> SeriesPaintSeries {
+        let mut series_spec = test_series_spec("series name");
+        for (id, name, notes) in [
+            ("FS30051", "Leather Brown", "RAL 8027"),
+            ("FS30055", "Brown", ""),
+            ("FS34092", "Medium Green", "Leather seat"),
+            ("FS37875", "White", ""),
+        ] {
+            let mut paint_spec = BasicPaintSpec::new(&HCV::RED, id);
+            paint_spec.name = name.to_string();
+            paint_spec.notes = notes.to_string();
+            series_spec.add(&paint_spec);
+        }
+        SeriesPaintSeries::from(&series_spec)
+    }
+
+    #[test]
+    fn term_scores() {
+        assert_eq!(term_score("brown", "Brown"), Some(100));
+        assert_eq!(term_score("lea", "Leather Brown"), Some(80));
+        assert_eq!(term_score("bro", "Leather Brown"), Some(70));
+        assert_eq!(term_score("rown", "Leather Brown"), Some(60));
+        assert!(term_score("lthr", "Leather Brown").unwrap() < 60);
+        assert_eq!(term_score("xyz", "Leather Brown"), None);
+    }
+
+    #[test]
+    fn search() {
+        let series = test_series();
+        let ids = |query: &str| -> Vec<String> {
+            search_series(query, [&series])
+                .iter()
+                .map(|(paint, _)| paint.id().to_string())
+                .collect()
+        };
+        assert_eq!(ids("leather brown"), vec!["FS30051"]);
+        assert_eq!(ids("30051"), vec!["FS30051"]);
+        assert_eq!(ids("leather"), vec!["FS30051", "FS34092"]);
+        assert_eq!(ids("brown")[0], "FS30055");
+        assert_eq!(ids("").len(), 4);
+        assert!(ids("purple").is_empty());
+    }
+}
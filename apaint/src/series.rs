@@ -1,8 +1,10 @@
 // Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
 
 use std::{
-    convert::From,
+    collections::HashMap,
+    convert::{From, TryFrom},
     fmt,
+    hash::Hash,
     io::{Read, Write},
     rc::Rc,
 };
@@ -18,7 +20,13 @@ use colour_math::{
 };
 
 use crate::{
-    properties::{Finish, Fluorescence, Metallicness, Permanence, Transparency},
+    equivalents::Equivalent,
+    pigments::Pigment,
+    properties::{
+        custom::{CustomProperty, CustomPropertyType, CustomPropertyValue},
+        Finish, Fluorescence, Granulation, LightFastness, Metallicness, Opacity, Permanence,
+        Property, PropertyMixRules, PropertyType, Staining, Transparency,
+    },
     BasicPaintIfce, LabelText, TooltipText,
 };
 use std::cmp::Ordering;
@@ -29,18 +37,29 @@ pub struct SeriesPaint {
     id: String,
     name: String,
     notes: String,
-    finish: Finish,
-    transparency: Transparency,
-    permanence: Permanence,
-    fluorescence: Fluorescence,
-    metallicness: Metallicness,
+    #[paint(properties)]
+    properties: Vec<Property>,
+    #[paint(custom_values)]
+    custom_values: Vec<CustomPropertyValue>,
+    #[paint(pigments)]
+    pigments: Vec<Pigment>,
+    #[paint(single_pigment)]
+    single_pigment: Option<bool>,
+    #[paint(equivalents)]
+    equivalents: Vec<Equivalent>,
     series_id: Rc<SeriesId>,
+    mix_rules: Rc<PropertyMixRules>,
 }
 
 impl SeriesPaint {
     pub fn series_id(&self) -> &Rc<SeriesId> {
         &self.series_id
     }
+
+    /// The series' overrides of the default property mixing rules.
+    pub fn mix_rules(&self) -> &PropertyMixRules {
+        &self.mix_rules
+    }
 }
 
 impl From<(&BasicPaintSpec, &Rc<SeriesId>)> for SeriesPaint {
@@ -50,16 +69,29 @@ impl From<(&BasicPaintSpec, &Rc<SeriesId>)> for SeriesPaint {
             id: spec.0.id.to_string(),
             name: spec.0.name.to_string(),
             notes: spec.0.notes.to_string(),
-            finish: spec.0.finish,
-            transparency: spec.0.transparency,
-            permanence: spec.0.permanence,
-            fluorescence: spec.0.fluorescence,
-            metallicness: spec.0.metallicness,
+            properties: spec.0.properties.clone(),
+            custom_values: vec![],
+            pigments: spec.0.pigments.clone(),
+            single_pigment: spec.0.single_pigment,
+            equivalents: spec.0.equivalents.clone(),
             series_id: Rc::clone(spec.1),
+            mix_rules: Rc::new(PropertyMixRules::default()),
         }
     }
 }
 
+impl From<(&BasicPaintSpec, &Rc<SeriesId>, &[Rc<CustomPropertyType>])> for SeriesPaint {
+    fn from(spec: (&BasicPaintSpec, &Rc<SeriesId>, &[Rc<CustomPropertyType>])) -> Self {
+        let mut paint: Self = (spec.0, spec.1).into();
+        paint.custom_values = spec
+            .2
+            .iter()
+            .filter_map(|property_type| spec.0.custom_value(property_type))
+            .collect();
+        paint
+    }
+}
+
 // TODO: think about not considering series id when testing equality and order
 impl PartialEq for SeriesPaint {
     fn eq(&self, other: &Self) -> bool {
@@ -125,6 +157,9 @@ impl MakeColouredShape for SeriesPaint {
 #[derive(Debug)]
 pub struct SeriesPaintSeries {
     series_id: Rc<SeriesId>,
+    property_types: Vec<PropertyType>,
+    custom_property_types: Vec<Rc<CustomPropertyType>>,
+    mix_rules: Rc<PropertyMixRules>,
     paint_list: Vec<Rc<SeriesPaint>>,
 }
 
@@ -133,6 +168,21 @@ impl SeriesPaintSeries {
         &self.series_id
     }
 
+    /// The properties recorded for each of the series' paints.
+    pub fn property_types(&self) -> &[PropertyType] {
+        &self.property_types
+    }
+
+    /// The custom properties declared (and recorded for each paint) by the series.
+    pub fn custom_property_types(&self) -> &[Rc<CustomPropertyType>] {
+        &self.custom_property_types
+    }
+
+    /// The series' overrides of the default property mixing rules.
+    pub fn mix_rules(&self) -> &PropertyMixRules {
+        &self.mix_rules
+    }
+
     pub fn find(&self, id: &str) -> Option<&Rc<SeriesPaint>> {
         debug_assert!(self.is_sorted_unique());
         match self.paint_list.binary_search_by_key(&id, |p| p.id()) {
@@ -159,13 +209,24 @@ impl From<&SeriesPaintSeriesSpec> for SeriesPaintSeries {
     fn from(spec: &SeriesPaintSeriesSpec) -> Self {
         debug_assert!(spec.is_sorted_unique());
         let series_id = Rc::new(spec.series_id().clone());
+        let custom_property_types: Vec<Rc<CustomPropertyType>> = spec
+            .custom_property_types()
+            .iter()
+            .map(|property_type| Rc::new(property_type.clone()))
+            .collect();
+        let mix_rules = Rc::new(spec.mix_rules().clone());
         let mut paint_list = vec![];
         for paint_spec in spec.paints() {
-            let series_paint: SeriesPaint = (paint_spec, &series_id).into();
+            let mut series_paint: SeriesPaint =
+                (paint_spec, &series_id, custom_property_types.as_slice()).into();
+            series_paint.mix_rules = Rc::clone(&mix_rules);
             paint_list.push(Rc::new(series_paint));
         }
         Self {
             series_id,
+            property_types: spec.property_types().to_vec(),
+            custom_property_types,
+            mix_rules,
             paint_list,
         }
     }
@@ -184,27 +245,164 @@ impl SeriesPaintFinder for SeriesPaintSeries {
     fn get_series_paint(
         &self,
         id: &str,
-        _series_id: Option<&SeriesId>,
+        series_id: Option<&SeriesId>,
     ) -> Result<Rc<SeriesPaint>, crate::Error> {
-        if let Some(paint) = self.find(id) {
-            Ok(Rc::clone(paint))
-        } else {
-            Err(crate::Error::NotFound(id.to_string()))
+        match series_id {
+            Some(series_id) if series_id != self.series_id.as_ref() => {
+                Err(crate::Error::UnknownSeries(series_id.clone()))
+            }
+            _ => match self.find(id) {
+                Some(paint) => Ok(Rc::clone(paint)),
+                None => match series_id {
+                    Some(series_id) => Err(crate::Error::UnknownSeriesPaint(
+                        series_id.clone(),
+                        id.to_string(),
+                    )),
+                    None => Err(crate::Error::NotFound(id.to_string())),
+                },
+            },
+        }
+    }
+}
+
+/// An empty series specification (named `series_name` and owned by "owner") for use in
+/// tests.
+#[cfg(test)]
+pub(crate) fn test_series_spec(series_name: &str) -> SeriesPaintSeriesSpec {
+    let mut series_spec = SeriesPaintSeriesSpec::default();
+    series_spec.set_proprietor("owner");
+    series_spec.set_series_name(series_name);
+    series_spec
+}
+
+/// A series (named "series name" and owned by "owner") of paints with the given colours
+/// and ids (and nothing else) for use in tests.
+#[cfg(test)]
+pub(crate) fn test_series(paints: &[(HCV, &str)]) -> SeriesPaintSeries {
+    let mut series_spec = test_series_spec("series name");
+    for (colour, id) in paints {
+        series_spec.add(&BasicPaintSpec::new(colour, id));
+    }
+    SeriesPaintSeries::from(&series_spec)
+}
+
+/// An index of the paints in a number of series by paint id and name so that paints can
+/// be found without knowing which series they belong to.
+#[derive(Debug, Default)]
+pub struct PaintIndex {
+    by_id: HashMap<String, Vec<Rc<SeriesPaint>>>,
+    by_name: HashMap<String, Vec<Rc<SeriesPaint>>>,
+    by_equivalent: HashMap<Equivalent, Vec<Rc<SeriesPaint>>>,
+}
+
+impl PaintIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert<K: Eq + Hash>(
+        map: &mut HashMap<K, Vec<Rc<SeriesPaint>>>,
+        key: K,
+        paint: &Rc<SeriesPaint>,
+    ) {
+        let candidates = map.entry(key).or_default();
+        if let Err(index) = candidates.binary_search_by(|p| {
+            p.series_id()
+                .cmp(paint.series_id())
+                .then_with(|| p.id().cmp(paint.id()))
+        }) {
+            candidates.insert(index, Rc::clone(paint));
+        }
+    }
+
+    pub fn add_series(&mut self, series: &SeriesPaintSeries) {
+        for paint in series.paints() {
+            Self::insert(&mut self.by_id, paint.id().to_string(), paint);
+            if let Some(name) = paint.name() {
+                Self::insert(&mut self.by_name, name.to_string(), paint);
+            }
+            for equivalent in paint.equivalents() {
+                Self::insert(&mut self.by_equivalent, equivalent.clone(), paint);
+            }
+        }
+    }
+
+    pub fn remove_series(&mut self, series_id: &SeriesId) {
+        for map in [&mut self.by_id, &mut self.by_name] {
+            map.retain(|_, candidates| {
+                candidates.retain(|paint| paint.series_id().as_ref() != series_id);
+                !candidates.is_empty()
+            });
+        }
+        self.by_equivalent.retain(|_, candidates| {
+            candidates.retain(|paint| paint.series_id().as_ref() != series_id);
+            !candidates.is_empty()
+        });
+    }
+
+    /// The paints (from any series) with the given id.
+    pub fn paints_with_id(&self, id: &str) -> &[Rc<SeriesPaint>] {
+        self.by_id.get(id).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// The paints (from any series) with the given name.
+    pub fn paints_with_name(&self, name: &str) -> &[Rc<SeriesPaint>] {
+        self.by_name.get(name).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// The paints (from any series) that claim to match the given standard colour.
+    pub fn paints_claiming(&self, equivalent: &Equivalent) -> &[Rc<SeriesPaint>] {
+        self.by_equivalent
+            .get(equivalent)
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Find the single paint whose id (or, failing that, name) is `id_or_name`.
+    pub fn find(&self, id_or_name: &str) -> Result<Rc<SeriesPaint>, crate::Error> {
+        let mut candidates = self.paints_with_id(id_or_name);
+        if candidates.is_empty() {
+            candidates = self.paints_with_name(id_or_name);
+        }
+        match candidates.len() {
+            0 => Err(crate::Error::NotFound(id_or_name.to_string())),
+            1 => Ok(Rc::clone(&candidates[0])),
+            _ => Err(crate::Error::AmbiguousPaint(
+                id_or_name.to_string(),
+                candidates
+                    .iter()
+                    .map(|paint| (paint.series_id().into(), paint.id().to_string()))
+                    .collect(),
+            )),
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, Colour, BasicPaint, Clone, PartialEq)]
+#[serde(from = "BasicPaintSpecRecord")]
 pub struct BasicPaintSpec {
     pub colour: HCV,
     pub id: String,
     pub name: String,
     pub notes: String,
-    pub finish: Finish,
-    pub transparency: Transparency,
-    pub permanence: Permanence,
-    pub fluorescence: Fluorescence,
-    pub metallicness: Metallicness,
+    /// At most one value per property type (in property type order).
+    #[paint(properties)]
+    pub properties: Vec<Property>,
+    /// Values of the custom properties declared by the series (in declaration order).
+    #[paint(custom_properties)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_properties: Vec<CustomProperty>,
+    /// The Colour Index codes of the paint's pigments (sorted).
+    #[paint(pigments)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pigments: Vec<Pigment>,
+    /// Overrides the assumption that a paint is single pigment iff it has one pigment.
+    #[paint(single_pigment)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub single_pigment: Option<bool>,
+    #[paint(equivalents)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub equivalents: Vec<Equivalent>,
 }
 
 impl BasicPaintSpec {
@@ -214,12 +412,155 @@ impl BasicPaintSpec {
             id: id.to_string(),
             name: String::new(),
             notes: String::new(),
-            finish: Finish::default(),
-            transparency: Transparency::default(),
-            permanence: Permanence::default(),
-            fluorescence: Fluorescence::default(),
-            metallicness: Metallicness::default(),
+            properties: vec![],
+            custom_properties: vec![],
+            pigments: vec![],
+            single_pigment: None,
+            equivalents: vec![],
+        }
+    }
+
+    /// Record `property` replacing (and returning) any existing value of the same type.
+    pub fn set_property(&mut self, property: Property) -> Option<Property> {
+        match self
+            .properties
+            .binary_search_by_key(&property.property_type(), |p| p.property_type())
+        {
+            Ok(index) => Some(std::mem::replace(&mut self.properties[index], property)),
+            Err(index) => {
+                self.properties.insert(index, property);
+                None
+            }
+        }
+    }
+
+    pub fn remove_property(&mut self, property_type: PropertyType) -> Option<Property> {
+        match self
+            .properties
+            .binary_search_by_key(&property_type, |p| p.property_type())
+        {
+            Ok(index) => Some(self.properties.remove(index)),
+            Err(_) => None,
+        }
+    }
+
+    /// Record exactly the properties in `property_types` keeping existing values and using
+    /// default values for those not previously recorded.
+    pub fn conform(&mut self, property_types: &[PropertyType]) {
+        self.properties
+            .retain(|property| property_types.contains(&property.property_type()));
+        for property_type in property_types.iter() {
+            if self.recorded_property(*property_type).is_none() {
+                self.set_property(property_type.default_value());
+            }
+        }
+    }
+
+    /// The recorded value (full name) of the custom property called `name`.
+    pub fn custom_property(&self, name: &str) -> Option<&str> {
+        self.custom_properties
+            .iter()
+            .find(|property| property.name == name)
+            .map(|property| property.value.as_str())
+    }
+
+    /// Record `value` (full name or abbreviation) for the custom property called `name`
+    /// returning the previous value.  The value is checked when the paint is added to a series.
+    pub fn set_custom_property(&mut self, name: &str, value: &str) -> Option<String> {
+        match self
+            .custom_properties
+            .iter_mut()
+            .find(|property| property.name == name)
+        {
+            Some(property) => Some(std::mem::replace(&mut property.value, value.to_string())),
+            None => {
+                self.custom_properties.push(CustomProperty {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    /// Record exactly the custom properties in `property_types` (in the same order) using
+    /// the first value for those not previously recorded (or recorded with an unknown value).
+    pub fn conform_custom(&mut self, property_types: &[CustomPropertyType]) {
+        self.custom_properties = property_types
+            .iter()
+            .map(|property_type| {
+                let index = self
+                    .custom_property(property_type.name())
+                    .and_then(|value| property_type.index_of(value))
+                    .unwrap_or(0);
+                CustomProperty {
+                    name: property_type.name().to_string(),
+                    value: property_type.values()[index].full.clone(),
+                }
+            })
+            .collect();
+    }
+}
+
+/// A paint specification as read from a series file.  Files written before series declared
+/// which properties they record have a field for each property instead of a property list.
+#[derive(Deserialize)]
+struct BasicPaintSpecRecord {
+    colour: HCV,
+    id: String,
+    name: String,
+    notes: String,
+    #[serde(default)]
+    properties: Vec<Property>,
+    finish: Option<Finish>,
+    transparency: Option<Transparency>,
+    permanence: Option<Permanence>,
+    fluorescence: Option<Fluorescence>,
+    metallicness: Option<Metallicness>,
+    light_fastness: Option<LightFastness>,
+    opacity: Option<Opacity>,
+    staining: Option<Staining>,
+    granulation: Option<Granulation>,
+    #[serde(default)]
+    custom_properties: Vec<CustomProperty>,
+    #[serde(default)]
+    pigments: Vec<Pigment>,
+    #[serde(default)]
+    single_pigment: Option<bool>,
+    #[serde(default)]
+    equivalents: Vec<Equivalent>,
+}
+
+impl From<BasicPaintSpecRecord> for BasicPaintSpec {
+    fn from(record: BasicPaintSpecRecord) -> Self {
+        let mut spec = Self {
+            colour: record.colour,
+            id: record.id,
+            name: record.name,
+            notes: record.notes,
+            properties: vec![],
+            custom_properties: record.custom_properties,
+            pigments: record.pigments,
+            single_pigment: record.single_pigment,
+            equivalents: record.equivalents,
+        };
+        let fields = [
+            record.finish.map(Property::Finish),
+            record.transparency.map(Property::Transparency),
+            record.permanence.map(Property::Permanence),
+            record.fluorescence.map(Property::Fluorescence),
+            record.metallicness.map(Property::Metallicness),
+            record.light_fastness.map(Property::LightFastness),
+            record.opacity.map(Property::Opacity),
+            record.staining.map(Property::Staining),
+            record.granulation.map(Property::Granulation),
+        ];
+        for property in fields.iter().flatten().chain(record.properties.iter()) {
+            spec.set_property(*property);
         }
+        spec.pigments.sort();
+        spec.pigments.dedup();
+        spec
     }
 }
 
@@ -240,7 +581,7 @@ impl MakeColouredShape for BasicPaintSpec {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, PartialOrd, Ord, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, PartialOrd, Ord, PartialEq, Eq, Hash, Clone)]
 pub struct SeriesId {
     pub(crate) proprietor: String,
     pub(crate) series_name: String,
@@ -278,17 +619,164 @@ impl From<&Rc<SeriesId>> for SeriesId {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(try_from = "SeriesPaintSeriesSpecRecord")]
 pub struct SeriesPaintSeriesSpec {
     pub(crate) series_id: SeriesId,
+    pub(crate) property_types: Vec<PropertyType>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) custom_property_types: Vec<CustomPropertyType>,
+    #[serde(default, skip_serializing_if = "PropertyMixRules::is_empty")]
+    pub(crate) mix_rules: PropertyMixRules,
     pub(crate) paint_list: Vec<BasicPaintSpec>,
 }
 
+impl Default for SeriesPaintSeriesSpec {
+    fn default() -> Self {
+        Self {
+            series_id: SeriesId::default(),
+            property_types: PropertyType::BASIC.to_vec(),
+            custom_property_types: vec![],
+            mix_rules: PropertyMixRules::default(),
+            paint_list: vec![],
+        }
+    }
+}
+
+/// A series specification as read from a file.  Series written before series declared
+/// which properties they record are taken to record those that their paints have.
+#[derive(Deserialize)]
+struct SeriesPaintSeriesSpecRecord {
+    series_id: SeriesId,
+    property_types: Option<Vec<PropertyType>>,
+    #[serde(default)]
+    custom_property_types: Vec<CustomPropertyType>,
+    #[serde(default)]
+    mix_rules: PropertyMixRules,
+    paint_list: Vec<BasicPaintSpec>,
+}
+
+impl TryFrom<SeriesPaintSeriesSpecRecord> for SeriesPaintSeriesSpec {
+    type Error = crate::Error;
+
+    fn try_from(record: SeriesPaintSeriesSpecRecord) -> Result<Self, crate::Error> {
+        let property_types = match record.property_types {
+            Some(property_types) => property_types,
+            None => {
+                let mut property_types: Vec<PropertyType> = record
+                    .paint_list
+                    .iter()
+                    .flat_map(|paint| paint.properties.iter().map(|p| p.property_type()))
+                    .collect();
+                if property_types.is_empty() {
+                    property_types = PropertyType::BASIC.to_vec();
+                }
+                property_types
+            }
+        };
+        let mut spec = Self {
+            series_id: record.series_id,
+            property_types: vec![],
+            custom_property_types: vec![],
+            mix_rules: record.mix_rules,
+            paint_list: vec![],
+        };
+        spec.set_property_types(&property_types);
+        for property_type in record.custom_property_types {
+            spec.add_custom_property_type(property_type)?;
+        }
+        for paint in record.paint_list.iter() {
+            spec.add(paint);
+        }
+        Ok(spec)
+    }
+}
+
 impl SeriesPaintSeriesSpec {
     pub fn series_id(&self) -> &SeriesId {
         &self.series_id
     }
 
+    /// The properties recorded for each of the series' paints.
+    pub fn property_types(&self) -> &[PropertyType] {
+        &self.property_types
+    }
+
+    /// Declare the properties recorded by the series.  Paints lose the values of properties
+    /// that are no longer declared and get default values for newly declared ones.
+    pub fn set_property_types(&mut self, property_types: &[PropertyType]) {
+        let mut property_types = property_types.to_vec();
+        property_types.sort();
+        property_types.dedup();
+        for paint in self.paint_list.iter_mut() {
+            paint.conform(&property_types);
+        }
+        self.property_types = property_types;
+    }
+
+    pub fn add_property_type(&mut self, property_type: PropertyType) {
+        if !self.property_types.contains(&property_type) {
+            let mut property_types = self.property_types.clone();
+            property_types.push(property_type);
+            self.set_property_types(&property_types);
+        }
+    }
+
+    pub fn remove_property_type(&mut self, property_type: PropertyType) {
+        if self.property_types.contains(&property_type) {
+            let mut property_types = self.property_types.clone();
+            property_types.retain(|pt| *pt != property_type);
+            self.set_property_types(&property_types);
+        }
+    }
+
+    /// The custom properties declared by the series (in declaration order).
+    pub fn custom_property_types(&self) -> &[CustomPropertyType] {
+        &self.custom_property_types
+    }
+
+    /// Declare a custom property (or redeclare one with the same name) for the series'
+    /// paints.  Paints keep any existing values that are still valid.
+    pub fn add_custom_property_type(
+        &mut self,
+        property_type: CustomPropertyType,
+    ) -> Result<(), crate::Error> {
+        property_type.validate()?;
+        match self
+            .custom_property_types
+            .iter_mut()
+            .find(|pt| pt.name() == property_type.name())
+        {
+            Some(existing) => *existing = property_type,
+            None => self.custom_property_types.push(property_type),
+        }
+        for paint in self.paint_list.iter_mut() {
+            paint.conform_custom(&self.custom_property_types);
+        }
+        Ok(())
+    }
+
+    pub fn remove_custom_property_type(&mut self, name: &str) -> Option<CustomPropertyType> {
+        let index = self
+            .custom_property_types
+            .iter()
+            .position(|pt| pt.name() == name)?;
+        let property_type = self.custom_property_types.remove(index);
+        for paint in self.paint_list.iter_mut() {
+            paint.conform_custom(&self.custom_property_types);
+        }
+        Some(property_type)
+    }
+
+    /// The series' overrides of the default property mixing rules.
+    pub fn mix_rules(&self) -> &PropertyMixRules {
+        &self.mix_rules
+    }
+
+    pub fn set_mix_rules(&mut self, mix_rules: &PropertyMixRules) {
+        self.mix_rules = mix_rules.clone()
+    }
+
     pub fn set_proprietor(&mut self, proprietor: &str) {
         self.series_id.proprietor = proprietor.to_string()
     }
@@ -301,20 +789,25 @@ impl SeriesPaintSeriesSpec {
         self.paint_list.iter()
     }
 
+    /// Add `paint` (recording only the series' declared properties) replacing and
+    /// returning any paint with the same id.
     pub fn add(&mut self, paint: &BasicPaintSpec) -> Option<BasicPaintSpec> {
         debug_assert!(self.is_sorted_unique());
+        let mut paint = paint.clone();
+        paint.conform(&self.property_types);
+        paint.conform_custom(&self.custom_property_types);
         match self
             .paint_list
             .binary_search_by_key(&paint.id(), |p| p.id())
         {
             Ok(index) => {
-                self.paint_list.push(paint.clone());
+                self.paint_list.push(paint);
                 let old = self.paint_list.swap_remove(index);
                 debug_assert!(self.is_sorted_unique());
                 Some(old)
             }
             Err(index) => {
-                self.paint_list.insert(index, paint.clone());
+                self.paint_list.insert(index, paint);
                 None
             }
         }
@@ -379,9 +872,37 @@ impl SeriesPaintSeriesSpec {
 
 #[cfg(test)]
 mod test {
-    use crate::series::{BasicPaintSpec, SeriesPaintSeriesSpec};
+    use crate::series::{
+        test_series_spec, BasicPaintSpec, PaintIndex, SeriesId, SeriesPaintSeries,
+        SeriesPaintSeriesSpec,
+    };
+    use crate::{
+        equivalents::Equivalent,
+        properties::{
+            custom::CustomPropertyType, Finish, Granulation, LightFastness, Opacity, Property,
+            PropertyType, Staining,
+        },
+        BasicPaintIfce,
+    };
     use colour_math::{HueConstants, HCV, RGB};
 
+    const OLD_FORMAT_SERIES: &str = r###"{
+  "series_id": {"proprietor": "owner", "series_name": "old series"},
+  "paint_list": [
+    {
+      "colour": {"hue": null, "chroma": {"Neither": 0}, "sum": 0},
+      "id": "black",
+      "name": "Black",
+      "notes": "",
+      "finish": "Flat",
+      "transparency": "Opaque",
+      "permanence": "Permanent",
+      "fluorescence": "NonFluorescent",
+      "metallicness": "NonMetallic"
+    }
+  ]
+}"###;
+
     #[test]
     fn save_and_recover() {
         let mut series_spec = SeriesPaintSeriesSpec::default();
@@ -399,4 +920,166 @@ mod test {
             assert_eq!(*pspec1, *pspec2);
         }
     }
+
+    #[test]
+    fn declared_properties() {
+        let mut series_spec = SeriesPaintSeriesSpec::default();
+        series_spec.set_property_types(&PropertyType::WATERCOLOUR);
+        let mut paint_spec = BasicPaintSpec::new(&HCV::RED, "red");
+        paint_spec.set_property(Property::Finish(Finish::Flat));
+        paint_spec.set_property(Property::Staining(Staining::HighStaining));
+        series_spec.add(&paint_spec);
+        let red = series_spec.find("red").unwrap();
+        assert_eq!(red.recorded_property(PropertyType::Finish), None);
+        assert_eq!(red.staining(), Staining::HighStaining);
+        assert_eq!(red.properties.len(), PropertyType::WATERCOLOUR.len());
+
+        series_spec.add_property_type(PropertyType::Finish);
+        series_spec.remove_property_type(PropertyType::Staining);
+        let red = series_spec.find("red").unwrap();
+        assert_eq!(red.recorded_property(PropertyType::Staining), None);
+        assert_eq!(
+            red.recorded_property(PropertyType::Finish),
+            Some(Property::Finish(Finish::default()))
+        );
+
+        let mut buffer: Vec<u8> = vec![];
+        series_spec.write(&mut buffer).unwrap();
+        let read_spec = SeriesPaintSeriesSpec::read(&mut &buffer[..]).unwrap();
+        assert_eq!(read_spec.property_types(), series_spec.property_types());
+        assert_eq!(read_spec.find("red"), series_spec.find("red"));
+    }
+
+    #[test]
+    fn all_properties() {
+        let mut series_spec = SeriesPaintSeriesSpec::default();
+        series_spec.set_property_types(&PropertyType::ALL);
+        let mut paint_spec = BasicPaintSpec::new(&HCV::RED, "red");
+        paint_spec.set_property(Property::Opacity(Opacity::SemiOpaque));
+        paint_spec.set_property(Property::LightFastness(LightFastness::Fair));
+        paint_spec.set_property(Property::Granulation(Granulation::Granulating));
+        paint_spec.set_property(Property::Staining(Staining::LowStaining));
+        series_spec.add(&paint_spec);
+        let mut buffer: Vec<u8> = vec![];
+        series_spec.write(&mut buffer).unwrap();
+        let read_spec = SeriesPaintSeriesSpec::read(&mut &buffer[..]).unwrap();
+        let series = SeriesPaintSeries::from(&read_spec);
+        let red = series.find("red").unwrap();
+        for property_type in PropertyType::ALL.iter() {
+            let property = red.recorded_property(*property_type).unwrap();
+            assert_eq!(property.property_type(), *property_type);
+            assert_eq!(red.property(*property_type), property);
+        }
+        assert_eq!(red.opacity(), Opacity::SemiOpaque);
+        assert_eq!(red.light_fastness(), LightFastness::Fair);
+        assert_eq!(red.granulation(), Granulation::Granulating);
+        assert_eq!(red.staining(), Staining::LowStaining);
+    }
+
+    #[test]
+    fn custom_properties() {
+        let mut series_spec = SeriesPaintSeriesSpec::default();
+        let medium = CustomPropertyType::new(
+            "Medium",
+            &[("acrylic", "A"), ("enamel", "E"), ("lacquer", "L")],
+            false,
+        )
+        .unwrap();
+        series_spec.add_custom_property_type(medium).unwrap();
+        let mut paint_spec = BasicPaintSpec::new(&HCV::RED, "red");
+        paint_spec.set_custom_property("Medium", "L");
+        paint_spec.set_custom_property("Undeclared", "whatever");
+        series_spec.add(&paint_spec);
+        series_spec.add(&BasicPaintSpec::new(&HCV::BLUE, "blue"));
+        assert_eq!(
+            series_spec.find("red").unwrap().custom_property("Medium"),
+            Some("lacquer")
+        );
+        assert_eq!(
+            series_spec
+                .find("red")
+                .unwrap()
+                .custom_property("Undeclared"),
+            None
+        );
+        assert_eq!(
+            series_spec.find("blue").unwrap().custom_property("Medium"),
+            Some("acrylic")
+        );
+
+        let mut buffer: Vec<u8> = vec![];
+        series_spec.write(&mut buffer).unwrap();
+        let read_spec = SeriesPaintSeriesSpec::read(&mut &buffer[..]).unwrap();
+        assert_eq!(
+            read_spec.custom_property_types(),
+            series_spec.custom_property_types()
+        );
+        let series = SeriesPaintSeries::from(&read_spec);
+        let red = series.find("red").unwrap();
+        assert_eq!(red.custom_values()[0].abbrev(), "L");
+
+        series_spec.remove_custom_property_type("Medium");
+        assert!(series_spec
+            .find("red")
+            .unwrap()
+            .custom_properties
+            .is_empty());
+    }
+
+    #[test]
+    fn read_old_format() {
+        let series_spec = SeriesPaintSeriesSpec::read(&mut OLD_FORMAT_SERIES.as_bytes()).unwrap();
+        assert_eq!(series_spec.property_types(), &PropertyType::BASIC);
+        let black = series_spec.find("black").unwrap();
+        assert_eq!(black.finish(), Finish::Flat);
+        assert_eq!(black.properties.len(), PropertyType::BASIC.len());
+    }
+
+    #[test]
+    fn paint_index() {
+        let mut index = PaintIndex::new();
+        for (series_name, paints) in [
+            (
+                "one",
+                vec![("red", "Red"), ("yellow", "Yellow"), ("vermilion", "Red")],
+            ),
+            ("two", vec![("red", "Scarlet"), ("blue", "Yellow")]),
+        ] {
+            let mut series_spec = test_series_spec(series_name);
+            for (id, name) in paints {
+                let mut paint_spec = BasicPaintSpec::new(&HCV::RED, id);
+                paint_spec.name = name.to_string();
+                paint_spec.equivalents = vec![Equivalent::new("FS", "30051")];
+                series_spec.add(&paint_spec);
+            }
+            index.add_series(&SeriesPaintSeries::from(&series_spec));
+        }
+        assert_eq!(
+            index.find("yellow").unwrap().series_id().series_name(),
+            "one"
+        );
+        assert_eq!(index.find("Scarlet").unwrap().id(), "red");
+        match index.find("red") {
+            Err(crate::Error::AmbiguousPaint(id, matches)) => {
+                assert_eq!(id, "red");
+                assert_eq!(
+                    matches,
+                    vec![
+                        (SeriesId::new("one", "owner"), "red".to_string()),
+                        (SeriesId::new("two", "owner"), "red".to_string()),
+                    ]
+                );
+            }
+            _ => panic!("ambiguity not detected"),
+        }
+        assert_eq!(index.paints_with_name("Yellow").len(), 2);
+        assert_eq!(index.paints_with_name("Red").len(), 2);
+        let fs30051 = Equivalent::new("FS", "30051");
+        assert_eq!(index.paints_claiming(&fs30051).len(), 5);
+        assert!(index.find("green").is_err());
+        index.remove_series(&SeriesId::new("one", "owner"));
+        assert_eq!(index.find("red").unwrap().name(), Some("Scarlet"));
+        assert_eq!(index.find("Yellow").unwrap().id(), "blue");
+        assert_eq!(index.paints_claiming(&fs30051).len(), 2);
+    }
 }
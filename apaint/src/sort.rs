@@ -0,0 +1,204 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Ordering paints by colour rather than by id.
+//!
+//! Greys (which have no hue) follow the hued paints in the hue based orders.  Ties are
+//! broken by id so that the orders are stable.
+
+use std::{cmp::Ordering, rc::Rc};
+
+use colour_math::ScalarAttribute;
+
+use crate::{
+    delta_e::delta_e,
+    mixtures::{MixingSession, Mixture},
+    series::{SeriesPaint, SeriesPaintSeries},
+    BasicPaintIfce,
+};
+
+/// The width (in degrees) of the hue bands used by `PaintOrder::ValueInHueBands`.
+pub const HUE_BAND_DEGREES: f64 = 30.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaintOrder {
+    Id,
+    /// Around the colour wheel starting at red.
+    HueAngle,
+    /// Darkest to lightest within each band of hues.
+    ValueInHueBands,
+    /// Least to most chromatic.
+    Chroma,
+    /// A path from the darkest paint that steps to the nearest (by ΔE) remaining paint.
+    ColourPath,
+}
+
+impl PaintOrder {
+    pub const ALL: [PaintOrder; 5] = [
+        PaintOrder::Id,
+        PaintOrder::HueAngle,
+        PaintOrder::ValueInHueBands,
+        PaintOrder::Chroma,
+        PaintOrder::ColourPath,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Id => "Id",
+            Self::HueAngle => "Hue",
+            Self::ValueInHueBands => "Value within Hue",
+            Self::Chroma => "Chroma",
+            Self::ColourPath => "Colour Path",
+        }
+    }
+}
+
+impl std::fmt::Display for PaintOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The hue angle in degrees from 0 to 360 (if the paint isn't grey).
+fn hue_degrees(paint: &impl BasicPaintIfce) -> Option<f64> {
+    paint
+        .hue_angle()
+        .map(|angle| f64::from(angle).rem_euclid(360.0))
+}
+
+fn compare_f64(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+/// Compare optional keys placing `None` (i.e. greys) last.
+fn compare_hued<K>(a: Option<K>, b: Option<K>, compare: impl Fn(K, K) -> Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => compare(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn chroma(paint: &impl BasicPaintIfce) -> f64 {
+    f64::from(paint.scalar_attribute(ScalarAttribute::Chroma))
+}
+
+fn compare_paints<P: BasicPaintIfce>(a: &P, b: &P, order: PaintOrder) -> Ordering {
+    let value = |paint: &P| f64::from(paint.value());
+    let ordering = match order {
+        PaintOrder::Id | PaintOrder::ColourPath => Ordering::Equal,
+        PaintOrder::HueAngle => compare_hued(hue_degrees(a), hue_degrees(b), compare_f64)
+            .then_with(|| compare_f64(value(a), value(b))),
+        PaintOrder::ValueInHueBands => {
+            let band = |paint: &P| hue_degrees(paint).map(|h| (h / HUE_BAND_DEGREES) as u32);
+            compare_hued(band(a), band(b), |a, b| a.cmp(&b))
+                .then_with(|| compare_f64(value(a), value(b)))
+        }
+        PaintOrder::Chroma => compare_f64(chroma(a), chroma(b))
+            .then_with(|| compare_hued(hue_degrees(a), hue_degrees(b), compare_f64)),
+    };
+    ordering.then_with(|| a.id().cmp(b.id()))
+}
+
+/// Sort `paints` into `order`.
+pub fn sort_paints<P: BasicPaintIfce>(paints: &mut Vec<Rc<P>>, order: PaintOrder) {
+    paints.sort_by(|a, b| compare_paints(a.as_ref(), b.as_ref(), order));
+    if order == PaintOrder::ColourPath && !paints.is_empty() {
+        let darkest = paints
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| compare_f64(f64::from(a.value()), f64::from(b.value())))
+            .map(|(index, _)| index)
+            .expect("not empty");
+        let mut remaining = std::mem::take(paints);
+        paints.push(remaining.remove(darkest));
+        while !remaining.is_empty() {
+            let last = paints.last().expect("not empty");
+            let nearest = remaining
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    compare_f64(
+                        delta_e(last.as_ref(), a.as_ref()),
+                        delta_e(last.as_ref(), b.as_ref()),
+                    )
+                })
+                .map(|(index, _)| index)
+                .expect("not empty");
+            paints.push(remaining.remove(nearest));
+        }
+    }
+}
+
+/// The paints in `paints` in `order`.
+pub fn sorted_paints<'a, P: BasicPaintIfce + 'a>(
+    paints: impl IntoIterator<Item = &'a Rc<P>>,
+    order: PaintOrder,
+) -> Vec<Rc<P>> {
+    let mut paints: Vec<Rc<P>> = paints.into_iter().cloned().collect();
+    sort_paints(&mut paints, order);
+    paints
+}
+
+impl SeriesPaintSeries {
+    /// The series' paints in `order`.
+    pub fn paints_in_order(&self, order: PaintOrder) -> Vec<Rc<SeriesPaint>> {
+        sorted_paints(self.paints(), order)
+    }
+}
+
+impl MixingSession {
+    /// The session's mixtures in `order`.
+    pub fn mixtures_in_order(&self, order: PaintOrder) -> Vec<Rc<Mixture>> {
+        sorted_paints(self.mixtures(), order)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use colour_math::{HueConstants, RGBConstants, HCV};
+
+    fn test_series() -> SeriesPaintSeries {
+        crate::series::test_series(&[
+            (HCV::BLUE, "a"),
+            (HCV::WHITE, "b"),
+            (HCV::RED, "c"),
+            (HCV::BLACK, "d"),
+            (HCV::YELLOW, "e"),
+            (HCV::CYAN, "f"),
+        ])
+    }
+
+    fn ids(series: &SeriesPaintSeries, order: PaintOrder) -> Vec<String> {
+        series
+            .paints_in_order(order)
+            .iter()
+            .map(|paint| paint.id().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn paint_orders() {
+        let series = test_series();
+        assert_eq!(
+            ids(&series, PaintOrder::Id),
+            vec!["a", "b", "c", "d", "e", "f"]
+        );
+        assert_eq!(
+            ids(&series, PaintOrder::HueAngle),
+            vec!["c", "e", "f", "a", "d", "b"]
+        );
+        assert_eq!(
+            ids(&series, PaintOrder::ValueInHueBands),
+            vec!["c", "e", "f", "a", "d", "b"]
+        );
+        assert_eq!(
+            ids(&series, PaintOrder::Chroma),
+            vec!["b", "d", "c", "e", "f", "a"]
+        );
+        let path = ids(&series, PaintOrder::ColourPath);
+        assert_eq!(path[0], "d");
+        assert_eq!(path.len(), 6);
+    }
+}
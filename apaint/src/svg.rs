@@ -0,0 +1,97 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Minimal helpers for writing charts as SVG documents.
+
+use std::io::Write;
+
+use colour_math::ColourBasics;
+
+/// Escape the characters that are special in XML text and attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The colour in the "#RRGGBB" form understood by SVG.
+pub fn fill(colour: &impl ColourBasics) -> String {
+    colour.rgb::<u8>().pango_string()
+}
+
+pub struct SvgWriter<'a, W: Write> {
+    writer: &'a mut W,
+}
+
+impl<'a, W: Write> SvgWriter<'a, W> {
+    pub fn new(writer: &'a mut W, width: f64, height: f64) -> Result<Self, crate::Error> {
+        writeln!(
+            writer,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12">"#
+        )?;
+        Ok(Self { writer })
+    }
+
+    /// A filled rectangle with an optional tooltip.
+    pub fn swatch(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        colour: &impl ColourBasics,
+        title: Option<&str>,
+    ) -> Result<(), crate::Error> {
+        write!(
+            self.writer,
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{}" stroke="black" stroke-width="0.5">"#,
+            fill(colour)
+        )?;
+        if let Some(title) = title {
+            write!(self.writer, "<title>{}</title>", escape(title))?;
+        }
+        writeln!(self.writer, "</rect>")?;
+        Ok(())
+    }
+
+    pub fn text(&mut self, x: f64, y: f64, text: &str) -> Result<(), crate::Error> {
+        writeln!(
+            self.writer,
+            r#"<text x="{x}" y="{y}">{}</text>"#,
+            escape(text)
+        )?;
+        Ok(())
+    }
+
+    pub fn bold_text(&mut self, x: f64, y: f64, text: &str) -> Result<(), crate::Error> {
+        writeln!(
+            self.writer,
+            r#"<text x="{x}" y="{y}" font-weight="bold">{}</text>"#,
+            escape(text)
+        )?;
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), crate::Error> {
+        writeln!(self.writer, "</svg>")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_text() {
+        assert_eq!(escape(r#"<a & "b">"#), "&lt;a &amp; &quot;b&quot;&gt;");
+        assert_eq!(escape("plain"), "plain");
+    }
+}
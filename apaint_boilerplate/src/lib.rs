@@ -11,7 +11,7 @@ extern crate proc_macro;
 use heck::KebabCase;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Ident};
+use syn::{parse_macro_input, Data, DeriveInput, Field, Ident, Meta, NestedMeta, Type};
 
 fn acronym(input: &str) -> String {
     let mut output = String::new();
@@ -160,65 +160,144 @@ pub fn property_derive(input: TokenStream) -> TokenStream {
     proc_macro::TokenStream::from(tokens)
 }
 
-#[proc_macro_derive(BasicPaint, attributes(colour))]
+/// The role of a field marked with `#[paint(<role>)]`.
+fn paint_field_role(field: &Field) -> Option<String> {
+    let attr = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("paint"))?;
+    if let Ok(Meta::List(list)) = attr.parse_meta() {
+        if list.nested.len() == 1 {
+            if let Some(NestedMeta::Meta(Meta::Path(path))) = list.nested.first() {
+                if let Some(role) = path.get_ident() {
+                    return Some(role.to_string());
+                }
+            }
+        }
+    }
+    panic!("'paint' attribute must have the form '#[paint(<role>)]'")
+}
+
+/// The fields that provide the optional parts of the interface are marked with their role:
+/// `#[paint(properties)]` for a declared set of property values or (in older
+/// specifications) `#[paint(property)]` on each field holding a property, and
+/// `#[paint(pigments)]`, `#[paint(single_pigment)]`, `#[paint(custom_values)]`,
+/// `#[paint(custom_properties)]` or `#[paint(equivalents)]`.
+#[proc_macro_derive(BasicPaint, attributes(colour, paint))]
 pub fn basic_paint_interface_derive(input: TokenStream) -> TokenStream {
     let parsed_input: DeriveInput = parse_macro_input!(input);
     let struct_name = parsed_input.ident;
     let (impl_generics, ty_generics, where_clause) = parsed_input.generics.split_for_impl();
-    let tokens = quote! {
-        impl #impl_generics crate::BasicPaintIfce for #struct_name #ty_generics #where_clause {
-            fn id(&self) -> &str {
-                &self.id
-            }
-
-            fn name(&self) -> Option<&str> {
-                if self.name.len() == 0 {
-                    None
-                } else {
-                    Some(&self.name)
+    let mut roles: Vec<(String, Ident)> = vec![];
+    let mut property_fields: Vec<(Ident, Type)> = vec![];
+    if let Data::Struct(s) = &parsed_input.data {
+        for field in s.fields.iter() {
+            if let (Some(role), Some(ident)) = (paint_field_role(field), &field.ident) {
+                match role.as_str() {
+                    "property" => property_fields.push((ident.clone(), field.ty.clone())),
+                    "properties" | "pigments" | "single_pigment" | "custom_values"
+                    | "custom_properties" | "equivalents" => roles.push((role, ident.clone())),
+                    _ => panic!("'{role}': unknown paint field role"),
                 }
             }
-
-            fn notes(&self) -> Option<&str> {
-                if self.notes.len() == 0 {
-                    None
-                } else {
-                    Some(&self.notes)
-                }
+        }
+    }
+    let field_for = |role: &str| {
+        roles
+            .iter()
+            .find(|(field_role, _)| field_role == role)
+            .map(|(_, ident)| ident.clone())
+    };
+    let equivalents_tokens = if let Some(equivalents) = field_for("equivalents") {
+        quote! {
+            fn equivalents(&self) -> &[crate::equivalents::Equivalent] {
+                &self.#equivalents
             }
-
-            fn finish(&self) -> Finish {
-                self.finish
+        }
+    } else {
+        quote! {}
+    };
+    let properties_tokens = if let Some(properties) = field_for("properties") {
+        quote! {
+            fn recorded_properties(&self) -> Vec<crate::properties::Property> {
+                self.#properties.clone()
             }
 
-            fn transparency(&self) -> Transparency {
-                self.transparency
+            fn recorded_property(
+                &self,
+                property_type: crate::properties::PropertyType,
+            ) -> Option<crate::properties::Property> {
+                self.#properties
+                    .iter()
+                    .find(|property| property.property_type() == property_type)
+                    .copied()
             }
-
-            fn fluorescence(&self) -> Fluorescence {
-                self.fluorescence
+        }
+    } else {
+        // the property's type names its `Property` variant
+        let mut tokens = vec![];
+        for (field, ty) in property_fields.iter() {
+            let variant = match ty {
+                Type::Path(path) => &path.path.segments.last().expect("type has a name").ident,
+                _ => panic!("'{field}': a paint property field's type must be a property"),
+            };
+            tokens.push(quote! {
+                crate::properties::Property::#variant(self.#field),
+            });
+        }
+        quote! {
+            fn recorded_properties(&self) -> Vec<crate::properties::Property> {
+                vec![#(#tokens)*]
             }
-
-            fn permanence(&self) -> Permanence {
-                self.permanence
+        }
+    };
+    // a paint with pigments is taken to be single pigment if it has exactly one unless
+    // explicitly flagged otherwise
+    let pigments_tokens = if let Some(pigments) = field_for("pigments") {
+        let single_pigment_tokens = if let Some(single_pigment) = field_for("single_pigment") {
+            quote! {
+                fn is_single_pigment(&self) -> bool {
+                    self.#single_pigment.unwrap_or(self.#pigments.len() == 1)
+                }
+            }
+        } else {
+            quote! {}
+        };
+        quote! {
+            fn pigments(&self) -> &[crate::pigments::Pigment] {
+                &self.#pigments
             }
 
-            fn metallicness(&self) -> Metallicness {
-                self.metallicness
+            #single_pigment_tokens
+        }
+    } else {
+        quote! {}
+    };
+    // custom property values either resolved (series paints) or as recorded (specifications)
+    let custom_tokens = if let Some(custom_values) = field_for("custom_values") {
+        quote! {
+            fn custom_values(&self) -> Vec<crate::properties::custom::CustomPropertyValue> {
+                self.#custom_values.clone()
+            }
+        }
+    } else if let Some(custom_properties) = field_for("custom_properties") {
+        quote! {
+            fn custom_value(
+                &self,
+                property_type: &std::rc::Rc<crate::properties::custom::CustomPropertyType>,
+            ) -> Option<crate::properties::custom::CustomPropertyValue> {
+                let property = self
+                    .#custom_properties
+                    .iter()
+                    .find(|property| property.name == property_type.name())?;
+                crate::properties::custom::CustomPropertyValue::from_str(property_type, &property.value)
             }
         }
+    } else {
+        quote! {}
     };
-
-    proc_macro::TokenStream::from(tokens)
-}
-
-#[proc_macro_derive(Watercolour, attributes(colour))]
-pub fn watercolour_interface_derive(input: TokenStream) -> TokenStream {
-    let parsed_input: DeriveInput = parse_macro_input!(input);
-    let struct_name = parsed_input.ident;
-    let (impl_generics, ty_generics, where_clause) = parsed_input.generics.split_for_impl();
     let tokens = quote! {
-        impl #impl_generics crate::WatercolourIfce for #struct_name #ty_generics #where_clause {
+        impl #impl_generics crate::BasicPaintIfce for #struct_name #ty_generics #where_clause {
             fn id(&self) -> &str {
                 &self.id
             }
@@ -239,25 +318,13 @@ pub fn watercolour_interface_derive(input: TokenStream) -> TokenStream {
                 }
             }
 
-            fn light_fastness(&self) -> LightFastness {
-                self.light_fastness
-            }
+            #properties_tokens
 
-            fn transparency(&self) -> Transparency {
-                self.transparency
-            }
+            #custom_tokens
 
-            fn staining(&self) -> Staining {
-                    self.staining
-            }
+            #pigments_tokens
 
-            fn granulation(&self) -> Granulation {
-                self.granulation
-            }
-
-            fn fluorescence(&self) -> Fluorescence {
-                self.fluorescence
-            }
+            #equivalents_tokens
         }
     };
 
@@ -6,6 +6,7 @@ use std::path::Path;
 use std::rc::Rc;
 
 use pw_gtk_ext::{
+    glib,
     gtk::{self, prelude::*},
     gtkx::{
         list::{ListViewWithPopUpMenu, ListViewWithPopUpMenuBuilder},
@@ -16,12 +17,15 @@ use pw_gtk_ext::{
     wrapper::*,
 };
 
-use colour_math::{beigui::hue_wheel::MakeColouredShape, ColourBasics, ScalarAttribute};
+use colour_math::{beigui::hue_wheel::MakeColouredShape, ScalarAttribute};
 use colour_math_gtk::hue_wheel::{GtkHueWheel, GtkHueWheelBuilder};
 
 use apaint::{
-    legacy::legacy_series::SeriesPaintSeriesSpec00, properties::PropertyType,
-    series::BasicPaintSpec, series::SeriesPaintSeriesSpec, BasicPaintIfce,
+    legacy::legacy_series::SeriesPaintSeriesSpec00,
+    properties::{custom::CustomPropertyType, PropertyMixRules, PropertyType},
+    series::BasicPaintSpec,
+    series::SeriesPaintSeriesSpec,
+    BasicPaintIfce,
 };
 
 use crate::{
@@ -38,14 +42,30 @@ pub struct BasicPaintFactory {
     paint_editor: Rc<BasicPaintSpecEditor>,
     hue_wheel: Rc<GtkHueWheel>,
     list_view: Rc<ListViewWithPopUpMenu>,
+    list_spec: BasicPaintListViewSpec,
     attributes: Vec<ScalarAttribute>,
     properties: Vec<PropertyType>,
+    custom_property_types: RefCell<Vec<Rc<CustomPropertyType>>>,
     paint_series: RefCell<SeriesPaintSeriesSpec>,
     proprietor_entry: gtk::Entry,
     series_name_entry: gtk::Entry,
+    property_type_buttons: Vec<(PropertyType, gtk::CheckButton)>,
 }
 
 impl BasicPaintFactory {
+    /// The number of list columns reserved for the series' custom properties.  Series
+    /// that declare more custom properties than this are refused when loaded.
+    const CUSTOM_COLUMNS: usize = 6;
+
+    fn row(&self, paint_spec: &BasicPaintSpec) -> Vec<glib::Value> {
+        paint_spec.row_with_custom(
+            &self.attributes,
+            &self.properties,
+            &self.custom_property_types.borrow(),
+            self.list_spec.custom_columns(),
+        )
+    }
+
     fn update_saveability(&self) {
         let series = self.paint_series.borrow();
         let series_id = series.series_id();
@@ -70,7 +90,7 @@ impl BasicPaintFactory {
             self.list_view.remove_row(old_paint.id());
         }
         self.hue_wheel.add_item(paint_spec.coloured_shape());
-        let row = paint_spec.row(&self.attributes, &self.properties);
+        let row = self.row(paint_spec);
         self.list_view.add_row(&row);
     }
 
@@ -118,20 +138,12 @@ impl BasicPaintFactory {
                 return;
             }
         }
-        let paint_series = self.paint_series.borrow();
-        let paint = paint_series.find(id).expect("should be there");
-        let mut spec = BasicPaintSpec::new(&paint.hcv(), paint.id());
-        if let Some(name) = paint.name() {
-            spec.name = name.to_string();
-        }
-        if let Some(notes) = paint.notes() {
-            spec.notes = notes.to_string();
-        }
-        spec.finish = paint.finish();
-        spec.permanence = paint.permanence();
-        spec.transparency = paint.transparency();
-        spec.fluorescence = paint.fluorescence();
-        spec.metallicness = paint.metallicness();
+        let spec = self
+            .paint_series
+            .borrow()
+            .find(id)
+            .expect("should be there")
+            .clone();
         self.paint_editor.edit(&spec);
         self.update_editor_needs_saving();
     }
@@ -154,8 +166,78 @@ impl BasicPaintFactory {
         self.proprietor_entry.set_text("");
         self.series_name_entry.set_text("");
         self.paint_series.borrow_mut().remove_all();
+        self.paint_series
+            .borrow_mut()
+            .set_property_types(&PropertyType::BASIC);
+        self.paint_series
+            .borrow_mut()
+            .set_mix_rules(&PropertyMixRules::default());
+        let custom_names: Vec<String> = self
+            .paint_series
+            .borrow()
+            .custom_property_types()
+            .iter()
+            .map(|property_type| property_type.name().to_string())
+            .collect();
+        for name in custom_names.iter() {
+            self.paint_series
+                .borrow_mut()
+                .remove_custom_property_type(name);
+        }
         self.hue_wheel.remove_all();
         self.list_view.remove_all();
+        self.update_property_types();
+        self.update_series_needs_saving();
+    }
+
+    /// Bring the property check buttons, the editor and the list into line with the
+    /// properties (including custom properties) that the series declares.
+    fn update_property_types(&self) {
+        let property_types = self.paint_series.borrow().property_types().to_vec();
+        for (property_type, button) in self.property_type_buttons.iter() {
+            button.set_active(property_types.contains(property_type));
+        }
+        self.paint_editor.set_property_types(&property_types);
+        let custom_property_types = self.paint_series.borrow().custom_property_types().to_vec();
+        self.paint_editor
+            .set_custom_property_types(&custom_property_types);
+        *self.custom_property_types.borrow_mut() =
+            custom_property_types.into_iter().map(Rc::new).collect();
+        self.list_spec
+            .update_custom_columns(&self.list_view, &self.custom_property_types.borrow());
+        self.list_view.remove_all();
+        for paint in self.paint_series.borrow().paints() {
+            let row = self.row(paint);
+            self.list_view.add_row(&row);
+        }
+    }
+
+    fn toggle_property_type(&self, property_type: PropertyType, active: bool) {
+        let is_declared = self
+            .paint_series
+            .borrow()
+            .property_types()
+            .contains(&property_type);
+        if active && !is_declared {
+            self.paint_series
+                .borrow_mut()
+                .add_property_type(property_type);
+        } else if !active && is_declared {
+            let question = format!(
+                "Stop recording '{}'? Its values will be discarded.",
+                property_type.name()
+            );
+            if self.paint_series.borrow().paints().next().is_none()
+                || self.ask_confirm_action(&question, None)
+            {
+                self.paint_series
+                    .borrow_mut()
+                    .remove_property_type(property_type);
+            }
+        } else {
+            return;
+        }
+        self.update_property_types();
         self.update_series_needs_saving();
     }
 
@@ -182,19 +264,35 @@ impl BasicPaintFactory {
                 }
             }
         };
+        if let Some(property_type) = new_series.custom_property_types().get(Self::CUSTOM_COLUMNS) {
+            let reason = format!(
+                "the editor can only show {} custom properties",
+                Self::CUSTOM_COLUMNS
+            );
+            return Err(apaint::Error::InvalidCustomProperty(
+                property_type.name().to_string(),
+                reason,
+            ));
+        }
         self.unguarded_reset();
         let id = new_series.series_id();
         self.proprietor_entry.set_text(id.proprietor());
         self.series_name_entry.set_text(id.series_name());
         {
             let mut series = self.paint_series.borrow_mut();
+            series.set_property_types(new_series.property_types());
+            series.set_mix_rules(new_series.mix_rules());
+            for property_type in new_series.custom_property_types() {
+                series
+                    .add_custom_property_type(property_type.clone())
+                    .expect("validated when read");
+            }
             for paint in new_series.paints() {
                 series.add(paint);
                 self.hue_wheel.add_item(paint.coloured_shape());
-                let row = paint.row(&self.attributes, &self.properties);
-                self.list_view.add_row(&row);
             }
         }
+        self.update_property_types();
         self.update_series_needs_saving();
         self.update_editor_needs_saving();
         let digest = self.paint_series.borrow().digest().expect("unrecoverable");
@@ -262,16 +360,31 @@ impl BasicPaintFactoryBuilder {
         grid.attach(&label, 0, 1, 1, 1);
         let proprietor_entry = gtk::EntryBuilder::new().hexpand(true).build();
         grid.attach(&proprietor_entry, 1, 1, 1, 1);
+        let label = gtk::LabelBuilder::new()
+            .label("Records:")
+            .halign(gtk::Align::End)
+            .build();
+        grid.attach(&label, 0, 2, 1, 1);
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        grid.attach(&hbox, 1, 2, 1, 1);
+        let mut property_type_buttons = vec![];
+        for property_type in self.properties.iter() {
+            let button = gtk::CheckButton::with_label(property_type.name());
+            button.set_active(PropertyType::BASIC.contains(property_type));
+            hbox.pack_start(&button, false, false, 0);
+            property_type_buttons.push((*property_type, button));
+        }
         let paned = gtk::Paned::new(gtk::Orientation::Horizontal);
         let paint_editor = BasicPaintSpecEditor::new(&self.attributes, &self.properties);
         let hue_wheel = GtkHueWheelBuilder::new()
             .menu_item_specs(menu_items)
             .attributes(&self.attributes)
             .build();
-        let paint_list_spec = BasicPaintListViewSpec::new(&self.attributes, &self.properties);
+        let mut list_spec = BasicPaintListViewSpec::new(&self.attributes, &self.properties);
+        list_spec.set_custom_columns(BasicPaintFactory::CUSTOM_COLUMNS);
         let list_view = ListViewWithPopUpMenuBuilder::new()
             .menu_items(menu_items.to_vec())
-            .build(&paint_list_spec);
+            .build(&list_spec);
         let scrolled_window = gtk::ScrolledWindowBuilder::new().build();
         scrolled_window.add(list_view.pwo());
         let notebook = gtk::NotebookBuilder::new().build();
@@ -302,11 +415,14 @@ impl BasicPaintFactoryBuilder {
             paint_editor,
             hue_wheel,
             list_view,
+            list_spec,
             attributes: self.attributes.to_vec(),
             properties: self.properties.to_vec(),
+            custom_property_types: RefCell::new(vec![]),
             paint_series: RefCell::new(SeriesPaintSeriesSpec::default()),
             proprietor_entry,
             series_name_entry,
+            property_type_buttons,
         });
 
         let bpf_c = Rc::clone(&bpf);
@@ -353,6 +469,14 @@ impl BasicPaintFactoryBuilder {
             bpf_c.update_series_needs_saving();
         });
 
+        for (property_type, button) in bpf.property_type_buttons.iter() {
+            let bpf_c = Rc::clone(&bpf);
+            let property_type = *property_type;
+            button.connect_toggled(move |button| {
+                bpf_c.toggle_property_type(property_type, button.get_active())
+            });
+        }
+
         let bpf_c = Rc::clone(&bpf);
         bpf.file_manager.connect_reset(move || bpf_c.reset());
 
@@ -22,7 +22,10 @@ pub mod properties {
     pub use apaint::properties::{
         Finish, Fluorescence, Metallicness, Permanence, PropertyIfce, PropertyType, Transparency,
     };
-    use apaint::properties::{Granulation, LightFastness, Opacity, Staining};
+    use apaint::properties::{
+        custom::{CustomPropertyType, CustomPropertyValue},
+        Granulation, LightFastness, Opacity, Staining,
+    };
 
     type ChangeCallback<T> = Box<dyn Fn(&T)>;
 
@@ -97,6 +100,68 @@ pub mod properties {
     pub type LightFastnessEntry = PropertyEntry<LightFastness>;
     pub type StainingEntry = PropertyEntry<Staining>;
     pub type OpacityEntry = PropertyEntry<Opacity>;
+
+    /// An entry for the values of a custom property declared by a paint series.
+    #[derive(PWO)]
+    pub struct CustomPropertyEntry {
+        combo_box_text: gtk::ComboBoxText,
+        property_type: Rc<CustomPropertyType>,
+        callbacks: RefCell<Vec<ChangeCallback<Self>>>,
+    }
+
+    impl CustomPropertyEntry {
+        pub fn new(property_type: &Rc<CustomPropertyType>) -> Rc<Self> {
+            let combo_box_text = gtk::ComboBoxText::new();
+            for str_value in property_type.str_values().iter() {
+                combo_box_text.append_text(str_value);
+            }
+            combo_box_text.set_id_column(0);
+            let ce = Rc::new(Self {
+                combo_box_text,
+                property_type: Rc::clone(property_type),
+                callbacks: RefCell::new(vec![]),
+            });
+            ce.set_value(None);
+            let ce_clone = Rc::clone(&ce);
+            ce.combo_box_text.connect_changed(move |_| {
+                for callback in ce_clone.callbacks.borrow().iter() {
+                    callback(&ce_clone);
+                }
+            });
+            ce
+        }
+
+        pub fn property_type(&self) -> &Rc<CustomPropertyType> {
+            &self.property_type
+        }
+
+        pub fn prompt(&self, align: gtk::Align) -> gtk::Label {
+            gtk::LabelBuilder::new()
+                .label(&self.property_type.prompt())
+                .halign(align)
+                .build()
+        }
+
+        pub fn value(&self) -> CustomPropertyValue {
+            self.combo_box_text
+                .get_active_text()
+                .and_then(|text| CustomPropertyValue::from_str(&self.property_type, &text))
+                .unwrap_or_else(|| CustomPropertyValue::new(&self.property_type, 0))
+        }
+
+        /// Set the value by full name or abbreviation (the first value if `None` or invalid).
+        pub fn set_value(&self, new_value: Option<&str>) {
+            let index = new_value
+                .and_then(|value| self.property_type.index_of(value))
+                .unwrap_or(0);
+            let id = self.property_type.values()[index].full.as_str();
+            self.combo_box_text.set_active_id(Some(id));
+        }
+
+        pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) {
+            self.callbacks.borrow_mut().push(Box::new(f))
+        }
+    }
 }
 
 pub mod colour {
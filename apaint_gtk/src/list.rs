@@ -1,15 +1,20 @@
 // Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
 
+use std::{cell::RefCell, rc::Rc, str::FromStr};
+
 use pw_gtk_ext::{
     glib,
     gtk::{self, prelude::*},
-    gtkx::list::ListViewSpec,
+    gtkx::list::{ListViewSpec, ListViewWithPopUpMenu},
+    wrapper::*,
 };
 
 use apaint::{
     mixtures::{Mixture, Paint},
-    properties::PropertyType,
+    properties::{custom::CustomPropertyType, PropertyType},
+    query::PaintQuery,
     series::{BasicPaintSpec, SeriesPaint},
+    sort::PaintOrder,
     BasicPaintIfce,
 };
 
@@ -18,6 +23,7 @@ use colour_math::{ScalarAttribute, HCV};
 pub struct BasicPaintListViewSpec {
     attributes: Vec<ScalarAttribute>,
     properties: Vec<PropertyType>,
+    custom_columns: usize,
 }
 
 impl BasicPaintListViewSpec {
@@ -25,6 +31,42 @@ impl BasicPaintListViewSpec {
         Self {
             attributes: attributes.to_vec(),
             properties: properties.to_vec(),
+            custom_columns: 0,
+        }
+    }
+
+    /// Reserve (initially hidden) columns for up to `custom_columns` custom properties.
+    pub fn set_custom_columns(&mut self, custom_columns: usize) -> &mut Self {
+        self.custom_columns = custom_columns;
+        self
+    }
+
+    pub fn custom_columns(&self) -> usize {
+        self.custom_columns
+    }
+
+    /// Title and show the reserved custom property columns of a list built from this
+    /// specification that are needed for `custom_property_types` and hide the rest.
+    pub fn update_custom_columns(
+        &self,
+        list_view: &ListViewWithPopUpMenu,
+        custom_property_types: &[Rc<CustomPropertyType>],
+    ) {
+        let mut index = 4 + self.attributes.len() + self.properties.len();
+        #[cfg(feature = "targeted_mixtures")]
+        {
+            index += 1;
+        }
+        for i in 0..self.custom_columns {
+            if let Some(col) = list_view.pwo().get_column((index + i) as i32) {
+                match custom_property_types.get(i) {
+                    Some(property_type) => {
+                        col.set_title(&property_type.list_header_name());
+                        col.set_visible(true);
+                    }
+                    None => col.set_visible(false),
+                }
+            }
         }
     }
 }
@@ -40,7 +82,7 @@ impl ListViewSpec for BasicPaintListViewSpec {
             glib::Type::String,
             f64::static_type(),
         ];
-        for _ in 0..self.attributes.len() * 3 + self.properties.len() {
+        for _ in 0..self.attributes.len() * 3 + self.properties.len() + self.custom_columns {
             column_types.push(glib::Type::String);
         }
         #[cfg(feature = "targeted_mixtures")]
@@ -52,7 +94,10 @@ impl ListViewSpec for BasicPaintListViewSpec {
     fn columns(&self) -> Vec<gtk::TreeViewColumn> {
         let mut cols = vec![];
         #[cfg(feature = "targeted_mixtures")]
-        let target_col = 7 + self.attributes.len() as i32 * 3 + self.properties.len() as i32;
+        let target_col = 7
+            + self.attributes.len() as i32 * 3
+            + self.properties.len() as i32
+            + self.custom_columns as i32;
 
         let col = gtk::TreeViewColumnBuilder::new()
             .title("Id")
@@ -147,6 +192,21 @@ impl ListViewSpec for BasicPaintListViewSpec {
             index += 1;
         }
 
+        for _ in 0..self.custom_columns {
+            let col = gtk::TreeViewColumnBuilder::new()
+                .sort_column_id(index)
+                .sort_indicator(true)
+                .visible(false)
+                .build();
+            let cell = gtk::CellRendererTextBuilder::new().editable(false).build();
+            col.pack_start(&cell, false);
+            col.add_attribute(&cell, "text", index);
+            col.add_attribute(&cell, "background", 1);
+            col.add_attribute(&cell, "foreground", 2);
+            cols.push(col);
+            index += 1;
+        }
+
         cols
     }
 }
@@ -181,7 +241,10 @@ pub trait PaintListRow: BasicPaintIfce {
             row.push(attr_rgb.best_foreground().pango_string().to_value());
         }
         for property in properties.iter() {
-            let string = self.property(*property).abbrev();
+            let string = match self.recorded_property(*property) {
+                Some(value) => value.abbrev(),
+                None => "",
+            };
             row.push(string.to_value());
         }
         #[cfg(feature = "targeted_mixtures")]
@@ -190,6 +253,30 @@ pub trait PaintListRow: BasicPaintIfce {
         }
         row
     }
+
+    /// A row for a list whose specification reserves `custom_columns` custom property
+    /// columns (used for `custom_property_types` in order).
+    fn row_with_custom(
+        &self,
+        attributes: &[ScalarAttribute],
+        properties: &[PropertyType],
+        custom_property_types: &[Rc<CustomPropertyType>],
+        custom_columns: usize,
+    ) -> Vec<glib::Value> {
+        let mut row = self.row(attributes, properties);
+        let index = 7 + attributes.len() * 3 + properties.len();
+        for i in (0..custom_columns).rev() {
+            let string = match custom_property_types.get(i) {
+                Some(property_type) => match self.custom_value(property_type) {
+                    Some(value) => value.abbrev().to_string(),
+                    None => "".to_string(),
+                },
+                None => "".to_string(),
+            };
+            row.insert(index, string.to_value());
+        }
+        row
+    }
 }
 
 impl PaintListRow for SeriesPaint {}
@@ -227,7 +314,10 @@ impl PaintListRow for Mixture {
             row.push(attr_rgb.best_foreground().pango_string().to_value());
         }
         for property in properties.iter() {
-            let string = self.property(*property).abbrev();
+            let string = match self.recorded_property(*property) {
+                Some(value) => value.abbrev(),
+                None => "",
+            };
             row.push(string.to_value());
         }
         #[cfg(feature = "targeted_mixtures")]
@@ -241,3 +331,112 @@ impl PaintListRow for Mixture {
 }
 
 impl PaintListRow for Paint {}
+
+type QueryChangedCallback = Box<dyn Fn(&PaintQuery)>;
+
+/// An entry for a query (see `apaint::query`) to filter the paints shown in a list.  While
+/// the text isn't a valid query the last valid query stays in force and a warning icon
+/// (whose tooltip explains the problem) is shown.
+#[derive(PWO)]
+pub struct PaintQueryEntry {
+    entry: gtk::Entry,
+    query: RefCell<PaintQuery>,
+    callbacks: RefCell<Vec<QueryChangedCallback>>,
+}
+
+impl PaintQueryEntry {
+    pub fn new() -> Rc<Self> {
+        let entry = gtk::EntryBuilder::new()
+            .placeholder_text("Filter e.g. transparency <= SemiOpaque && value > 0.6")
+            .build();
+        let qe = Rc::new(Self {
+            entry,
+            query: RefCell::new(PaintQuery::All),
+            callbacks: RefCell::new(vec![]),
+        });
+        let qe_c = Rc::clone(&qe);
+        qe.entry
+            .connect_changed(move |entry| qe_c.update_query(&entry.get_text()));
+        qe
+    }
+
+    fn update_query(&self, text: &str) {
+        match PaintQuery::from_str(text) {
+            Ok(query) => {
+                self.entry
+                    .set_icon_from_icon_name(gtk::EntryIconPosition::Secondary, None);
+                self.entry
+                    .set_icon_tooltip_text(gtk::EntryIconPosition::Secondary, None);
+                *self.query.borrow_mut() = query;
+                let query = self.query.borrow();
+                for callback in self.callbacks.borrow().iter() {
+                    callback(&query);
+                }
+            }
+            Err(err) => {
+                self.entry.set_icon_from_icon_name(
+                    gtk::EntryIconPosition::Secondary,
+                    Some("dialog-warning"),
+                );
+                self.entry.set_icon_tooltip_text(
+                    gtk::EntryIconPosition::Secondary,
+                    Some(&err.to_string()),
+                );
+            }
+        }
+    }
+
+    pub fn query(&self) -> PaintQuery {
+        self.query.borrow().clone()
+    }
+
+    pub fn connect_query_changed<F: Fn(&PaintQuery) + 'static>(&self, callback: F) {
+        self.callbacks.borrow_mut().push(Box::new(callback))
+    }
+}
+
+type OrderChangedCallback = Box<dyn Fn(PaintOrder)>;
+
+/// A selector for the order in which the paints in a list are shown.  (Clicking a column
+/// header still sorts the list by that column.)
+#[derive(PWO)]
+pub struct PaintOrderSelector {
+    combo_box_text: gtk::ComboBoxText,
+    callbacks: RefCell<Vec<OrderChangedCallback>>,
+}
+
+impl PaintOrderSelector {
+    pub fn new() -> Rc<Self> {
+        let combo_box_text = gtk::ComboBoxText::new();
+        for order in PaintOrder::ALL.iter() {
+            combo_box_text.append(Some(order.name()), order.name());
+        }
+        combo_box_text.set_active_id(Some(PaintOrder::Id.name()));
+        combo_box_text.set_tooltip_text(Some("Order in which to list the paints"));
+        let pos = Rc::new(Self {
+            combo_box_text,
+            callbacks: RefCell::new(vec![]),
+        });
+        let pos_c = Rc::clone(&pos);
+        pos.combo_box_text.connect_changed(move |_| {
+            let order = pos_c.order();
+            for callback in pos_c.callbacks.borrow().iter() {
+                callback(order);
+            }
+        });
+        pos
+    }
+
+    pub fn order(&self) -> PaintOrder {
+        let id = self.combo_box_text.get_active_id();
+        PaintOrder::ALL
+            .iter()
+            .find(|order| id.as_deref() == Some(order.name()))
+            .copied()
+            .unwrap_or(PaintOrder::Id)
+    }
+
+    pub fn connect_order_changed<F: Fn(PaintOrder) + 'static>(&self, callback: F) {
+        self.callbacks.borrow_mut().push(Box::new(callback))
+    }
+}
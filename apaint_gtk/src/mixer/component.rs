@@ -206,6 +206,14 @@ impl<P: PartsColour> PartsSpinButtonBox<P> {
             .collect()
     }
 
+    pub fn paints(&self) -> Vec<Rc<P>> {
+        self.spinners
+            .borrow()
+            .iter()
+            .map(|s| Rc::clone(&s.paint))
+            .collect()
+    }
+
     fn binary_search_paint(&self, paint: &P) -> Result<usize, usize> {
         self.spinners
             .borrow()
@@ -281,6 +289,19 @@ impl<P: PartsColour> PartsSpinButtonBox<P> {
         }
     }
 
+    /// Set the parts of the paints in `paint_parts` and zero the parts of all other paints.
+    /// Paints that aren't in the box are ignored.
+    pub fn set_paint_parts(&self, paint_parts: &[(Rc<P>, u64)]) {
+        for spinner in self.spinners.borrow().iter() {
+            let parts = paint_parts
+                .iter()
+                .find(|(paint, _)| *paint == spinner.paint)
+                .map(|(_, parts)| *parts)
+                .unwrap_or(0);
+            spinner.set_parts(parts);
+        }
+    }
+
     pub fn div_all_parts_by(&self, divisor: u64) {
         if divisor > 0 {
             for spinner in self.spinners.borrow().iter() {
@@ -139,8 +139,22 @@ impl MixtureDisplayBuilder {
         vbox.pack_start(cads.pwo(), true, true, 0);
 
         for property_type in self.properties.iter() {
-            let value = mixture.property(*property_type).full();
-            let label = gtk::LabelBuilder::new().label(value).build();
+            if let Some(property) = mixture.recorded_property(*property_type) {
+                let label = gtk::LabelBuilder::new().label(property.full()).build();
+                label.set_widget_colour(&colour);
+                vbox.pack_start(&label, false, false, 0);
+            }
+        }
+        if !mixture.pigments().is_empty() {
+            let codes: Vec<&str> = mixture.pigments().iter().map(|p| p.code()).collect();
+            let text = format!("Pigments: {}", codes.join(", "));
+            let label = gtk::LabelBuilder::new().label(&text).build();
+            label.set_widget_colour(&colour);
+            vbox.pack_start(&label, false, false, 0);
+        }
+        for value in mixture.custom_values() {
+            let text = format!("{}: {}", value.name(), value.full());
+            let label = gtk::LabelBuilder::new().label(&text).build();
             label.set_widget_colour(&colour);
             vbox.pack_start(&label, false, false, 0);
         }
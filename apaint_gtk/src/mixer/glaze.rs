@@ -0,0 +1,159 @@
+// Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use std::{cell::RefCell, rc::Rc};
+
+use pw_gtk_ext::{
+    gtk::{self, prelude::*},
+    wrapper::*,
+};
+
+use colour_math::{ColourBasics, RGBConstants, HCV};
+use colour_math_gtk::coloured::Colourable;
+
+use apaint::{
+    glaze::{dilution_thickness, GlazeLayer, Glazing},
+    series::SeriesPaint,
+    BasicPaintIfce,
+};
+
+/// A preview of the colour produced by glazing one of the palette's paints over white or
+/// another of the palette's paints.
+#[derive(PWO)]
+pub struct GlazePreview {
+    frame: gtk::Frame,
+    paints: RefCell<Vec<Rc<SeriesPaint>>>,
+    substrate_combo: gtk::ComboBoxText,
+    glaze_combo: gtk::ComboBoxText,
+    coats_spin: gtk::SpinButton,
+    dilution_spin: gtk::SpinButton,
+    substrate_label: gtk::Label,
+    result_label: gtk::Label,
+}
+
+impl GlazePreview {
+    const WHITE_ID: &'static str = "WHITE";
+
+    pub fn new() -> Rc<Self> {
+        let frame = gtk::FrameBuilder::new().label("Glaze Preview").build();
+        let substrate_combo = gtk::ComboBoxText::new();
+        substrate_combo.set_tooltip_text(Some("The colour being glazed over."));
+        let glaze_combo = gtk::ComboBoxText::new();
+        glaze_combo.set_tooltip_text(Some("The paint used for the glaze."));
+        let coats_spin = gtk::SpinButtonBuilder::new()
+            .adjustment(&gtk::Adjustment::new(1.0, 1.0, 10.0, 1.0, 2.0, 0.0))
+            .climb_rate(0.0)
+            .digits(0)
+            .numeric(true)
+            .tooltip_text("The number of coats of glaze.")
+            .build();
+        let dilution_spin = gtk::SpinButtonBuilder::new()
+            .adjustment(&gtk::Adjustment::new(0.0, 0.0, 20.0, 1.0, 5.0, 0.0))
+            .climb_rate(0.0)
+            .digits(0)
+            .numeric(true)
+            .tooltip_text("Parts of medium per part of paint in the glaze.")
+            .build();
+        let substrate_label = gtk::Label::new(Some("Substrate"));
+        let result_label = gtk::Label::new(Some("Glazed"));
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        hbox.pack_start(&gtk::Label::new(Some("Over:")), false, false, 0);
+        hbox.pack_start(&substrate_combo, true, true, 0);
+        hbox.pack_start(&gtk::Label::new(Some("Glaze:")), false, false, 0);
+        hbox.pack_start(&glaze_combo, true, true, 0);
+        let vbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        vbox.pack_start(&hbox, false, false, 0);
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        hbox.pack_start(&gtk::Label::new(Some("Coats:")), false, false, 0);
+        hbox.pack_start(&coats_spin, false, false, 0);
+        hbox.pack_start(&gtk::Label::new(Some("Medium:")), false, false, 0);
+        hbox.pack_start(&dilution_spin, false, false, 0);
+        hbox.pack_start(&substrate_label, true, true, 0);
+        hbox.pack_start(&result_label, true, true, 0);
+        vbox.pack_start(&hbox, false, false, 0);
+        frame.add(&vbox);
+        let glaze_preview = Rc::new(Self {
+            frame,
+            paints: RefCell::new(vec![]),
+            substrate_combo,
+            glaze_combo,
+            coats_spin,
+            dilution_spin,
+            substrate_label,
+            result_label,
+        });
+        glaze_preview.set_paints(&[]);
+
+        let glaze_preview_c = Rc::clone(&glaze_preview);
+        glaze_preview
+            .substrate_combo
+            .connect_changed(move |_| glaze_preview_c.update());
+
+        let glaze_preview_c = Rc::clone(&glaze_preview);
+        glaze_preview
+            .glaze_combo
+            .connect_changed(move |_| glaze_preview_c.update());
+
+        let glaze_preview_c = Rc::clone(&glaze_preview);
+        glaze_preview
+            .coats_spin
+            .connect_value_changed(move |_| glaze_preview_c.update());
+
+        let glaze_preview_c = Rc::clone(&glaze_preview);
+        glaze_preview
+            .dilution_spin
+            .connect_value_changed(move |_| glaze_preview_c.update());
+
+        glaze_preview
+    }
+
+    fn paint(&self, id: Option<String>) -> Option<Rc<SeriesPaint>> {
+        let index: usize = id?.parse().ok()?;
+        self.paints.borrow().get(index).cloned()
+    }
+
+    /// Offer the palette's `paints` as substrates and glazes.
+    pub fn set_paints(&self, paints: &[Rc<SeriesPaint>]) {
+        let substrate_id = self.substrate_combo.get_active_id();
+        let glaze_id = self.glaze_combo.get_active_id();
+        let substrate = self.paint(substrate_id.map(String::from));
+        let glaze = self.paint(glaze_id.map(String::from));
+        *self.paints.borrow_mut() = paints.to_vec();
+        self.substrate_combo.remove_all();
+        self.glaze_combo.remove_all();
+        self.substrate_combo.append(Some(Self::WHITE_ID), "White");
+        for (index, paint) in paints.iter().enumerate() {
+            let id = index.to_string();
+            self.substrate_combo.append(Some(&id), paint.id());
+            self.glaze_combo.append(Some(&id), paint.id());
+        }
+        let index_of = |paint: Option<Rc<SeriesPaint>>| {
+            paint.and_then(|paint| paints.iter().position(|p| *p == paint))
+        };
+        match index_of(substrate) {
+            Some(index) => self.substrate_combo.set_active_id(Some(&index.to_string())),
+            None => self.substrate_combo.set_active_id(Some(Self::WHITE_ID)),
+        };
+        if let Some(index) = index_of(glaze) {
+            self.glaze_combo.set_active_id(Some(&index.to_string()));
+        } else if !paints.is_empty() {
+            self.glaze_combo.set_active(Some(0));
+        }
+        self.update();
+    }
+
+    fn update(&self) {
+        let substrate = match self.paint(self.substrate_combo.get_active_id().map(String::from)) {
+            Some(paint) => paint.hcv(),
+            None => HCV::WHITE,
+        };
+        self.substrate_label.set_widget_colour(&substrate);
+        let mut glazing = Glazing::new(&substrate);
+        if let Some(glaze) = self.paint(self.glaze_combo.get_active_id().map(String::from)) {
+            let thickness = dilution_thickness(1, self.dilution_spin.get_value_as_int() as u64);
+            for _ in 0..self.coats_spin.get_value_as_int() {
+                glazing.add_layer(GlazeLayer::from_paint(glaze.as_ref(), thickness));
+            }
+        }
+        self.result_label.set_widget_colour(&glazing.colour());
+    }
+}
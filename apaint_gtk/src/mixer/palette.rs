@@ -29,7 +29,9 @@ use pw_gtk_ext::{
 };
 
 use colour_math::{
-    hue_wheel::MakeColouredShape, mixing::SubtractiveMixer, RGBConstants, ScalarAttribute, HCV,
+    hue_wheel::{ColouredShape, MakeColouredShape, Shape},
+    mixing::SubtractiveMixer,
+    ColourBasics, RGBConstants, ScalarAttribute, HCV,
 };
 use colour_math_cairo::CairoSetColour;
 
@@ -46,18 +48,30 @@ use colour_math_gtk::{
 use pw_gtk_ext::sav_state::ConditionalWidgetGroupsBuilder;
 
 use apaint::{
-    mixtures::{MixingSession, MixtureBuilder, Paint},
+    conversion::ConversionChartBuilder,
+    gamut::{Gamut, GamutBuilder},
+    harmony::{match_harmony, Harmony},
+    history::{History, SessionEdit},
+    ladder::{LadderBuilder, LadderKind},
+    mixtures::{
+        Dependents, MixingSession, Mixture, MixtureBuilder, NearestColourFallback, Paint,
+        SaveableMixingSession,
+    },
     properties::PropertyType,
+    query::filter_session,
+    recipe::{RecipeFormat, RecipeSheetBuilder},
     series::SeriesPaint,
-    BasicPaintIfce,
+    sort::{sort_paints, PaintOrder},
+    BasicPaintIfce, TooltipText,
 };
 
 use crate::{
     icons,
-    list::{BasicPaintListViewSpec, PaintListRow},
+    list::{BasicPaintListViewSpec, PaintListRow, PaintOrderSelector, PaintQueryEntry},
     mixer::{
         component::{PartsSpinButtonBox, RcPartsSpinButtonBox},
         display::{MixtureDisplayDialogManager, MixtureDisplayDialogManagerBuilder},
+        glaze::GlazePreview,
     },
     series::{PaintSeriesManager, PaintSeriesManagerBuilder},
     storage::{StorageManager, StorageManagerBuilder},
@@ -69,8 +83,6 @@ use crate::series::display::{
 };
 #[cfg(feature = "targeted_mixtures")]
 use crate::series::{PaintStandardsManager, PaintStandardsManagerBuilder};
-#[cfg(feature = "mixtures_may_mix")]
-use apaint::mixtures::Mixture;
 use apaint::series::SeriesPaintFinder;
 
 pub const IMAGE_AVAILABLE: u64 = SAV_NEXT_CONDN;
@@ -289,7 +301,6 @@ impl PalettePaintEntry {
 
     #[cfg(feature = "targeted_mixtures")]
     pub fn target_rgb<L: LightLevel>(&self) -> Option<RGB<L>> {
-        use colour_math::ColourBasics;
         if let Some(colour) = self.target_colour.borrow().as_ref() {
             Some(colour.rgb::<L>())
         } else {
@@ -299,7 +310,6 @@ impl PalettePaintEntry {
 
     #[cfg(feature = "targeted_mixtures")]
     pub fn target_colour(&self) -> Option<HCV> {
-        use colour_math::ColourBasics;
         if let Some(colour) = self.target_colour.borrow().as_ref() {
             Some(colour.hcv())
         } else {
@@ -313,6 +323,28 @@ impl PalettePaintEntry {
     }
 }
 
+/// An undoable change to the mixer: either to the mixing session or to the parts allocated
+/// to the palette's paints.
+#[derive(Debug)]
+enum MixerEdit {
+    Session(SessionEdit),
+    Parts {
+        old: Vec<(Rc<SeriesPaint>, u64)>,
+        new: Vec<(Rc<SeriesPaint>, u64)>,
+    },
+    /// Edits made by a single action (applied in order and reverted in reverse order).
+    Group(Vec<MixerEdit>),
+}
+
+impl MixerEdit {
+    fn absorb(&mut self, next: &Self) -> bool {
+        match (self, next) {
+            (Self::Session(edit), Self::Session(next)) => edit.absorb(next),
+            _ => false,
+        }
+    }
+}
+
 #[derive(PWO, Wrapper)]
 pub struct PalettePaintMixer {
     vbox: gtk::Box,
@@ -320,10 +352,21 @@ pub struct PalettePaintMixer {
     file_manager: Rc<StorageManager>,
     notes_entry: gtk::Entry,
     hue_wheel: Rc<GtkHueWheel>,
+    harmony_selector: gtk::ComboBoxText,
+    harmony_base: RefCell<Option<HCV>>,
+    harmony_ids: RefCell<Vec<String>>,
+    gamut_button: gtk::CheckButton,
+    gamut_chroma: gtk::SpinButton,
+    gamut_label: gtk::Label,
+    gamut_ids: RefCell<Vec<String>>,
+    gamut: RefCell<Option<Gamut>>,
     list_view: Rc<ListViewWithPopUpMenu>,
+    query_entry: Rc<PaintQueryEntry>,
+    order_selector: Rc<PaintOrderSelector>,
     attributes: Vec<ScalarAttribute>,
     properties: Vec<PropertyType>,
     mix_entry: Rc<PalettePaintEntry>,
+    glaze_preview: Rc<GlazePreview>,
     series_paint_spinner_box: Rc<PartsSpinButtonBox<SeriesPaint>>,
     #[cfg(feature = "mixtures_may_mix")]
     mixed_paint_spinner_box: Rc<PartsSpinButtonBox<Mixture>>,
@@ -332,6 +375,10 @@ pub struct PalettePaintMixer {
     #[cfg(feature = "targeted_mixtures")]
     paint_standards_manager: Rc<PaintStandardsManager>,
     next_mix_id: Cell<u64>,
+    editing: RefCell<Option<Rc<Mixture>>>,
+    history: RefCell<History<MixerEdit>>,
+    last_parts: RefCell<Vec<(Rc<SeriesPaint>, u64)>>,
+    replaying: Cell<bool>,
     mixture_display_dialog_manager: RefCell<MixtureDisplayDialogManager<gtk::Box>>,
     paint_display_dialog_manager: RefCell<Rc<PaintDisplayDialogManager<gtk::Box>>>,
 }
@@ -343,6 +390,8 @@ impl PalettePaintMixer {
     #[allow(dead_code)]
     const HAS_TARGET_MASK: u64 = Self::SAV_HAS_TARGET + Self::SAV_NOT_HAS_TARGET;
     const SAV_HAS_NAME: u64 = SAV_NEXT_CONDN << 3;
+    const SAV_CAN_UNDO: u64 = SAV_NEXT_CONDN << 4;
+    const SAV_CAN_REDO: u64 = SAV_NEXT_CONDN << 5;
 
     fn format_mix_id(&self) -> String {
         format!("MIX#{:03}", self.next_mix_id.get())
@@ -352,14 +401,129 @@ impl PalettePaintMixer {
         self.next_mix_id.set(self.next_mix_id.get() + 1);
     }
 
+    /// Make sure that the ids given to new mixtures (including ladders) are not already
+    /// used by the mixtures in the (newly loaded) session.
+    fn advance_mix_id_past_session(&self) {
+        let last_used = self
+            .mixing_session
+            .borrow()
+            .mixtures()
+            .filter_map(|mixture| mixture.id().strip_prefix("MIX#")?.parse::<u64>().ok())
+            .max();
+        if let Some(last_used) = last_used {
+            if last_used >= self.next_mix_id.get() {
+                self.next_mix_id.set(last_used + 1);
+            }
+        }
+    }
+
     fn add_series_paint(&self, paint: &Rc<SeriesPaint>) {
         self.series_paint_spinner_box.add_paint(paint);
         self.hue_wheel.add_item(paint.coloured_shape());
+        self.glaze_preview
+            .set_paints(&self.series_paint_spinner_box.paints());
+        self.update_harmony_overlay();
+        self.invalidate_gamut();
     }
 
     fn remove_series_paint(&self, paint: &Rc<SeriesPaint>) {
         self.series_paint_spinner_box.remove_paint(paint);
         self.hue_wheel.remove_item(paint.id());
+        self.glaze_preview
+            .set_paints(&self.series_paint_spinner_box.paints());
+        self.update_harmony_overlay();
+        self.invalidate_gamut();
+    }
+
+    /// Forget the gamut of the palette (which has changed) and redraw its overlay.
+    fn invalidate_gamut(&self) {
+        *self.gamut.borrow_mut() = None;
+        self.update_gamut_overlay();
+    }
+
+    /// Show the limits of the colours that can be mixed from the paints in the palette on
+    /// the hue wheel and report the hues that can't reach the chosen chroma.
+    fn update_gamut_overlay(&self) {
+        for id in self.gamut_ids.borrow_mut().drain(..) {
+            self.hue_wheel.remove_item(&id);
+        }
+        self.gamut_label.set_text("");
+        if !self.gamut_button.get_active() || self.series_paint_spinner_box.paints().is_empty() {
+            return;
+        }
+        let chroma = self.gamut_chroma.get_value();
+        // NB: the gamut is only rebuilt when the palette changes, not the target chroma
+        let mut cached_gamut = self.gamut.borrow_mut();
+        let gamut = cached_gamut.get_or_insert_with(|| {
+            GamutBuilder::new().build(&self.series_paint_spinner_box.paints())
+        });
+        let sector_degrees = gamut.sector_degrees();
+        let max_chromas = gamut.sector_max_chromas();
+        let mut gamut_ids = self.gamut_ids.borrow_mut();
+        for (index, limit) in gamut.sector_limits().iter().enumerate() {
+            if let Some(colour) = limit {
+                let id = format!("GAMUT#{}", index + 1);
+                let start = index as f64 * sector_degrees;
+                let max_chroma = max_chromas[index];
+                let tooltip_text = format!(
+                    "Gamut limit {:.0}°-{:.0}°: chroma {:.2}{}",
+                    start,
+                    start + sector_degrees,
+                    max_chroma,
+                    if max_chroma < chroma {
+                        " (short of target chroma)"
+                    } else {
+                        ""
+                    }
+                );
+                self.hue_wheel.add_item(ColouredShape::new(
+                    colour,
+                    &id,
+                    &tooltip_text,
+                    Shape::Circle,
+                ));
+                gamut_ids.push(id);
+            }
+        }
+        self.gamut_label.set_text(&gamut.report(chroma));
+    }
+
+    fn harmony(&self) -> Option<Harmony> {
+        let id = self.harmony_selector.get_active_id();
+        Harmony::ALL
+            .iter()
+            .find(|harmony| id.as_deref() == Some(harmony.name()))
+            .copied()
+    }
+
+    fn set_harmony_base(&self, colour: Option<HCV>) {
+        *self.harmony_base.borrow_mut() = colour;
+        self.update_harmony_overlay();
+    }
+
+    /// Show the selected harmony of the harmony base colour (matched to the paints in the
+    /// palette) on the hue wheel.  The overlay is not part of the mixing session.
+    fn update_harmony_overlay(&self) {
+        for id in self.harmony_ids.borrow_mut().drain(..) {
+            self.hue_wheel.remove_item(&id);
+        }
+        let base = *self.harmony_base.borrow();
+        if let (Some(harmony), Some(base)) = (self.harmony(), base) {
+            let paints = self.series_paint_spinner_box.paints();
+            let entries = match_harmony(&base, harmony, &paints, &ConversionChartBuilder::new());
+            let mut harmony_ids = self.harmony_ids.borrow_mut();
+            for (index, entry) in entries.iter().enumerate() {
+                let id = format!("HARMONY#{}", index + 1);
+                let tooltip_text = format!("{}: {}", harmony, entry.tooltip_text());
+                self.hue_wheel.add_item(ColouredShape::new(
+                    &entry.colour,
+                    &id,
+                    &tooltip_text,
+                    Shape::Circle,
+                ));
+                harmony_ids.push(id);
+            }
+        }
     }
 
     #[cfg(feature = "mixtures_may_mix")]
@@ -436,6 +600,148 @@ impl PalettePaintMixer {
             .update_session_is_saveable(!self.mixing_session.borrow().notes().is_empty());
     }
 
+    fn update_history_condns(&self) {
+        let history = self.history.borrow();
+        let mut condns = 0;
+        if history.can_undo() {
+            condns += Self::SAV_CAN_UNDO;
+        }
+        if history.can_redo() {
+            condns += Self::SAV_CAN_REDO;
+        }
+        self.change_notifier.notify_changed_condns(MaskedCondns {
+            condns,
+            mask: Self::SAV_CAN_UNDO + Self::SAV_CAN_REDO,
+        });
+    }
+
+    /// Record an edit that has just been made (unless it is the result of undoing or
+    /// redoing an edit or of a change that is being recorded as a whole).
+    fn record_edit(&self, edit: MixerEdit) {
+        if self.replaying.get() {
+            return;
+        }
+        self.history
+            .borrow_mut()
+            .record_or_absorb(edit, MixerEdit::absorb);
+        self.update_history_condns();
+    }
+
+    fn clear_history(&self) {
+        self.history.borrow_mut().clear();
+        self.update_history_condns();
+    }
+
+    /// Make changes without the signals that they cause being recorded as edits.
+    fn without_recording<R>(&self, change: impl FnOnce() -> R) -> R {
+        let replaying = self.replaying.replace(true);
+        let result = change();
+        self.replaying.set(replaying);
+        result
+    }
+
+    /// Make a change to the paints' parts returning it as a single edit (if anything changed).
+    fn change_parts(&self, change: impl FnOnce()) -> Option<MixerEdit> {
+        let old = self.last_parts.borrow().clone();
+        self.without_recording(change);
+        let new = self.last_parts.borrow().clone();
+        if old == new {
+            None
+        } else {
+            Some(MixerEdit::Parts { old, new })
+        }
+    }
+
+    fn series_parts_changed(&self) {
+        let new = self.series_paint_spinner_box.paint_contributions();
+        let old = self.last_parts.replace(new.clone());
+        if old != new {
+            self.record_edit(MixerEdit::Parts { old, new });
+        }
+        self.contributions_changed();
+    }
+
+    fn add_mixture_shapes(&self, mixture: &Rc<Mixture>) {
+        self.hue_wheel.add_item(mixture.coloured_shape());
+        #[cfg(feature = "targeted_mixtures")]
+        if mixture.targeted_colour().is_some() {
+            self.hue_wheel.add_item(mixture.targeted_rgb_shape());
+        }
+    }
+
+    fn remove_mixture_shapes(&self, mixture: &Rc<Mixture>) {
+        self.hue_wheel.remove_item(mixture.id());
+        #[cfg(feature = "targeted_mixtures")]
+        self.hue_wheel.remove_item(&mixture.targeted_rgb_id());
+    }
+
+    fn apply_session_edit(&self, edit: &SessionEdit, undo: bool) {
+        let before: Vec<Rc<Mixture>> = self.mixing_session.borrow().mixtures().cloned().collect();
+        if undo {
+            edit.revert(&mut self.mixing_session.borrow_mut());
+        } else {
+            edit.apply(&mut self.mixing_session.borrow_mut());
+        }
+        for mixture in before.iter() {
+            self.remove_mixture_shapes(mixture);
+        }
+        let notes = self.mixing_session.borrow().notes().to_string();
+        for mixture in self.mixing_session.borrow().mixtures() {
+            self.add_mixture_shapes(mixture);
+        }
+        if self.notes_entry.get_text().as_str() != notes {
+            self.notes_entry.set_text(&notes);
+        }
+        self.update_mixture_list();
+        self.update_session_needs_saving();
+        self.update_session_is_saveable();
+    }
+
+    fn apply_edit(&self, edit: &MixerEdit, undo: bool) {
+        match edit {
+            MixerEdit::Session(edit) => self.apply_session_edit(edit, undo),
+            MixerEdit::Parts { old, new } => {
+                let paint_parts = if undo { old } else { new };
+                let paints = self.series_paint_spinner_box.paints();
+                for (paint, _) in paint_parts.iter() {
+                    if !paints.contains(paint) {
+                        self.add_series_paint(paint);
+                    }
+                }
+                self.series_paint_spinner_box.set_paint_parts(paint_parts);
+            }
+            MixerEdit::Group(edits) => {
+                if undo {
+                    for edit in edits.iter().rev() {
+                        self.apply_edit(edit, true);
+                    }
+                } else {
+                    for edit in edits.iter() {
+                        self.apply_edit(edit, false);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn undo(&self) {
+        let mut history = self.history.take();
+        if let Some(edit) = history.undo() {
+            self.without_recording(|| self.apply_edit(edit, true));
+        }
+        *self.history.borrow_mut() = history;
+        self.update_history_condns();
+    }
+
+    pub fn redo(&self) {
+        let mut history = self.history.take();
+        if let Some(edit) = history.redo() {
+            self.without_recording(|| self.apply_edit(edit, false));
+        }
+        *self.history.borrow_mut() = history;
+        self.update_history_condns();
+    }
+
     fn write_to_file<Q: AsRef<Path>>(&self, path: Q) -> apaint::Result<Vec<u8>> {
         let path: &Path = path.as_ref();
         let mut file = File::create(path)?;
@@ -446,9 +752,32 @@ impl PalettePaintMixer {
     fn read_from_file<Q: AsRef<Path>>(&self, path: Q) -> apaint::Result<Vec<u8>> {
         let path: &Path = path.as_ref();
         let mut file = File::open(path)?;
-        let session = MixingSession::read(&mut file, &self.paint_series_manager)?;
+        let saved_session = SaveableMixingSession::read(&mut file)?;
+        let (session, digest) = match saved_session.mixing_session(&self.paint_series_manager) {
+            Ok(session) => {
+                let digest = session.digest().expect("should work");
+                (session, digest)
+            }
+            Err(apaint::Error::UnresolvedPaints(unresolved)) => {
+                let question = format!(
+                    "{unresolved}\nReplace them with the nearest colours from the loaded series?"
+                );
+                if !self.ask_confirm_action(&question, None) {
+                    return Err(apaint::Error::UnresolvedPaints(unresolved));
+                }
+                let fallback =
+                    NearestColourFallback::new(self.paint_series_manager.series_paints());
+                let session = saved_session
+                    .mixing_session_with_fallback(&self.paint_series_manager, Some(&fallback))?;
+                // the substituted session doesn't match the file so will need saving
+                (session, saved_session.digest()?)
+            }
+            Err(err) => return Err(err),
+        };
+        // an edit of one of the old session's mixtures can't be accepted into the new one
+        self.without_recording(|| self.cancel_current_mixture());
         // TODO: completely clear the mixer
-        self.notes_entry.set_text(session.notes());
+        self.without_recording(|| self.notes_entry.set_text(session.notes()));
         for mixture in session.mixtures() {
             for (paint, _) in mixture.components() {
                 match paint {
@@ -461,11 +790,11 @@ impl PalettePaintMixer {
                 }
             }
             self.hue_wheel.add_item(mixture.coloured_shape());
-            self.list_view
-                .add_row(&mixture.row(&self.attributes, &self.properties));
         }
-        let digest = session.digest().expect("should work");
         *self.mixing_session.borrow_mut() = session;
+        self.advance_mix_id_past_session();
+        self.update_mixture_list();
+        self.clear_history();
         Ok(digest)
     }
 
@@ -489,6 +818,9 @@ impl PalettePaintMixer {
     pub fn set_target_colour(&self, colour: Option<&impl GdkColour>) {
         self.hue_wheel.set_target_colour(colour);
         self.mix_entry.set_target_colour(colour);
+        if let Some(colour) = colour {
+            self.set_harmony_base(Some(colour.hcv()));
+        }
         self.paint_series_manager.set_target_colour(colour);
         if colour.is_some() {
             let masked_condns = MaskedCondns {
@@ -511,44 +843,464 @@ impl PalettePaintMixer {
         }
     }
 
+    /// Load the mixture with `id` into the mixer so that its name, notes and parts can be
+    /// changed.  Accepting it replaces the mixture in the session.
+    pub fn start_editing_mixture(&self, id: &str) {
+        let mixture = match self.mixing_session.borrow().mixture(id) {
+            Some(mixture) => Rc::clone(mixture),
+            None => return,
+        };
+        // without mixed paint spinners accepting the edit would silently drop such components
+        if !cfg!(feature = "mixtures_may_mix")
+            && mixture
+                .components()
+                .any(|(paint, _)| matches!(paint, Paint::Mixed(_)))
+        {
+            let msg = format!(
+                "{}: uses other mixtures as components so it can't be edited in this mixer.",
+                mixture.id()
+            );
+            self.inform_user(&msg, None);
+            return;
+        }
+        let mut series_parts = vec![];
+        #[cfg(feature = "mixtures_may_mix")]
+        let mut mixed_parts = vec![];
+        for (paint, parts) in mixture.components() {
+            match paint {
+                Paint::Series(paint) => {
+                    if !self.series_paint_spinner_box.paints().contains(paint) {
+                        self.add_series_paint(paint);
+                    }
+                    series_parts.push((Rc::clone(paint), *parts));
+                }
+                #[cfg(feature = "mixtures_may_mix")]
+                Paint::Mixed(paint) => {
+                    self.add_mixed_paint(paint);
+                    mixed_parts.push((Rc::clone(paint), *parts));
+                }
+                #[cfg(not(feature = "mixtures_may_mix"))]
+                Paint::Mixed(_) => (),
+            }
+        }
+        self.mix_entry.id_label.set_label(mixture.id());
+        self.mix_entry
+            .name_entry
+            .set_text(mixture.name().unwrap_or(""));
+        self.mix_entry
+            .notes_entry
+            .set_text(mixture.notes().unwrap_or(""));
+        #[cfg(feature = "targeted_mixtures")]
+        self.set_target_colour(Some(
+            &mixture.targeted_colour().unwrap_or_else(|| mixture.hcv()),
+        ));
+        let edit = self.change_parts(|| {
+            self.series_paint_spinner_box.set_paint_parts(&series_parts);
+            #[cfg(feature = "mixtures_may_mix")]
+            self.mixed_paint_spinner_box.set_paint_parts(&mixed_parts);
+        });
+        if let Some(edit) = edit {
+            self.record_edit(edit);
+        }
+        *self.editing.borrow_mut() = Some(mixture);
+    }
+
+    /// Replace a mixture in the session with an edited version (remixing the mixtures that
+    /// use it if the user agrees) returning the edits made.
+    fn replace_mixture(&self, mixture: &Rc<Mixture>) -> Option<Vec<MixerEdit>> {
+        let result = self
+            .mixing_session
+            .borrow_mut()
+            .edit_mixture(mixture, Dependents::Refuse);
+        let result = match result {
+            Err(apaint::Error::MixtureInUse(id, users)) => {
+                let question = format!(
+                    "{id} is used by: {}.\nRemix them with the edited mixture?",
+                    users.join(", ")
+                );
+                if !self.ask_confirm_action(&question, None) {
+                    return None;
+                }
+                self.mixing_session
+                    .borrow_mut()
+                    .edit_mixture(mixture, Dependents::Cascade)
+            }
+            result => result,
+        };
+        match result {
+            Ok(changes) => Some(
+                changes
+                    .into_iter()
+                    .map(|(old, new)| {
+                        self.remove_mixture_shapes(&old);
+                        self.add_mixture_shapes(&new);
+                        MixerEdit::Session(SessionEdit::ReplaceMixture { old, new })
+                    })
+                    .collect(),
+            ),
+            Err(err) => {
+                self.report_error("Failed to edit mixture", &err);
+                None
+            }
+        }
+    }
+
+    /// Delete the mixture with `id` from the session (after confirmation) unless other
+    /// mixtures use it.
+    pub fn delete_mixture(&self, id: &str) {
+        let question = format!("Delete mixture {id}?");
+        if !self.ask_confirm_action(&question, None) {
+            return;
+        }
+        let result = self.mixing_session.borrow_mut().delete_mixture(id);
+        match result {
+            Ok(mixture) => {
+                self.remove_mixture_shapes(&mixture);
+                #[cfg(feature = "mixtures_may_mix")]
+                self.remove_mixed_paint(&mixture);
+                let is_being_edited =
+                    matches!(&*self.editing.borrow(), Some(editing) if editing.id() == id);
+                if is_being_edited {
+                    self.cancel_current_mixture();
+                }
+                self.record_edit(MixerEdit::Session(SessionEdit::RemoveMixture(mixture)));
+                self.update_mixture_list();
+                self.update_session_needs_saving();
+            }
+            Err(err) => self.report_error("Failed to delete mixture", &err),
+        }
+    }
+
     pub fn accept_current_mixture(&self) {
-        let mix_id = self.format_mix_id();
-        self.advance_mix_id();
+        let editing = self.editing.borrow().clone();
+        let mix_id = match editing {
+            Some(ref mixture) => mixture.id().to_string(),
+            None => self.format_mix_id(),
+        };
         let mut mixed_paint_builder = MixtureBuilder::new(&mix_id);
         mixed_paint_builder
             .name(&self.mix_entry.name_entry.get_text())
             .notes(&self.mix_entry.notes_entry.get_text())
-            .series_paint_components(self.series_paint_spinner_box.paint_contributions());
+            .series_paint_components(self.series_paint_spinner_box.paint_contributions())
+            .mix_rules(self.mixing_session.borrow().mix_rules());
         #[cfg(feature = "mixtures_may_mix")]
         mixed_paint_builder
             .mixed_paint_components(self.mixed_paint_spinner_box.paint_contributions());
         #[cfg(feature = "targeted_mixtures")]
-        mixed_paint_builder.targeted_colour(
-            &self
-                .mix_entry
-                .target_colour()
-                .expect("should not be accepted without target"),
-        );
+        match editing {
+            // an edit keeps the mixture's original target (if any)
+            Some(ref mixture) => {
+                if let Some(colour) = mixture.targeted_colour() {
+                    mixed_paint_builder.targeted_colour(&colour);
+                }
+            }
+            None => {
+                mixed_paint_builder.targeted_colour(
+                    &self
+                        .mix_entry
+                        .target_colour()
+                        .expect("should not be accepted without target"),
+                );
+            }
+        }
         let mixed_paint = mixed_paint_builder.build();
-        self.hue_wheel.add_item(mixed_paint.coloured_shape());
-        #[cfg(feature = "targeted_mixtures")]
-        self.hue_wheel.add_item(mixed_paint.targeted_rgb_shape());
-        self.list_view
-            .add_row(&mixed_paint.row(&self.attributes, &self.properties));
+        let session_edits = if editing.is_some() {
+            match self.replace_mixture(&mixed_paint) {
+                Some(session_edits) => session_edits,
+                None => return,
+            }
+        } else {
+            self.advance_mix_id();
+            self.add_mixture_shapes(&mixed_paint);
+            // TODO: handle case of duplicate mixed paint
+            let replaced = self.mixing_session.borrow_mut().add_mixture(&mixed_paint);
+            vec![MixerEdit::Session(SessionEdit::AddMixture {
+                mixture: mixed_paint,
+                replaced,
+            })]
+        };
+        *self.editing.borrow_mut() = None;
         self.mix_entry.id_label.set_label("MIX#???");
         self.mix_entry.name_entry.set_text("");
         self.mix_entry.notes_entry.set_text("");
-        self.series_paint_spinner_box.zero_all_parts();
-        // TODO: handle case of duplicate mixed paint
-        self.mixing_session.borrow_mut().add_mixture(&mixed_paint);
+        let mut edits: Vec<MixerEdit> = self
+            .change_parts(|| self.series_paint_spinner_box.zero_all_parts())
+            .into_iter()
+            .collect();
+        edits.extend(session_edits);
+        self.record_edit(MixerEdit::Group(edits));
+        self.update_mixture_list();
         self.update_session_needs_saving();
     }
 
+    /// List the session's mixtures that match the filter query in the selected order.
+    fn update_mixture_list(&self) {
+        self.list_view.remove_all();
+        let mut mixtures = filter_session(&self.query_entry.query(), &self.mixing_session.borrow());
+        sort_paints(&mut mixtures, self.order_selector.order());
+        for mixture in mixtures {
+            self.list_view
+                .add_row(&mixture.row(&self.attributes, &self.properties));
+        }
+    }
+
+    /// Ask for the paints and steps of a tint, shade or tone ladder and then add its
+    /// mixtures to the session or export it as a chart.
+    fn ask_add_ladder(&self) {
+        let paints = self.series_paint_spinner_box.paints();
+        if paints.len() < 2 {
+            self.inform_user("A ladder needs at least two paints in the palette.", None);
+            return;
+        }
+        let base_combo = gtk::ComboBoxText::new();
+        let modifier_combo = gtk::ComboBoxText::new();
+        for paint in paints.iter() {
+            let text = format!("{} ({})", paint.id(), paint.series_id());
+            base_combo.append_text(&text);
+            modifier_combo.append_text(&text);
+        }
+        base_combo.set_active(Some(0));
+        modifier_combo.set_active(Some(1));
+        let kind_combo = gtk::ComboBoxText::new();
+        for kind in LadderKind::ALL.iter() {
+            kind_combo.append(Some(kind.name()), kind.name());
+        }
+        kind_combo.set_active_id(Some(LadderKind::Tint.name()));
+        let max_ratio_spin = gtk::SpinButtonBuilder::new()
+            .adjustment(&gtk::Adjustment::new(8.0, 1.0, 32.0, 1.0, 4.0, 0.0))
+            .climb_rate(0.0)
+            .digits(0)
+            .numeric(true)
+            .tooltip_text("The ladder's steps are 1:0, 1:1, ... 1:N (base:modifier).")
+            .build();
+        let grid = gtk::Grid::new();
+        for (row, (label, widget)) in [
+            ("Kind:", kind_combo.clone().upcast::<gtk::Widget>()),
+            ("Base:", base_combo.clone().upcast::<gtk::Widget>()),
+            ("Modifier:", modifier_combo.clone().upcast::<gtk::Widget>()),
+            (
+                "Steps to 1:",
+                max_ratio_spin.clone().upcast::<gtk::Widget>(),
+            ),
+        ]
+        .iter()
+        .enumerate()
+        {
+            grid.attach(&gtk::Label::new(Some(*label)), 0, row as i32, 1, 1);
+            grid.attach(widget, 1, row as i32, 1, 1);
+        }
+        let dialog = self
+            .new_dialog_builder()
+            .title("Tint, Shade or Tone Ladder")
+            .destroy_with_parent(true)
+            .modal(true)
+            .build();
+        dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+        dialog.add_button("Export Chart", gtk::ResponseType::Other(0));
+        dialog.add_button("Add to Session", gtk::ResponseType::Ok);
+        dialog.get_content_area().pack_start(&grid, true, true, 0);
+        dialog.show_all();
+        let response = dialog.run();
+        let base = base_combo.get_active().map(|index| &paints[index as usize]);
+        let modifier = modifier_combo
+            .get_active()
+            .map(|index| &paints[index as usize]);
+        let kind = LadderKind::ALL
+            .iter()
+            .find(|kind| kind_combo.get_active_id().as_deref() == Some(kind.name()))
+            .copied()
+            .unwrap_or(LadderKind::Tint);
+        let max_ratio = max_ratio_spin.get_value_as_int().max(1) as u64;
+        unsafe { dialog.destroy() };
+        let (base, modifier) = match (base, modifier) {
+            (Some(base), Some(modifier)) if base != modifier => (base, modifier),
+            _ => {
+                if response != gtk::ResponseType::Cancel {
+                    self.inform_user("The base and modifier must be different paints.", None);
+                }
+                return;
+            }
+        };
+        let mut builder = LadderBuilder::new(kind, base, modifier);
+        builder
+            .max_ratio(max_ratio)
+            .mix_rules(self.mixing_session.borrow().mix_rules());
+        match response {
+            gtk::ResponseType::Ok => {
+                let ladder = builder.build(|| {
+                    let id = self.format_mix_id();
+                    self.advance_mix_id();
+                    id
+                });
+                for mixture in ladder.mixtures() {
+                    self.hue_wheel.add_item(mixture.coloured_shape());
+                }
+                let edits = ladder
+                    .mixtures()
+                    .map(|mixture| {
+                        let replaced = self.mixing_session.borrow_mut().add_mixture(mixture);
+                        MixerEdit::Session(SessionEdit::AddMixture {
+                            mixture: Rc::clone(mixture),
+                            replaced,
+                        })
+                    })
+                    .collect();
+                self.record_edit(MixerEdit::Group(edits));
+                self.update_mixture_list();
+                self.update_session_needs_saving();
+            }
+            gtk::ResponseType::Other(0) => {
+                let mut step = 0;
+                let ladder = builder.build(|| {
+                    step += 1;
+                    format!("STEP#{step}")
+                });
+                if let Some(path) = self.ask_file_path(Some("Export chart as: "), None, false) {
+                    let result = File::create(&path)
+                        .map_err(apaint::Error::from)
+                        .and_then(|mut file| ladder.write_svg(&mut file));
+                    if let Err(err) = result {
+                        self.report_error("Failed to export chart", &err);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Ask for a saved session file and which of its mixtures to import into the session.
+    fn ask_import_mixtures(&self) {
+        let path = match self.ask_file_path(Some("Import mixtures from: "), None, false) {
+            Some(path) => path,
+            None => return,
+        };
+        let other: MixingSession = match File::open(&path)
+            .map_err(apaint::Error::from)
+            .and_then(|mut file| MixingSession::read(&mut file, &self.paint_series_manager))
+        {
+            Ok(other) => other,
+            Err(err) => {
+                self.report_error("Failed to read session", &err);
+                return;
+            }
+        };
+        if other.mixtures().next().is_none() {
+            self.inform_user("The session has no mixtures to import.", None);
+            return;
+        }
+        let vbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        let check_buttons: Vec<(String, gtk::CheckButton)> = other
+            .mixtures()
+            .map(|mixture| {
+                let label = match mixture.name() {
+                    Some(name) => format!("{}: {}", mixture.id(), name),
+                    None => mixture.id().to_string(),
+                };
+                let check_button = gtk::CheckButton::with_label(&label);
+                check_button.set_tooltip_text(Some(&mixture.tooltip_text()));
+                vbox.pack_start(&check_button, false, false, 0);
+                (mixture.id().to_string(), check_button)
+            })
+            .collect();
+        let scrolled_window = gtk::ScrolledWindowBuilder::new()
+            .min_content_height(200)
+            .build();
+        scrolled_window.add(&vbox);
+        let dialog = self
+            .new_dialog_builder()
+            .title("Import Mixtures")
+            .destroy_with_parent(true)
+            .modal(true)
+            .build();
+        dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+        dialog.add_button("Import", gtk::ResponseType::Ok);
+        dialog
+            .get_content_area()
+            .pack_start(&scrolled_window, true, true, 0);
+        dialog.show_all();
+        let response = dialog.run();
+        let ids: Vec<&str> = check_buttons
+            .iter()
+            .filter(|(_, check_button)| check_button.get_active())
+            .map(|(id, _)| id.as_str())
+            .collect();
+        unsafe { dialog.destroy() };
+        if response != gtk::ResponseType::Ok || ids.is_empty() {
+            return;
+        }
+        let result = self
+            .mixing_session
+            .borrow_mut()
+            .import_mixtures(&other, &ids, || {
+                let id = self.format_mix_id();
+                self.advance_mix_id();
+                id
+            });
+        match result {
+            Ok(imported) => {
+                let mut edits = vec![];
+                for (_, mixture) in imported {
+                    for (paint, _) in mixture.components() {
+                        if let Paint::Series(paint) = paint {
+                            if !self.series_paint_spinner_box.paints().contains(paint) {
+                                self.add_series_paint(paint);
+                            }
+                        }
+                    }
+                    self.add_mixture_shapes(&mixture);
+                    edits.push(MixerEdit::Session(SessionEdit::AddMixture {
+                        mixture,
+                        replaced: None,
+                    }));
+                }
+                self.record_edit(MixerEdit::Group(edits));
+                self.update_mixture_list();
+                self.update_session_needs_saving();
+            }
+            Err(err) => self.report_error("Failed to import mixtures", &err),
+        }
+    }
+
+    /// Export the session's recipes as a printable HTML or Markdown (chosen by the file's
+    /// extension) document.
+    fn export_recipe_sheet(&self) {
+        if self.mixing_session.borrow().mixtures().next().is_none() {
+            self.inform_user("The session has no mixtures.", None);
+            return;
+        }
+        let path = match self.ask_file_path(Some("Export recipes as: "), None, false) {
+            Some(path) => path,
+            None => return,
+        };
+        let format = RecipeFormat::ALL
+            .iter()
+            .find(|format| {
+                path.extension().and_then(|extension| extension.to_str())
+                    == Some(format.extension())
+            })
+            .copied()
+            .unwrap_or(RecipeFormat::Html);
+        let mut builder = RecipeSheetBuilder::new();
+        builder.format(format);
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            builder.title(stem);
+        }
+        let result = File::create(&path)
+            .map_err(apaint::Error::from)
+            .and_then(|mut file| builder.write(&*self.mixing_session.borrow(), &mut file));
+        if let Err(err) = result {
+            self.report_error("Failed to export recipes", &err);
+        }
+    }
+
     pub fn cancel_current_mixture(&self) {
+        *self.editing.borrow_mut() = None;
         self.mix_entry.id_label.set_label("MIX#???");
         self.mix_entry.name_entry.set_text("");
         self.mix_entry.notes_entry.set_text("");
-        self.series_paint_spinner_box.zero_all_parts();
+        if let Some(edit) = self.change_parts(|| self.series_paint_spinner_box.zero_all_parts()) {
+            self.record_edit(edit);
+        }
         #[cfg(feature = "targeted_mixtures")]
         self.set_target_colour(Option::<&HCV>::None);
     }
@@ -556,22 +1308,35 @@ impl PalettePaintMixer {
     pub fn full_reset(&self) -> apaint::Result<Vec<u8>> {
         #[cfg(feature = "palette_samples")]
         self.mix_entry.delete_samples();
-        self.notes_entry.set_text("");
-        self.cancel_current_mixture();
+        self.without_recording(|| {
+            self.notes_entry.set_text("");
+            self.cancel_current_mixture();
+        });
+        self.set_harmony_base(None);
         *self.mixing_session.borrow_mut() = MixingSession::new();
+        self.clear_history();
         let digest = self.mixing_session.borrow().digest().expect("should work");
         Ok(digest)
     }
 
     pub fn simplify_current_parts(&self) {
         let gcd = self.series_paint_spinner_box.parts_gcd();
-        self.series_paint_spinner_box.div_all_parts_by(gcd);
+        if let Some(edit) =
+            self.change_parts(|| self.series_paint_spinner_box.div_all_parts_by(gcd))
+        {
+            self.record_edit(edit);
+        }
     }
 
     pub fn zero_all_parts(&self) {
-        self.series_paint_spinner_box.zero_all_parts();
-        #[cfg(feature = "mixtures_may_mix")]
-        self.mixed_paint_spinner_box.zero_all_parts();
+        let edit = self.change_parts(|| {
+            self.series_paint_spinner_box.zero_all_parts();
+            #[cfg(feature = "mixtures_may_mix")]
+            self.mixed_paint_spinner_box.zero_all_parts();
+        });
+        if let Some(edit) = edit {
+            self.record_edit(edit);
+        }
     }
 
     pub fn needs_saving(&self) -> bool {
@@ -619,17 +1384,50 @@ impl PalettePaintMixerBuilder {
         let notes_entry = gtk::EntryBuilder::new().build();
         let hue_wheel = GtkHueWheelBuilder::new()
             .attributes(&self.attributes)
-            .menu_item_specs(&[(
-                "info",
+            .menu_item_specs(&[
                 (
-                    "Paint Information",
-                    None,
-                    Some("Display information for the indicated paint"),
-                )
-                    .into(),
-                SAV_HOVER_OK,
-            )])
+                    "info",
+                    (
+                        "Paint Information",
+                        None,
+                        Some("Display information for the indicated paint"),
+                    )
+                        .into(),
+                    SAV_HOVER_OK,
+                ),
+                (
+                    "harmony",
+                    (
+                        "Show Harmony",
+                        None,
+                        Some("Show the selected harmony of the indicated paint"),
+                    )
+                        .into(),
+                    SAV_HOVER_OK,
+                ),
+            ])
+            .build();
+        let harmony_selector = gtk::ComboBoxText::new();
+        harmony_selector.append(Some("None"), "No Harmony");
+        for harmony in Harmony::ALL.iter() {
+            harmony_selector.append(Some(harmony.name()), harmony.name());
+        }
+        harmony_selector.set_active_id(Some("None"));
+        harmony_selector.set_tooltip_text(Some(
+            "Colour harmony (matched to the palette's paints) to show on the hue wheel",
+        ));
+        let gamut_button = gtk::CheckButtonBuilder::new()
+            .label("Gamut")
+            .tooltip_text("Show the limits of the colours that can be mixed from the palette.")
             .build();
+        let gamut_chroma = gtk::SpinButtonBuilder::new()
+            .adjustment(&gtk::Adjustment::new(0.5, 0.0, 1.0, 0.05, 0.1, 0.0))
+            .climb_rate(0.0)
+            .digits(2)
+            .numeric(true)
+            .tooltip_text("The chroma that the gamut is required to reach.")
+            .build();
+        let gamut_label = gtk::LabelBuilder::new().wrap(true).xalign(0.0).build();
         let list_spec = BasicPaintListViewSpec::new(&self.attributes, &self.properties);
         let list_view = ListViewWithPopUpMenuBuilder::new()
             .menu_items(vec![
@@ -643,6 +1441,26 @@ impl PalettePaintMixerBuilder {
                         .into(),
                     SAV_HOVER_OK,
                 ),
+                (
+                    "edit",
+                    (
+                        "Edit",
+                        None,
+                        Some("Load the indicated mixture into the mixer to be edited."),
+                    )
+                        .into(),
+                    SAV_HOVER_OK,
+                ),
+                (
+                    "delete",
+                    (
+                        "Delete",
+                        None,
+                        Some("Delete the indicated mixture (if no other mixtures use it)."),
+                    )
+                        .into(),
+                    SAV_HOVER_OK,
+                ),
                 #[cfg(feature = "mixtures_may_mix")]
                 (
                     "add",
@@ -722,8 +1540,20 @@ impl PalettePaintMixerBuilder {
         hbox.pack_start(&notes_entry, true, true, 0);
         vbox.pack_start(&hbox, false, false, 0);
         let paned = gtk::Paned::new(gtk::Orientation::Horizontal);
-        paned.add1(hue_wheel.pwo());
-        paned.add2(mix_entry.pwo());
+        let wheel_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        hbox.pack_start(&harmony_selector, true, true, 0);
+        hbox.pack_start(&gamut_button, false, false, 0);
+        hbox.pack_start(&gamut_chroma, false, false, 0);
+        wheel_box.pack_start(&hbox, false, false, 0);
+        wheel_box.pack_start(hue_wheel.pwo(), true, true, 0);
+        wheel_box.pack_start(&gamut_label, false, false, 0);
+        paned.add1(&wheel_box);
+        let glaze_preview = GlazePreview::new();
+        let entry_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        entry_box.pack_start(mix_entry.pwo(), true, true, 0);
+        entry_box.pack_start(glaze_preview.pwo(), false, false, 0);
+        paned.add2(&entry_box);
         paned.set_position_from_recollections("basic paint factory h paned position", 200);
         vbox.pack_start(&paned, true, true, 0);
         let buttons = ConditionalWidgetGroupsBuilder::new()
@@ -795,10 +1625,54 @@ impl PalettePaintMixerBuilder {
             .expect("Duplicate key or button: zero_parts");
         button_box.pack_start(&zero_parts_btn, true, true, 0);
 
+        let ladder_btn = gtk::ButtonBuilder::new()
+            .label("Ladder")
+            .tooltip_text("Add a tint, shade or tone ladder of a palette paint to the mixtures.")
+            .build();
+        button_box.pack_start(&ladder_btn, true, true, 0);
+
+        let import_btn = gtk::ButtonBuilder::new()
+            .label("Import")
+            .tooltip_text("Import mixtures (and the mixtures that they use) from a saved session.")
+            .build();
+        button_box.pack_start(&import_btn, true, true, 0);
+
+        let recipes_btn = gtk::ButtonBuilder::new()
+            .label("Recipes")
+            .tooltip_text(
+                "Export the mixtures' recipes as a printable HTML or Markdown (.md) sheet.",
+            )
+            .build();
+        button_box.pack_start(&recipes_btn, true, true, 0);
+
+        let undo_btn = gtk::ButtonBuilder::new()
+            .label("Undo")
+            .tooltip_text("Undo the last change to the mixtures, notes or parts.")
+            .build();
+        buttons
+            .add_widget("undo", &undo_btn, PalettePaintMixer::SAV_CAN_UNDO)
+            .expect("Duplicate key or button: undo");
+        button_box.pack_start(&undo_btn, true, true, 0);
+
+        let redo_btn = gtk::ButtonBuilder::new()
+            .label("Redo")
+            .tooltip_text("Redo the last undone change.")
+            .build();
+        buttons
+            .add_widget("redo", &redo_btn, PalettePaintMixer::SAV_CAN_REDO)
+            .expect("Duplicate key or button: redo");
+        button_box.pack_start(&redo_btn, true, true, 0);
+
         vbox.pack_start(&button_box, false, false, 0);
         vbox.pack_start(series_paint_spinner_box.pwo(), false, false, 0);
         #[cfg(feature = "mixtures_may_mix")]
         vbox.pack_start(mixed_paint_spinner_box.pwo(), false, false, 0);
+        let query_entry = PaintQueryEntry::new();
+        let order_selector = PaintOrderSelector::new();
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        hbox.pack_start(query_entry.pwo(), true, true, 0);
+        hbox.pack_start(order_selector.pwo(), false, false, 0);
+        vbox.pack_start(&hbox, false, false, 0);
         vbox.pack_start(list_view.pwo(), true, true, 0);
         vbox.show_all();
 
@@ -808,10 +1682,21 @@ impl PalettePaintMixerBuilder {
             notes_entry,
             mixing_session: RefCell::new(MixingSession::new()),
             hue_wheel,
+            harmony_selector,
+            harmony_base: RefCell::new(None),
+            harmony_ids: RefCell::new(vec![]),
+            gamut_button,
+            gamut_chroma,
+            gamut_label,
+            gamut_ids: RefCell::new(vec![]),
+            gamut: RefCell::new(None),
             list_view,
+            query_entry,
+            order_selector,
             attributes: self.attributes.clone(),
             properties: self.properties.clone(),
             mix_entry,
+            glaze_preview,
             series_paint_spinner_box,
             #[cfg(feature = "mixtures_may_mix")]
             mixed_paint_spinner_box,
@@ -820,6 +1705,10 @@ impl PalettePaintMixerBuilder {
             #[cfg(feature = "targeted_mixtures")]
             paint_standards_manager,
             next_mix_id: Cell::new(1),
+            editing: RefCell::new(None),
+            history: RefCell::new(History::new()),
+            last_parts: RefCell::new(vec![]),
+            replaying: Cell::new(false),
             mixture_display_dialog_manager: RefCell::new(mixture_display_dialog_manager),
             paint_display_dialog_manager: RefCell::new(paint_display_dialog_manager),
         });
@@ -836,10 +1725,35 @@ impl PalettePaintMixerBuilder {
             change_notifier_c.notify_changed_condns(condns);
         });
 
+        let tpm_c = Rc::clone(&tpm);
+        tpm.harmony_selector
+            .connect_changed(move |_| tpm_c.update_harmony_overlay());
+
+        let tpm_c = Rc::clone(&tpm);
+        tpm.gamut_button
+            .connect_toggled(move |_| tpm_c.update_gamut_overlay());
+
+        let tpm_c = Rc::clone(&tpm);
+        tpm.gamut_chroma
+            .connect_value_changed(move |_| tpm_c.update_gamut_overlay());
+
+        let tpm_c = Rc::clone(&tpm);
+        tpm.query_entry
+            .connect_query_changed(move |_| tpm_c.update_mixture_list());
+
+        let tpm_c = Rc::clone(&tpm);
+        tpm.order_selector
+            .connect_order_changed(move |_| tpm_c.update_mixture_list());
+
         let tpm_c = Rc::clone(&tpm);
         tpm.notes_entry.connect_changed(move |entry| {
             let text = entry.get_text();
+            let old = tpm_c.mixing_session.borrow().notes().to_string();
             tpm_c.mixing_session.borrow_mut().set_notes(&text);
+            tpm_c.record_edit(MixerEdit::Session(SessionEdit::SetNotes {
+                old,
+                new: text.to_string(),
+            }));
             tpm_c.update_session_needs_saving();
             tpm_c.update_session_is_saveable();
         });
@@ -850,7 +1764,6 @@ impl PalettePaintMixerBuilder {
 
         #[cfg(feature = "targeted_mixtures")]
         {
-            use colour_math::ColourBasics;
             let tpm_c = Rc::clone(&tpm);
             tpm.paint_standards_manager
                 .connect_set_as_target(move |paint| {
@@ -863,7 +1776,7 @@ impl PalettePaintMixerBuilder {
 
         let tpm_c = Rc::clone(&tpm);
         tpm.series_paint_spinner_box
-            .connect_contributions_changed(move || tpm_c.contributions_changed());
+            .connect_contributions_changed(move || tpm_c.series_parts_changed());
 
         let tpm_c = Rc::clone(&tpm);
         tpm.series_paint_spinner_box
@@ -904,6 +1817,21 @@ impl PalettePaintMixerBuilder {
         let tpm_c = Rc::clone(&tpm);
         zero_parts_btn.connect_clicked(move |_| tpm_c.zero_all_parts());
 
+        let tpm_c = Rc::clone(&tpm);
+        ladder_btn.connect_clicked(move |_| tpm_c.ask_add_ladder());
+
+        let tpm_c = Rc::clone(&tpm);
+        import_btn.connect_clicked(move |_| tpm_c.ask_import_mixtures());
+
+        let tpm_c = Rc::clone(&tpm);
+        recipes_btn.connect_clicked(move |_| tpm_c.export_recipe_sheet());
+
+        let tpm_c = Rc::clone(&tpm);
+        undo_btn.connect_clicked(move |_| tpm_c.undo());
+
+        let tpm_c = Rc::clone(&tpm);
+        redo_btn.connect_clicked(move |_| tpm_c.redo());
+
         // FILE MANAGEMENT
         let tpm_c = Rc::clone(&tpm);
         tpm.file_manager
@@ -928,6 +1856,17 @@ impl PalettePaintMixerBuilder {
                 .display_mixture(mixture);
         });
 
+        let tpm_c = Rc::clone(&tpm);
+        tpm.list_view.connect_popup_menu_item("edit", move |id, _| {
+            tpm_c.start_editing_mixture(&id.unwrap());
+        });
+
+        let tpm_c = Rc::clone(&tpm);
+        tpm.list_view
+            .connect_popup_menu_item("delete", move |id, _| {
+                tpm_c.delete_mixture(&id.unwrap());
+            });
+
         #[cfg(feature = "mixtures_may_mix")]
         {
             let tpm_c = Rc::clone(&tpm);
@@ -968,6 +1907,21 @@ impl PalettePaintMixerBuilder {
             }
         });
 
+        let tpm_c = Rc::clone(&tpm);
+        tpm.hue_wheel.connect_popup_menu_item("harmony", move |id| {
+            let colour = if let Some(mixture) = tpm_c.mixing_session.borrow().mixture(id) {
+                Some(mixture.hcv())
+            } else if let Ok(paint) = tpm_c.paint_series_manager.get_series_paint(id, None) {
+                Some(paint.hcv())
+            } else {
+                None
+            };
+            match colour {
+                Some(colour) => tpm_c.set_harmony_base(Some(colour)),
+                None => tpm_c.inform_user("Unknown paint", None),
+            }
+        });
+
         tpm
     }
 }
@@ -1019,3 +1973,44 @@ impl TargetPaintEntry {
         self.colour_editor.rgb()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use apaint::series::{BasicPaintSpec, SeriesPaintSeries, SeriesPaintSeriesSpec};
+    use colour_math::{HueConstants, HCV};
+
+    #[test]
+    fn load_session_while_editing() {
+        if gtk::init().is_err() {
+            // no display to build the mixer on
+            return;
+        }
+        let mut series_spec = SeriesPaintSeriesSpec::default();
+        series_spec.set_proprietor("owner");
+        series_spec.set_series_name("series name");
+        series_spec.add(&BasicPaintSpec::new(&HCV::RED, "red"));
+        let series = SeriesPaintSeries::from(&series_spec);
+        let red = series.find("red").unwrap();
+        let mixture = MixtureBuilder::new("MIX#001")
+            .series_paint_components(vec![(Rc::clone(red), 1)])
+            .build();
+
+        let mixer = PalettePaintMixerBuilder::new().build();
+        mixer.mixing_session.borrow_mut().add_mixture(&mixture);
+        mixer.start_editing_mixture("MIX#001");
+        assert!(mixer.editing.borrow().is_some());
+        assert_eq!(mixer.mix_entry.id_label.get_label().as_str(), "MIX#001");
+
+        let path = std::env::temp_dir().join("apaint_gtk_load_session_while_editing");
+        MixingSession::new()
+            .write(&mut File::create(&path).unwrap())
+            .unwrap();
+        let result = mixer.read_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_ok());
+        assert!(mixer.editing.borrow().is_none());
+        assert_eq!(mixer.mix_entry.id_label.get_label().as_str(), "MIX#???");
+    }
+}
@@ -29,14 +29,16 @@ use colour_math_gtk::{
 use pw_gtk_ext::gtkx::notebook::TabRemoveLabelBuilder;
 
 use apaint::{
-    legacy::{legacy_series::SeriesPaintSeriesSpec00, read_legacy_paint_series_spec},
+    library::read_paint_series_spec_file,
     properties::PropertyType,
-    series::{SeriesId, SeriesPaint, SeriesPaintFinder, SeriesPaintSeries, SeriesPaintSeriesSpec},
+    search::search_series,
+    sort::{sort_paints, PaintOrder},
+    series::{PaintIndex, SeriesId, SeriesPaint, SeriesPaintFinder, SeriesPaintSeries},
 };
 
 use crate::{
     icons,
-    list::{BasicPaintListViewSpec, PaintListRow},
+    list::{BasicPaintListViewSpec, PaintListRow, PaintOrderSelector, PaintQueryEntry},
 };
 
 pub mod display;
@@ -47,8 +49,13 @@ type PaintActionCallback = Box<dyn Fn(Rc<SeriesPaint>)>;
 
 #[derive(PWO, Wrapper)]
 struct SeriesPage {
-    paned: gtk::Paned,
+    vbox: gtk::Box,
+    search_entry: gtk::SearchEntry,
+    query_entry: Rc<PaintQueryEntry>,
+    order_selector: Rc<PaintOrderSelector>,
     paint_series: SeriesPaintSeries,
+    attributes: Vec<ScalarAttribute>,
+    properties: Vec<PropertyType>,
     hue_wheel: Rc<GtkHueWheel>,
     list_view: Rc<ListViewWithPopUpMenu>,
     callbacks: RefCell<HashMap<String, Vec<PaintActionCallback>>>,
@@ -105,23 +112,48 @@ impl SeriesPageBuilder {
             .menu_item_specs(&self.menu_items)
             .attributes(&self.attributes)
             .build();
-        let list_spec = BasicPaintListViewSpec::new(&self.attributes, &self.properties);
+        let custom_property_types = paint_series.custom_property_types();
+        let mut list_spec = BasicPaintListViewSpec::new(&self.attributes, &self.properties);
+        list_spec.set_custom_columns(custom_property_types.len());
         let list_view = ListViewWithPopUpMenuBuilder::new()
             .menu_items(self.menu_items.to_vec())
             .selection_mode(self.selection_mode)
             .build(&list_spec);
+        list_spec.update_custom_columns(&list_view, custom_property_types);
         for paint in paint_series.paints() {
             hue_wheel.add_item(paint.coloured_shape());
-            let row = paint.row(&self.attributes, &self.properties);
+            let row = paint.row_with_custom(
+                &self.attributes,
+                &self.properties,
+                custom_property_types,
+                custom_property_types.len(),
+            );
             list_view.add_row(&row);
         }
         let scrolled_window = gtk::ScrolledWindowBuilder::new().build();
         scrolled_window.add(list_view.pwo());
         paned.add1(hue_wheel.pwo());
         paned.add2(&scrolled_window);
+        let search_entry = gtk::SearchEntryBuilder::new()
+            .placeholder_text("Search id, name or notes")
+            .build();
+        let query_entry = PaintQueryEntry::new();
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        hbox.pack_start(&search_entry, true, true, 0);
+        hbox.pack_start(query_entry.pwo(), true, true, 0);
+        let order_selector = PaintOrderSelector::new();
+        hbox.pack_start(order_selector.pwo(), false, false, 0);
+        let vbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        vbox.pack_start(&hbox, false, false, 0);
+        vbox.pack_start(&paned, true, true, 0);
         let sp = Rc::new(SeriesPage {
-            paned,
+            vbox,
+            search_entry,
+            query_entry,
+            order_selector,
             paint_series,
+            attributes: self.attributes.clone(),
+            properties: self.properties.clone(),
             hue_wheel,
             list_view,
             callbacks: RefCell::new(HashMap::new()),
@@ -148,6 +180,12 @@ impl SeriesPageBuilder {
                 .borrow_mut()
                 .insert((*name).to_string(), vec![]);
         }
+        let sp_c = Rc::clone(&sp);
+        sp.search_entry.connect_search_changed(move |_| sp_c.apply_filter());
+        let sp_c = Rc::clone(&sp);
+        sp.query_entry.connect_query_changed(move |_| sp_c.apply_filter());
+        let sp_c = Rc::clone(&sp);
+        sp.order_selector.connect_order_changed(move |_| sp_c.apply_filter());
 
         sp
     }
@@ -188,12 +226,41 @@ impl SeriesPage {
     fn set_target_colour(&self, rgb: Option<&impl GdkColour>) {
         self.hue_wheel.set_target_colour(rgb);
     }
+
+    /// Show the paints that match both the search text and the filter query (best search
+    /// match first unless a colour order is selected).
+    fn apply_filter(&self) {
+        self.hue_wheel.remove_all();
+        self.list_view.remove_all();
+        let query = self.query_entry.query();
+        let mut paints: Vec<Rc<SeriesPaint>> =
+            search_series(&self.search_entry.get_text(), [&self.paint_series])
+                .into_iter()
+                .map(|(paint, _)| paint)
+                .filter(|paint| query.matches(paint.as_ref()))
+                .collect();
+        let order = self.order_selector.order();
+        if order != PaintOrder::Id {
+            sort_paints(&mut paints, order);
+        }
+        for paint in paints {
+            self.hue_wheel.add_item(paint.coloured_shape());
+            let custom_property_types = self.paint_series.custom_property_types();
+            self.list_view.add_row(&paint.row_with_custom(
+                &self.attributes,
+                &self.properties,
+                custom_property_types,
+                custom_property_types.len(),
+            ));
+        }
+    }
 }
 
 #[derive(PWO, Wrapper)]
 struct SeriesBinder {
     notebook: gtk::Notebook,
     pages: RefCell<Vec<(Rc<SeriesPage>, PathBuf)>>,
+    index: RefCell<PaintIndex>,
     series_page_builder: SeriesPageBuilder,
     menu_items: Vec<(&'static str, MenuItemSpec, u64)>,
     target_colour: RefCell<Option<HCV>>,
@@ -226,6 +293,7 @@ impl SeriesBinder {
         let binder = Rc::new(Self {
             notebook,
             pages,
+            index: RefCell::new(PaintIndex::new()),
             series_page_builder,
             menu_items: menu_items.to_vec(),
             target_colour: RefCell::new(None),
@@ -264,6 +332,14 @@ impl SeriesBinder {
         }
     }
 
+    fn series_paints(&self) -> Vec<Rc<SeriesPaint>> {
+        let mut v = vec![];
+        for (page, _) in self.pages.borrow().iter() {
+            v.extend(page.paint_series.paints().cloned());
+        }
+        v
+    }
+
     fn connect_popup_menu_item<F: Fn(Rc<SeriesPaint>) + 'static>(&self, name: &str, callback: F) {
         self.callbacks
             .borrow_mut()
@@ -301,6 +377,7 @@ impl SeriesBinder {
 
     fn remove_series_at_index(&self, index: usize) {
         let page = self.pages.borrow_mut().remove(index);
+        self.index.borrow_mut().remove_series(page.0.series_id());
         let page_num = self.notebook.page_num(page.0.pwo());
         self.notebook.remove_page(page_num);
     }
@@ -376,6 +453,7 @@ impl RcSeriesBinder for Rc<SeriesBinder> {
                     new_series.series_id().proprietor(),
                 );
                 let menu_label = gtk::Label::new(Some(l_text.as_str()));
+                self.index.borrow_mut().add_series(&new_series);
                 let new_page = self.series_page_builder.build(new_series);
                 if let Some(colour) = self.target_colour.borrow().as_ref() {
                     new_page.set_target_colour(Some(colour));
@@ -407,27 +485,7 @@ impl RcSeriesBinder for Rc<SeriesBinder> {
             let msg = format!("{}: is already loaded", path.to_string_lossy());
             return Err(crate::Error::DuplicateFile(msg));
         }
-        let mut file = File::open(path)?;
-        let new_series_spec = match SeriesPaintSeriesSpec::read(&mut file) {
-            Ok(spec) => spec,
-            Err(_) => {
-                let mut file = File::open(path)?;
-                match SeriesPaintSeriesSpec00::<f64>::read(&mut file) {
-                    Ok(spec) => spec,
-                    Err(err) => match &err {
-                        apaint::Error::SerdeJsonError(_) => {
-                            let mut file = File::open(path)?;
-                            if let Ok(series) = read_legacy_paint_series_spec(&mut file) {
-                                series
-                            } else {
-                                return Err(crate::Error::APaintError(err));
-                            }
-                        }
-                        _ => return Err(crate::Error::APaintError(err)),
-                    },
-                }
-            }
-        };
+        let new_series_spec = read_paint_series_spec_file(path)?;
         self.add_series((&new_series_spec).into(), path)?;
         Ok(())
     }
@@ -455,12 +513,7 @@ impl SeriesPaintFinder for SeriesBinder {
                 Err(_) => Err(apaint::Error::UnknownSeries(series_id.clone())),
             }
         } else {
-            for page in self.pages.borrow().iter() {
-                if let Some(paint) = page.0.paint_series.find(paint_id) {
-                    return Ok(Rc::clone(paint));
-                }
-            }
-            Err(apaint::Error::NotFound(paint_id.to_string()))
+            self.index.borrow().find(paint_id)
         }
     }
 }
@@ -512,6 +565,11 @@ impl PaintSeriesManager {
     pub fn update_popup_condns(&self, changed_condns: MaskedCondns) {
         self.binder.update_popup_condns(changed_condns);
     }
+
+    /// All of the paints in all of the loaded series.
+    pub fn series_paints(&self) -> Vec<Rc<SeriesPaint>> {
+        self.binder.series_paints()
+    }
 }
 
 impl SeriesPaintFinder for PaintSeriesManager {
@@ -141,8 +141,22 @@ impl PaintDisplayBuilder {
         vbox.pack_start(cads.pwo(), true, true, 0);
 
         for property_type in self.properties.iter() {
-            let value = paint.property(*property_type).full();
-            let label = gtk::LabelBuilder::new().label(value).build();
+            if let Some(property) = paint.recorded_property(*property_type) {
+                let label = gtk::LabelBuilder::new().label(property.full()).build();
+                label.set_widget_colour(&hcv);
+                vbox.pack_start(&label, false, false, 0);
+            }
+        }
+        if !paint.pigments().is_empty() {
+            let codes: Vec<&str> = paint.pigments().iter().map(|p| p.code()).collect();
+            let text = format!("Pigments: {}", codes.join(", "));
+            let label = gtk::LabelBuilder::new().label(&text).build();
+            label.set_widget_colour(&hcv);
+            vbox.pack_start(&label, false, false, 0);
+        }
+        for value in paint.custom_values() {
+            let text = format!("{}: {}", value.name(), value.full());
+            let label = gtk::LabelBuilder::new().label(&text).build();
             label.set_widget_colour(&hcv);
             vbox.pack_start(&label, false, false, 0);
         }
@@ -11,14 +11,17 @@ use pw_gtk_ext::{
 
 use colour_math::ScalarAttribute;
 
+use apaint::pigments::{parse_pigments, Pigment};
+use apaint::properties::{custom::CustomPropertyType, Property};
 use apaint::series::BasicPaintSpec;
 use apaint::BasicPaintIfce;
 use colour_math_gtk::colour_edit::{ColourEditor, ColourEditorBuilder};
 use pw_gtk_ext::sav_state::ConditionalWidgetGroupsBuilder;
 
 use crate::properties::{
-    FinishEntry, FluorescenceEntry, GranulationEntry, LightFastnessEntry, MetallicnessEntry,
-    OpacityEntry, PermanenceEntry, PropertyType, StainingEntry, TransparencyEntry,
+    CustomPropertyEntry, FinishEntry, FluorescenceEntry, GranulationEntry, LightFastnessEntry,
+    MetallicnessEntry, OpacityEntry, PermanenceEntry, PropertyType, StainingEntry,
+    TransparencyEntry,
 };
 
 type AddCallback = Box<dyn Fn(&BasicPaintSpec)>;
@@ -31,6 +34,8 @@ pub struct BasicPaintSpecEditor {
     id_entry: gtk::Entry,
     name_entry: gtk::Entry,
     notes_entry: gtk::Entry,
+    pigments_entry: gtk::Entry,
+    single_pigment_button: gtk::CheckButton,
     colour_editor: Rc<ColourEditor<u16>>,
     finish_entry: Rc<FinishEntry>,
     transparency_entry: Rc<TransparencyEntry>,
@@ -41,6 +46,10 @@ pub struct BasicPaintSpecEditor {
     lightfastness_entry: Rc<LightFastnessEntry>,
     granulation_entry: Rc<GranulationEntry>,
     staining_entry: Rc<StainingEntry>,
+    property_rows: Vec<(PropertyType, gtk::Label, gtk::Widget)>,
+    property_types: RefCell<Vec<PropertyType>>,
+    custom_grid: gtk::Grid,
+    custom_entries: RefCell<Vec<Rc<CustomPropertyEntry>>>,
     buttons: ConditionalWidgetGroups<gtk::Button>,
     current_spec: RefCell<Option<BasicPaintSpec>>,
     add_callbacks: RefCell<Vec<AddCallback>>,
@@ -69,6 +78,9 @@ impl BasicPaintSpecEditor {
     const SAV_LIGHTFASTNESS_CHANGED: u64 = SAV_NEXT_CONDN << 16;
     const SAV_GRANULATION_CHANGED: u64 = SAV_NEXT_CONDN << 17;
     const SAV_STAINING_CHANGED: u64 = SAV_NEXT_CONDN << 18;
+    const SAV_CUSTOM_CHANGED: u64 = SAV_NEXT_CONDN << 19;
+    const SAV_PIGMENTS_CHANGED: u64 = SAV_NEXT_CONDN << 20;
+    const SAV_PIGMENTS_READY: u64 = SAV_NEXT_CONDN << 21;
 
     const CHANGED_MASK: u64 = Self::SAV_ID_CHANGED
         + Self::SAV_NAME_CHANGED
@@ -78,7 +90,13 @@ impl BasicPaintSpecEditor {
         + Self::SAV_PERMANENCE_CHANGED
         + Self::SAV_TRANSPARENCY_CHANGED
         + Self::SAV_FLUORESCENCE_CHANGED
-        + Self::SAV_METALLICNESS_CHANGED;
+        + Self::SAV_METALLICNESS_CHANGED
+        + Self::SAV_OPACITY_CHANGED
+        + Self::SAV_LIGHTFASTNESS_CHANGED
+        + Self::SAV_GRANULATION_CHANGED
+        + Self::SAV_STAINING_CHANGED
+        + Self::SAV_CUSTOM_CHANGED
+        + Self::SAV_PIGMENTS_CHANGED;
 
     pub fn new(attributes: &[ScalarAttribute], properties: &[PropertyType]) -> Rc<Self> {
         let vbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
@@ -105,6 +123,23 @@ impl BasicPaintSpecEditor {
         grid.attach(&label, 0, 2, 1, 1);
         let notes_entry = gtk::EntryBuilder::new().hexpand(true).build();
         grid.attach(&notes_entry, 1, 2, 1, 1);
+        let label = gtk::LabelBuilder::new()
+            .label("Pigments:")
+            .halign(gtk::Align::End)
+            .build();
+        grid.attach(&label, 0, 3, 1, 1);
+        let pigments_entry = gtk::EntryBuilder::new()
+            .hexpand(true)
+            .tooltip_text("Colour Index codes e.g. \"PB 15:3, PG 7\"")
+            .build();
+        grid.attach(&pigments_entry, 1, 3, 1, 1);
+        // NB: inconsistent means that the number of pigments decides
+        let single_pigment_button = gtk::CheckButtonBuilder::new()
+            .label("Single pigment")
+            .inconsistent(true)
+            .tooltip_text("Override whether the paint counts as a single pigment paint")
+            .build();
+        grid.attach(&single_pigment_button, 1, 4, 1, 1);
 
         let finish_entry = FinishEntry::new();
         let transparency_entry = TransparencyEntry::new();
@@ -116,48 +151,58 @@ impl BasicPaintSpecEditor {
         let opacity_entry = OpacityEntry::new();
         let staining_entry = StainingEntry::new();
 
-        let mut row: i32 = 3;
+        let mut property_rows = vec![];
+        let mut row: i32 = 5;
         for property in properties.iter() {
-            match *property {
-                PropertyType::Finish => {
-                    grid.attach(&finish_entry.prompt(gtk::Align::End), 0, row, 1, 1);
-                    grid.attach(finish_entry.pwo(), 1, row, 1, 1);
-                }
-                PropertyType::Transparency => {
-                    grid.attach(&transparency_entry.prompt(gtk::Align::End), 0, row, 1, 1);
-                    grid.attach(transparency_entry.pwo(), 1, row, 1, 1);
-                }
-                PropertyType::Opacity => {
-                    grid.attach(&opacity_entry.prompt(gtk::Align::End), 0, row, 1, 1);
-                    grid.attach(opacity_entry.pwo(), 1, row, 1, 1);
-                }
-                PropertyType::Permanence => {
-                    grid.attach(&permanence_entry.prompt(gtk::Align::End), 0, row, 1, 1);
-                    grid.attach(permanence_entry.pwo(), 1, row, 1, 1);
-                }
-                PropertyType::Fluorescence => {
-                    grid.attach(&fluorescence_entry.prompt(gtk::Align::End), 0, row, 1, 1);
-                    grid.attach(fluorescence_entry.pwo(), 1, row, 1, 1);
-                }
-                PropertyType::Metallicness => {
-                    grid.attach(&metallicness_entry.prompt(gtk::Align::End), 0, row, 1, 1);
-                    grid.attach(metallicness_entry.pwo(), 1, row, 1, 1);
-                }
-                PropertyType::Granulation => {
-                    grid.attach(&granulation_entry.prompt(gtk::Align::End), 0, row, 1, 1);
-                    grid.attach(granulation_entry.pwo(), 1, row, 1, 1);
-                }
-                PropertyType::LightFastness => {
-                    grid.attach(&lightfastness_entry.prompt(gtk::Align::End), 0, row, 1, 1);
-                    grid.attach(lightfastness_entry.pwo(), 1, row, 1, 1);
-                }
-                PropertyType::Staining => {
-                    grid.attach(&staining_entry.prompt(gtk::Align::End), 0, row, 1, 1);
-                    grid.attach(staining_entry.pwo(), 1, row, 1, 1);
-                }
+            let (prompt, widget) = match *property {
+                PropertyType::Finish => (
+                    finish_entry.prompt(gtk::Align::End),
+                    finish_entry.pwo().clone().upcast::<gtk::Widget>(),
+                ),
+                PropertyType::Transparency => (
+                    transparency_entry.prompt(gtk::Align::End),
+                    transparency_entry.pwo().clone().upcast::<gtk::Widget>(),
+                ),
+                PropertyType::Opacity => (
+                    opacity_entry.prompt(gtk::Align::End),
+                    opacity_entry.pwo().clone().upcast::<gtk::Widget>(),
+                ),
+                PropertyType::Permanence => (
+                    permanence_entry.prompt(gtk::Align::End),
+                    permanence_entry.pwo().clone().upcast::<gtk::Widget>(),
+                ),
+                PropertyType::Fluorescence => (
+                    fluorescence_entry.prompt(gtk::Align::End),
+                    fluorescence_entry.pwo().clone().upcast::<gtk::Widget>(),
+                ),
+                PropertyType::Metallicness => (
+                    metallicness_entry.prompt(gtk::Align::End),
+                    metallicness_entry.pwo().clone().upcast::<gtk::Widget>(),
+                ),
+                PropertyType::Granulation => (
+                    granulation_entry.prompt(gtk::Align::End),
+                    granulation_entry.pwo().clone().upcast::<gtk::Widget>(),
+                ),
+                PropertyType::LightFastness => (
+                    lightfastness_entry.prompt(gtk::Align::End),
+                    lightfastness_entry.pwo().clone().upcast::<gtk::Widget>(),
+                ),
+                PropertyType::Staining => (
+                    staining_entry.prompt(gtk::Align::End),
+                    staining_entry.pwo().clone().upcast::<gtk::Widget>(),
+                ),
             };
+            // NB: visibility is controlled by the properties the series records
+            prompt.set_no_show_all(true);
+            widget.set_no_show_all(true);
+            grid.attach(&prompt, 0, row, 1, 1);
+            grid.attach(&widget, 1, row, 1, 1);
+            property_rows.push((*property, prompt, widget));
             row += 1;
         }
+        // NB: populated when the series' custom properties are known
+        let custom_grid = gtk::GridBuilder::new().hexpand(true).build();
+        vbox.pack_start(&custom_grid, false, false, 0);
 
         let add_btn = gtk::ButtonBuilder::new().label("Add").build();
         let accept_btn = gtk::ButtonBuilder::new().label("Accept").build();
@@ -171,13 +216,20 @@ impl BasicPaintSpecEditor {
             .widget_states_controlled(WidgetStatesControlled::Sensitivity)
             .build::<gtk::Button>();
         buttons
-            .add_widget("add", &add_btn, Self::SAV_ID_READY + Self::SAV_NOT_EDITING)
+            .add_widget(
+                "add",
+                &add_btn,
+                Self::SAV_ID_READY + Self::SAV_PIGMENTS_READY + Self::SAV_NOT_EDITING,
+            )
             .expect("Duplicate key or button: add");
         buttons
             .add_widget(
                 "accept",
                 &accept_btn,
-                Self::SAV_ID_READY + Self::SAV_HAS_CHANGES + Self::SAV_EDITING,
+                Self::SAV_ID_READY
+                    + Self::SAV_PIGMENTS_READY
+                    + Self::SAV_HAS_CHANGES
+                    + Self::SAV_EDITING,
             )
             .expect("Duplicate key or button: accept");
         buttons
@@ -188,6 +240,8 @@ impl BasicPaintSpecEditor {
             id_entry,
             name_entry,
             notes_entry,
+            pigments_entry,
+            single_pigment_button,
             colour_editor,
             finish_entry,
             transparency_entry,
@@ -198,6 +252,10 @@ impl BasicPaintSpecEditor {
             lightfastness_entry,
             granulation_entry,
             staining_entry,
+            property_rows,
+            property_types: RefCell::new(vec![]),
+            custom_grid,
+            custom_entries: RefCell::new(vec![]),
             buttons,
             current_spec: RefCell::new(None),
             add_callbacks: RefCell::new(Vec::new()),
@@ -271,6 +329,16 @@ impl BasicPaintSpecEditor {
             bpe_c.inform_changed();
         });
 
+        let bpe_c = Rc::clone(&bpe);
+        bpe.pigments_entry
+            .connect_changed(move |_| bpe_c.update_pigments_condns());
+
+        let bpe_c = Rc::clone(&bpe);
+        bpe.single_pigment_button.connect_toggled(move |button| {
+            button.set_inconsistent(false);
+            bpe_c.update_pigments_condns();
+        });
+
         let bpe_c = Rc::clone(&bpe);
         bpe.colour_editor.connect_changed(move |hcv| {
             let mut masked_condns = MaskedCondns {
@@ -294,7 +362,7 @@ impl BasicPaintSpecEditor {
                 mask: Self::SAV_FINISH_CHANGED,
             };
             if let Some(spec) = bpe_c.current_spec.borrow().as_ref() {
-                if spec.finish != entry.value() {
+                if spec.finish() != entry.value() {
                     masked_condns.condns += Self::SAV_FINISH_CHANGED;
                 }
             }
@@ -310,7 +378,7 @@ impl BasicPaintSpecEditor {
                 mask: Self::SAV_PERMANENCE_CHANGED,
             };
             if let Some(spec) = bpe_c.current_spec.borrow().as_ref() {
-                if spec.permanence != entry.value() {
+                if spec.permanence() != entry.value() {
                     masked_condns.condns += Self::SAV_PERMANENCE_CHANGED;
                 }
             }
@@ -326,7 +394,7 @@ impl BasicPaintSpecEditor {
                 mask: Self::SAV_TRANSPARENCY_CHANGED,
             };
             if let Some(spec) = bpe_c.current_spec.borrow().as_ref() {
-                if spec.transparency != entry.value() {
+                if spec.transparency() != entry.value() {
                     masked_condns.condns += Self::SAV_TRANSPARENCY_CHANGED;
                 }
             }
@@ -342,7 +410,7 @@ impl BasicPaintSpecEditor {
                 mask: Self::SAV_FLUORESCENCE_CHANGED,
             };
             if let Some(spec) = bpe_c.current_spec.borrow().as_ref() {
-                if spec.fluorescence != entry.value() {
+                if spec.fluorescence() != entry.value() {
                     masked_condns.condns += Self::SAV_FLUORESCENCE_CHANGED;
                 }
             }
@@ -358,7 +426,7 @@ impl BasicPaintSpecEditor {
                 mask: Self::SAV_METALLICNESS_CHANGED,
             };
             if let Some(spec) = bpe_c.current_spec.borrow().as_ref() {
-                if spec.metallicness != entry.value() {
+                if spec.metallicness() != entry.value() {
                     masked_condns.condns += Self::SAV_METALLICNESS_CHANGED;
                 }
             }
@@ -431,8 +499,11 @@ impl BasicPaintSpecEditor {
             bpe_c.inform_changed();
         });
 
+        bpe.set_property_types(&PropertyType::BASIC);
+
         // NB: needed to correctly set the current state
         bpe.set_current_spec(None);
+        bpe.update_pigments_condns();
         bpe.update_has_changes();
 
         bpe
@@ -453,17 +524,159 @@ impl BasicPaintSpecEditor {
         self.buttons.update_condns(masked_condns);
     }
 
+    fn single_pigment(&self) -> Option<bool> {
+        if self.single_pigment_button.get_inconsistent() {
+            None
+        } else {
+            Some(self.single_pigment_button.get_active())
+        }
+    }
+
+    fn set_single_pigment(&self, single_pigment: Option<bool>) {
+        // NB: the toggled handler clears the inconsistent state so set it afterwards
+        self.single_pigment_button
+            .set_active(single_pigment.unwrap_or(false));
+        self.single_pigment_button
+            .set_inconsistent(single_pigment.is_none());
+        self.update_pigments_condns();
+    }
+
+    /// Check that the pigments entry holds a list of valid pigment codes (naming the first
+    /// invalid one in the entry's icon tooltip) and whether they or the single pigment
+    /// override differ from the spec being edited.
+    fn update_pigments_condns(&self) {
+        let mut masked_condns = MaskedCondns {
+            condns: 0,
+            mask: Self::SAV_PIGMENTS_READY + Self::SAV_PIGMENTS_CHANGED,
+        };
+        match parse_pigments(&self.pigments_entry.get_text()) {
+            Ok(pigments) => {
+                masked_condns.condns += Self::SAV_PIGMENTS_READY;
+                self.pigments_entry
+                    .set_icon_from_icon_name(gtk::EntryIconPosition::Secondary, None);
+                if let Some(spec) = self.current_spec.borrow().as_ref() {
+                    if spec.pigments != pigments || spec.single_pigment != self.single_pigment() {
+                        masked_condns.condns += Self::SAV_PIGMENTS_CHANGED;
+                    }
+                }
+            }
+            Err(err) => {
+                self.pigments_entry.set_icon_from_icon_name(
+                    gtk::EntryIconPosition::Secondary,
+                    Some("dialog-error"),
+                );
+                self.pigments_entry.set_icon_tooltip_text(
+                    gtk::EntryIconPosition::Secondary,
+                    Some(&err.to_string()),
+                );
+            }
+        }
+        self.buttons.update_condns(masked_condns);
+        self.update_has_changes();
+        self.inform_changed();
+    }
+
+    fn entry_value(&self, property_type: PropertyType) -> Property {
+        match property_type {
+            PropertyType::Finish => Property::Finish(self.finish_entry.value()),
+            PropertyType::Transparency => Property::Transparency(self.transparency_entry.value()),
+            PropertyType::Permanence => Property::Permanence(self.permanence_entry.value()),
+            PropertyType::Fluorescence => Property::Fluorescence(self.fluorescence_entry.value()),
+            PropertyType::Metallicness => Property::Metallicness(self.metallicness_entry.value()),
+            PropertyType::LightFastness => {
+                Property::LightFastness(self.lightfastness_entry.value())
+            }
+            PropertyType::Opacity => Property::Opacity(self.opacity_entry.value()),
+            PropertyType::Staining => Property::Staining(self.staining_entry.value()),
+            PropertyType::Granulation => Property::Granulation(self.granulation_entry.value()),
+        }
+    }
+
+    fn set_entry_value(&self, property: Property) {
+        match property {
+            Property::Finish(value) => self.finish_entry.set_value(Some(value)),
+            Property::Transparency(value) => self.transparency_entry.set_value(Some(value)),
+            Property::Permanence(value) => self.permanence_entry.set_value(Some(value)),
+            Property::Fluorescence(value) => self.fluorescence_entry.set_value(Some(value)),
+            Property::Metallicness(value) => self.metallicness_entry.set_value(Some(value)),
+            Property::LightFastness(value) => self.lightfastness_entry.set_value(Some(value)),
+            Property::Opacity(value) => self.opacity_entry.set_value(Some(value)),
+            Property::Staining(value) => self.staining_entry.set_value(Some(value)),
+            Property::Granulation(value) => self.granulation_entry.set_value(Some(value)),
+        }
+    }
+
+    fn update_custom_changed(&self) {
+        let mut masked_condns = MaskedCondns {
+            condns: 0,
+            mask: Self::SAV_CUSTOM_CHANGED,
+        };
+        if let Some(spec) = self.current_spec.borrow().as_ref() {
+            for entry in self.custom_entries.borrow().iter() {
+                let value = entry.value();
+                if spec.custom_property(value.name()) != Some(value.full()) {
+                    masked_condns.condns = Self::SAV_CUSTOM_CHANGED;
+                    break;
+                }
+            }
+        }
+        self.buttons.update_condns(masked_condns);
+    }
+
+    /// Provide entries for the custom properties that the series declares.
+    pub fn set_custom_property_types(self: &Rc<Self>, property_types: &[CustomPropertyType]) {
+        for child in self.custom_grid.get_children() {
+            self.custom_grid.remove(&child);
+        }
+        let mut custom_entries = vec![];
+        for (row, property_type) in property_types.iter().enumerate() {
+            let entry = CustomPropertyEntry::new(&Rc::new(property_type.clone()));
+            let bpe_c = Rc::clone(self);
+            entry.connect_changed(move |_| {
+                bpe_c.update_custom_changed();
+                bpe_c.update_has_changes();
+                bpe_c.inform_changed();
+            });
+            self.custom_grid
+                .attach(&entry.prompt(gtk::Align::End), 0, row as i32, 1, 1);
+            self.custom_grid.attach(entry.pwo(), 1, row as i32, 1, 1);
+            custom_entries.push(entry);
+        }
+        self.custom_grid.show_all();
+        *self.custom_entries.borrow_mut() = custom_entries;
+        self.update_custom_changed();
+        self.update_has_changes();
+    }
+
+    /// Show (and record in specifications) only the properties that the series declares.
+    pub fn set_property_types(&self, property_types: &[PropertyType]) {
+        for (property_type, prompt, widget) in self.property_rows.iter() {
+            let visible = property_types.contains(property_type);
+            prompt.set_visible(visible);
+            widget.set_visible(visible);
+        }
+        *self.property_types.borrow_mut() = property_types.to_vec();
+    }
+
     fn spec_from_entries(&self) -> BasicPaintSpec {
         let id = self.id_entry.get_text();
         let hcv = self.colour_editor.hcv();
         let mut paint_spec = BasicPaintSpec::new(&hcv, &id);
         paint_spec.name = self.name_entry.get_text().to_string();
         paint_spec.notes = self.notes_entry.get_text().to_string();
-        paint_spec.finish = self.finish_entry.value();
-        paint_spec.permanence = self.permanence_entry.value();
-        paint_spec.transparency = self.transparency_entry.value();
-        paint_spec.fluorescence = self.fluorescence_entry.value();
-        paint_spec.metallicness = self.metallicness_entry.value();
+        // NB: adding and accepting are only possible when the codes are valid
+        paint_spec.pigments = parse_pigments(&self.pigments_entry.get_text()).unwrap_or_default();
+        paint_spec.single_pigment = self.single_pigment();
+        for property_type in self.property_types.borrow().iter() {
+            paint_spec.set_property(self.entry_value(*property_type));
+        }
+        for entry in self.custom_entries.borrow().iter() {
+            let value = entry.value();
+            paint_spec.set_custom_property(value.name(), value.full());
+        }
+        if let Some(current_spec) = self.current_spec.borrow().as_ref() {
+            paint_spec.equivalents = current_spec.equivalents.clone();
+        }
         paint_spec
     }
 
@@ -492,7 +705,8 @@ impl BasicPaintSpecEditor {
 
     fn process_reset_action(&self) {
         if self.buttons.current_condns() & Self::SAV_HAS_CHANGES != 0 {
-            if self.buttons.current_condns() & Self::SAV_ID_READY != 0 {
+            let savable = Self::SAV_ID_READY + Self::SAV_PIGMENTS_READY;
+            if self.buttons.current_condns() & savable == savable {
                 let buttons = &[
                     ("Cancel", gtk::ResponseType::Other(0)),
                     ("Save and Continue", gtk::ResponseType::Other(1)),
@@ -525,6 +739,8 @@ impl BasicPaintSpecEditor {
         self.id_entry.set_text("");
         self.name_entry.set_text("");
         self.notes_entry.set_text("");
+        self.pigments_entry.set_text("");
+        self.set_single_pigment(None);
         // NB: do not reset properties
         self.colour_editor.reset();
         self.update_has_changes();
@@ -550,12 +766,16 @@ impl BasicPaintSpecEditor {
         self.id_entry.set_text(&spec.id);
         self.name_entry.set_text(&spec.name);
         self.notes_entry.set_text(&spec.notes);
+        let codes: Vec<&str> = spec.pigments.iter().map(Pigment::code).collect();
+        self.pigments_entry.set_text(&codes.join(", "));
+        self.set_single_pigment(spec.single_pigment);
         self.colour_editor.set_colour(&spec.colour);
-        self.finish_entry.set_value(Some(spec.finish));
-        self.permanence_entry.set_value(Some(spec.permanence));
-        self.transparency_entry.set_value(Some(spec.transparency));
-        self.fluorescence_entry.set_value(Some(spec.fluorescence));
-        self.metallicness_entry.set_value(Some(spec.metallicness));
+        for property in spec.properties.iter() {
+            self.set_entry_value(*property);
+        }
+        for entry in self.custom_entries.borrow().iter() {
+            entry.set_value(spec.custom_property(entry.property_type().name()));
+        }
         self.update_has_changes();
     }
 
@@ -595,11 +815,20 @@ impl BasicPaintSpecEditor {
         self.id_entry.set_text("");
         self.name_entry.set_text("");
         self.notes_entry.set_text("");
+        self.pigments_entry.set_text("");
+        self.set_single_pigment(None);
         self.finish_entry.set_value(None);
         self.permanence_entry.set_value(None);
         self.transparency_entry.set_value(None);
         self.fluorescence_entry.set_value(None);
         self.metallicness_entry.set_value(None);
+        self.opacity_entry.set_value(None);
+        self.lightfastness_entry.set_value(None);
+        self.granulation_entry.set_value(None);
+        self.staining_entry.set_value(None);
+        for entry in self.custom_entries.borrow().iter() {
+            entry.set_value(None);
+        }
         self.colour_editor.reset();
         self.update_has_changes();
     }